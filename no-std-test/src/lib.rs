@@ -0,0 +1,66 @@
+//! Uses the `taken` macros from a `#![no_std]` crate. Build it on its own so that the `std`
+//! feature of `taken` isn't enabled by the rest of the workspace:
+//!
+//! ```text
+//! cargo build -p taken-no-std-test
+//! cargo build -p taken-no-std-test --features alloc
+//! ```
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(test)]
+extern crate std;
+
+#[macro_use]
+extern crate taken;
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Buf {
+    pub len: usize,
+}
+
+pub fn take_core(mut a: u32, b: Buf, c: Option<u32>, d: Buf) -> Option<(u32, usize)> {
+    take!(&mut a, =b as copy, ?c, hold d, mut b);
+    *a += c;
+    b.len += 1;
+    take!(b <- Buf::default() as old);
+    Some((*a, copy.len + old.len))
+}
+
+pub fn lend(mut buf: Buf) -> (usize, Buf) {
+    let len = with_taken!(buf as lent => {
+        lent.len += 1;
+        lent.len
+    });
+    mut_scope!(buf {
+        buf.len *= 2;
+    });
+    (len, buf)
+}
+
+#[cfg(feature = "alloc")]
+pub fn allocating(v: alloc::vec::Vec<u32>) -> usize {
+    share!(v => shared);
+    take_arc_clone!(shared as again);
+    leak!(v as leaked);
+    leaked.len() + again.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_std() {
+        assert_eq!(take_core(1, Buf { len: 2 }, Some(3), Buf::default()), Some((4, 5)));
+        assert_eq!(take_core(1, Buf::default(), None, Buf::default()), None);
+        assert_eq!(lend(Buf { len: 1 }), (2, Buf { len: 4 }));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn no_std_alloc() {
+        assert_eq!(allocating(std::vec![1, 2]), 4);
+    }
+}
@@ -6,6 +6,43 @@
 //! All of them expand into some sort of `let v = v;`. See the [`take!`](macro.take.html)
 //! for more details and possible use cases.
 //!
+//! ### Features
+//! No features are enabled by default, so the crate is `#![no_std]` and only needs `core` unless
+//! `std` or `alloc` is enabled.
+//!
+//! - `std`: [`take_sender!`](macro.take_sender.html),
+//!   [`SendOnce`](struct.SendOnce.html), [`lazy_take_sync!`](macro.lazy_take_sync.html),
+//!   [`take_into_mutex!`](macro.take_into_mutex.html), [`take_shared!`](macro.take_shared.html)
+//!   and [`take_shared_rw!`](macro.take_shared_rw.html).
+//!   Enables `alloc`.
+//! - `alloc`: the macros that allocate: [`share!`](macro.share.html), [`leak!`](macro.leak.html),
+//!   [`leak_box!`](macro.leak_box.html), [`move_into!`](macro.move_into.html),
+//!   [`take_box!`](macro.take_box.html), [`take_arc_clone!`](macro.take_arc_clone.html),
+//!   [`take_rc_clone!`](macro.take_rc_clone.html), [`take_arc_weak!`](macro.take_arc_weak.html),
+//!   [`take_weak!`](macro.take_weak.html), [`take_rc_weak!`](macro.take_rc_weak.html) and
+//!   [`take_weak_rc!`](macro.take_weak_rc.html).
+//! - `macros`: procedural macros, such as the `#[capture(...)]` and `#[captures(...)]` closure
+//!   attributes, `take_fn!`, `take_all_fields!`, [`capture_set!`](macro.capture_set.html),
+//!   `#[taken(...)]` for functions and the derives.
+//! - `derive`: only the derives, `#[derive(TakeBuilder)]` and `#[derive(TakeFields)]`.
+//! - `tokio`: [`spawn_task!`](macro.spawn_task.html), which spawns onto the tokio runtime. The
+//!   expansion names `::tokio`, so the crate using it has to depend on tokio itself.
+//! - `wasm`: [`js_closure!`](macro.js_closure.html), which builds `wasm_bindgen` closures. The
+//!   expansion names `::wasm_bindgen`, so the crate using it has to depend on wasm-bindgen itself.
+//!   Enables `alloc`.
+//! - `strict-clone-semantics`: `=x` on an `Arc` or `Rc` warns that it is deprecated, pointing at
+//!   `arc_clone x` to clone the pointer or `=*x` to clone the value. `=x` keeps cloning the
+//!   pointer, because a feature enabled by any crate in the build applies to all of them. Enables
+//!   `alloc`.
+//! - `trace`: every `take!` entry prints the name, sigil, type and size of what it took to
+//!   stderr. Useful for finding out what a closure or future is holding on to. Enables `std`.
+//! - `once-cell`: re-exports the `once_cell` crate as `taken::once_cell`, whose `OnceCell`s work
+//!   with [`take_once!`](macro.take_once.html) like the ones in `std`. Enables `std`.
+//!
+//! ### Minimum Rust Version
+//! Rust 1.80, for the `core::cell::LazyCell` and `std::sync::LazyLock` behind
+//! [`lazy_take!`](macro.lazy_take.html) and [`lazy_take_sync!`](macro.lazy_take_sync.html).
+//!
 //! ### Special Thanks
 //! This crate was created through the community efforts at [/r/rust]. Special thanks to:
 //!
@@ -24,7 +61,423 @@
 //! [/u/jasonkdark]: https://www.reddit.com/r/rust/comments/7u29r3/help_me_make_the_own_macro_and_understand_its_use/dthfcnt/
 //! [/u/i_r_witty]: https://www.reddit.com/r/rust/comments/7ubwjv/announcing_the_taken_crate_with_special_thanks_to/dtjrusk/
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(any(feature = "macros", feature = "derive"))]
+extern crate taken_macros;
+
+#[cfg(feature = "macros")]
+pub use taken_macros::{capture, captures, take_all_fields, take_fn, taken};
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use taken_macros::__take_set;
+#[cfg(any(feature = "macros", feature = "derive"))]
+pub use taken_macros::{TakeBuilder, TakeFields};
+
+/// The `once_cell` crate, for cells to use with [`take_once!`](macro.take_once.html).
+#[cfg(feature = "once-cell")]
+pub extern crate once_cell;
+
+mod traits;
+pub use traits::RefCounted;
+
+/// Paths used by the macro expansions, which also have to work in `#![no_std]` and 2015 edition
+/// crates.
+#[doc(hidden)]
+pub mod __private {
+    pub use core::cell::LazyCell;
+    pub use core::clone::Clone;
+    pub use core::default::Default;
+    pub use core::iter::{IntoIterator, Iterator};
+    pub use core::mem;
+    pub use core::ops::{Fn, FnMut, FnOnce};
+    pub use core::option::Option::{self, None, Some};
+    pub use core::panic::AssertUnwindSafe;
+    pub use core::pin;
+    pub use core::ptr;
+
+    #[cfg(feature = "alloc")]
+    pub use alloc::{borrow::Cow, boxed::Box, rc::Rc, sync::Arc, vec::Vec};
+
+    #[cfg(feature = "std")]
+    pub use std::sync::{LazyLock, Mutex, RwLock};
+
+    /// Used by `take!(shrink x)`. Implemented only for `Vec<T>` and `String`.
+    #[cfg(feature = "alloc")]
+    #[diagnostic::on_unimplemented(
+        message = "`shrink` can't be used on `{Self}`",
+        label = "expected a `Vec<T>` or a `String`",
+        note = "`shrink` turns a `Vec<T>` into a `Box<[T]>` and a `String` into a `Box<str>`"
+    )]
+    pub trait Shrink {
+        type Output;
+        fn shrink(self) -> Self::Output;
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T> Shrink for alloc::vec::Vec<T> {
+        type Output = Box<[T]>;
+        #[inline(always)]
+        fn shrink(self) -> Box<[T]> {
+            self.into_boxed_slice()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl Shrink for alloc::string::String {
+        type Output = Box<str>;
+        #[inline(always)]
+        fn shrink(self) -> Box<str> {
+            self.into_boxed_str()
+        }
+    }
+
+    /// Used by `take!(unwrap x)`. Implemented for `Arc<T>` and `Rc<T>` with a `Clone` inner value.
+    #[cfg(feature = "alloc")]
+    #[diagnostic::on_unimplemented(
+        message = "`unwrap` can't be used on `{Self}`",
+        label = "expected an `Arc<T>` or an `Rc<T>` where `T: Clone`",
+        note = "`unwrap` takes the value out of an `Arc` or `Rc`, cloning it if it is shared"
+    )]
+    pub trait UnwrapOrClone {
+        type Output;
+        fn unwrap_or_clone(self) -> Self::Output;
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T: Clone> UnwrapOrClone for Arc<T> {
+        type Output = T;
+        #[inline(always)]
+        fn unwrap_or_clone(self) -> T {
+            Arc::unwrap_or_clone(self)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T: Clone> UnwrapOrClone for Rc<T> {
+        type Output = T;
+        #[inline(always)]
+        fn unwrap_or_clone(self) -> T {
+            Rc::unwrap_or_clone(self)
+        }
+    }
+
+    /// Used by `take!(weak x)`. Implemented for `Arc<T>` and `Rc<T>`.
+    #[cfg(feature = "alloc")]
+    #[diagnostic::on_unimplemented(
+        message = "`weak` can't be used on `{Self}`",
+        label = "expected an `Arc<T>` or an `Rc<T>`",
+        note = "`weak` downgrades an `Arc` or `Rc` to its `Weak`"
+    )]
+    pub trait Downgrade {
+        type Weak;
+        fn downgrade(&self) -> Self::Weak;
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T: ?Sized> Downgrade for Arc<T> {
+        type Weak = alloc::sync::Weak<T>;
+        #[inline(always)]
+        fn downgrade(&self) -> Self::Weak {
+            Arc::downgrade(self)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T: ?Sized> Downgrade for Rc<T> {
+        type Weak = alloc::rc::Weak<T>;
+        #[inline(always)]
+        fn downgrade(&self) -> Self::Weak {
+            Rc::downgrade(self)
+        }
+    }
+
+    /// Used by `take!(arc_clone x)`. Implemented for `Arc<T>` and `Rc<T>`, so the clone is always
+    /// a reference count increment.
+    #[cfg(feature = "alloc")]
+    #[diagnostic::on_unimplemented(
+        message = "`arc_clone` can't be used on `{Self}`",
+        label = "expected an `Arc<T>` or an `Rc<T>`",
+        note = "`arc_clone` only clones the pointer; use `=x` or `=*x` to clone other values"
+    )]
+    pub trait ShareClone {
+        fn share_clone(&self) -> Self;
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T: ?Sized> ShareClone for Arc<T> {
+        #[inline(always)]
+        fn share_clone(&self) -> Self {
+            Arc::clone(self)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T: ?Sized> ShareClone for Rc<T> {
+        #[inline(always)]
+        fn share_clone(&self) -> Self {
+            Rc::clone(self)
+        }
+    }
+
+    /// With `strict-clone-semantics`, `=x` calls `check` on `&CloneCheck(&x)`. Method resolution
+    /// picks the deprecated `SharedClone::check` without autoref when `x` is an `Arc` or `Rc`, and
+    /// `ValueClone::check` with autoref otherwise.
+    #[cfg(feature = "strict-clone-semantics")]
+    pub struct CloneCheck<'a, T: ?Sized>(pub &'a T);
+
+    #[cfg(feature = "strict-clone-semantics")]
+    pub trait SharedClone {
+        #[deprecated(
+            note = "`=x` on an `Arc` or `Rc` only clones the pointer. Write `arc_clone x` for that, \
+                    or `=*x` to clone the value inside"
+        )]
+        #[inline(always)]
+        fn check(&self) {}
+    }
+    #[cfg(feature = "strict-clone-semantics")]
+    impl<T: ?Sized> SharedClone for CloneCheck<'_, Arc<T>> {}
+    #[cfg(feature = "strict-clone-semantics")]
+    impl<T: ?Sized> SharedClone for CloneCheck<'_, Rc<T>> {}
+
+    #[cfg(feature = "strict-clone-semantics")]
+    pub trait ValueClone {
+        #[inline(always)]
+        fn check(&self) {}
+    }
+    #[cfg(feature = "strict-clone-semantics")]
+    impl<T: ?Sized> ValueClone for &CloneCheck<'_, T> {}
+
+    /// Used by `=` entries through `__take_clone!`. Method call syntax keeps auto-deref, so `=x`
+    /// on a `&T` still clones the `T`, and the name can't be shadowed by an inherent `clone`.
+    pub trait TakeClone: Clone {
+        #[inline(always)]
+        fn __taken_clone(&self) -> Self {
+            Clone::clone(self)
+        }
+    }
+    impl<T: Clone> TakeClone for T {}
+
+    /// Used by `take!(send x)`.
+    #[inline(always)]
+    pub fn assert_send<T: Send + ?Sized>(_: &T) {}
+
+    /// Used by `take!(sync x)`.
+    #[inline(always)]
+    pub fn assert_sync<T: Sync + ?Sized>(_: &T) {}
+
+    /// Used by `take!(static x)`.
+    #[inline(always)]
+    pub fn assert_static<T: 'static + ?Sized>(_: &T) {}
+
+    /// Used by `take_inspect!`. Taking the inspector as a bound gives the closure its argument
+    /// type, so `|v| v.len()` needs no annotation.
+    #[inline(always)]
+    pub fn inspect<T, F: FnOnce(&T)>(value: &mut T, f: F) {
+        f(value)
+    }
+
+    /// Used by `take_inspect_mut!`.
+    #[inline(always)]
+    pub fn inspect_mut<T, F: FnOnce(&mut T)>(value: &mut T, f: F) {
+        f(value)
+    }
+
+    /// Used by `take_iter_array!`, so that the element type is known even for `[]`.
+    #[inline(always)]
+    pub fn item_of<I: Iterator>(_: &I) -> core::marker::PhantomData<I::Item> {
+        core::marker::PhantomData
+    }
+
+    /// Used by `take_iter_array!`, see `item_of`.
+    #[inline(always)]
+    pub fn items<T, const N: usize>(_: core::marker::PhantomData<T>, items: [T; N]) -> [T; N] {
+        items
+    }
+
+    /// Used by `take_enum_variant!(x as V(v) else |other| ..)`, so the closure isn't called in
+    /// place, which clippy would flag in the caller.
+    #[inline(always)]
+    pub fn call_else<T, R, F: FnOnce(T) -> R>(f: F, other: T) -> R {
+        f(other)
+    }
+
+    /// Used by `take!(!x)`, `try_take!` and `take_or_default!`, so that the methods of `Option`
+    /// and `Result` are called by path. `Missing` is what is there instead of a value.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` is not an `Option` or a `Result`",
+        label = "expected an `Option` or a `Result`"
+    )]
+    pub trait Fallible: Sized {
+        type Value;
+        type Missing;
+        #[track_caller]
+        fn unwrap(self) -> Self::Value
+        where
+            Self::Missing: core::fmt::Debug;
+        #[track_caller]
+        fn expect(self, msg: &str) -> Self::Value
+        where
+            Self::Missing: core::fmt::Debug;
+        fn unwrap_or(self, default: Self::Value) -> Self::Value;
+        fn unwrap_or_default(self) -> Self::Value
+        where
+            Self::Value: Default;
+    }
+
+    impl<T> Fallible for Option<T> {
+        type Value = T;
+        type Missing = ();
+        #[inline(always)]
+        #[track_caller]
+        fn unwrap(self) -> T {
+            Option::unwrap(self)
+        }
+        #[inline(always)]
+        #[track_caller]
+        fn expect(self, msg: &str) -> T {
+            Option::expect(self, msg)
+        }
+        #[inline(always)]
+        fn unwrap_or(self, default: T) -> T {
+            Option::unwrap_or(self, default)
+        }
+        #[inline(always)]
+        fn unwrap_or_default(self) -> T
+        where
+            T: Default,
+        {
+            Option::unwrap_or_default(self)
+        }
+    }
+
+    impl<T, E> Fallible for core::result::Result<T, E> {
+        type Value = T;
+        type Missing = E;
+        #[inline(always)]
+        #[track_caller]
+        fn unwrap(self) -> T
+        where
+            E: core::fmt::Debug,
+        {
+            core::result::Result::unwrap(self)
+        }
+        #[inline(always)]
+        #[track_caller]
+        fn expect(self, msg: &str) -> T
+        where
+            E: core::fmt::Debug,
+        {
+            core::result::Result::expect(self, msg)
+        }
+        #[inline(always)]
+        fn unwrap_or(self, default: T) -> T {
+            core::result::Result::unwrap_or(self, default)
+        }
+        #[inline(always)]
+        fn unwrap_or_default(self) -> T
+        where
+            T: Default,
+        {
+            core::result::Result::unwrap_or_default(self)
+        }
+    }
+
+    /// Used by `try_take!(x or_else f)`. The closure takes no argument for an `Option` and the
+    /// error for a `Result`, which the impls give it as a bound, so `|e| ..` needs no annotation.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` is not an `Option` or a `Result`, or `or_else` got the wrong closure",
+        label = "expected an `Option` or a `Result`"
+    )]
+    pub trait UnwrapOrElse<F>: Fallible {
+        fn unwrap_or_else(self, f: F) -> Self::Value;
+    }
+
+    impl<T, F: FnOnce() -> T> UnwrapOrElse<F> for Option<T> {
+        #[inline(always)]
+        fn unwrap_or_else(self, f: F) -> T {
+            Option::unwrap_or_else(self, f)
+        }
+    }
+
+    impl<T, E, F: FnOnce(E) -> T> UnwrapOrElse<F> for core::result::Result<T, E> {
+        #[inline(always)]
+        fn unwrap_or_else(self, f: F) -> T {
+            core::result::Result::unwrap_or_else(self, f)
+        }
+    }
+
+    /// Implemented only for `T` itself, so that types are compared without coercions.
+    #[diagnostic::on_unimplemented(
+        message = "expected `{T}`, found `{Self}`",
+        label = "this has type `{Self}`"
+    )]
+    pub trait SameType<T: ?Sized> {}
+    impl<T: ?Sized> SameType<T> for T {}
+
+    /// Used by `capture_assert_type!`. The type of the argument is inferred, not coerced.
+    #[inline(always)]
+    pub fn assert_type<T: ?Sized, U: SameType<T> + ?Sized>(_: &U) {}
+}
+
+/// Print what a `take!` entry bound. `value` is borrowed so that reference entries are not moved.
+#[cfg(feature = "trace")]
+#[doc(hidden)]
+pub fn __trace_take<T: ?Sized>(sigil: &str, name: &str, value: &T) {
+    eprintln!(
+        "take!({}{}{}): {} ({} bytes)",
+        sigil,
+        if sigil.is_empty() { "" } else { " " },
+        name,
+        core::any::type_name::<T>(),
+        core::mem::size_of_val(value),
+    );
+}
+
+#[cfg(feature = "trace")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __take_trace {
+    [$(#[$attr:meta])* $sigil:literal, $name:ident] => {
+        $(#[$attr])* $crate::__trace_take($sigil, stringify!($name), &$name);
+    };
+}
+
+/// Without the `trace` feature the expansion of `take!` is unchanged.
+#[cfg(not(feature = "trace"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __take_trace {
+    [$(#[$attr:meta])* $sigil:literal, $name:ident] => {};
+}
+
+/// Clone a value through `TakeClone`, so that expansions don't depend on the prelude's `Clone`.
+#[cfg(not(feature = "strict-clone-semantics"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __take_clone {
+    [$value:expr] => {{
+        #[allow(unused_imports)]
+        use $crate::__private::TakeClone as _;
+        ($value).__taken_clone()
+    }};
+}
 
+/// With `strict-clone-semantics`, cloning an `Arc` or `Rc` also warns that it is deprecated.
+#[cfg(feature = "strict-clone-semantics")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __take_clone {
+    [$value:expr] => {{
+        #[allow(unused_imports)]
+        use $crate::__private::{SharedClone as _, TakeClone as _, ValueClone as _};
+        (&$crate::__private::CloneCheck(&$value)).check();
+        ($value).__taken_clone()
+    }};
+}
 
 /// Take ownership of specific variables.
 ///
@@ -116,11 +569,14 @@
 ///
 /// ## Changing Ownership
 /// It is easy to change the mutability and take references or clones on one or more variables.
+/// Values can also be moved out from behind a `Box` (or copied out from behind a reference) with
+/// `*`.
 ///
 /// ```rust
 /// # #[macro_use] extern crate taken;
 /// # fn main() {
 /// let (a, mut b, c, d, e, f) = (1, 2, 3, 4, 5, 6);
+/// let (g, h) = (Box::new(7), Box::new(8));
 /// take!(
 ///     &a,     // let a = &a;
 ///     &mut b, // let b = &mut b;
@@ -128,10 +584,50 @@
 ///     mut d,  // let mut d = d;
 ///     =e,     // let e = e.clone();
 ///     =mut f, // let mut f = f.clone();
+///     *g,     // let g = *g;
+///     *mut h, // let mut h = *h;
 /// );
 /// # }
 /// ```
 ///
+/// `=*x` clones the value behind a smart pointer or reference instead of the pointer itself:
+/// `let x = (*x).clone();` turns an `Arc<String>` into a `String`, where `=x` would only bump the
+/// reference count and `*x` would try to move out of the `Arc`. `=mut *x` binds it mutably.
+///
+/// `arc_clone x` is the other half: it only accepts an `Arc` or an `Rc` and clones the pointer,
+/// `let x = Arc::clone(&x);`, so it's clear at the take site that nothing is deep cloned. With the
+/// `strict-clone-semantics` feature, `=x` on an `Arc` or `Rc` warns that it is deprecated in favor
+/// of one of the two.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// # #[cfg(feature = "alloc")] {
+/// use std::sync::Arc;
+///
+/// let (config, cache) = (Arc::new(String::from("config")), Arc::new(vec![1, 2]));
+/// take!(arc_clone config, arc_clone cache as shared); // let config = Arc::clone(&config); ...
+/// assert_eq!((Arc::strong_count(&config), shared.len()), (2, 2));
+/// # }
+/// # }
+/// ```
+///
+/// `rc_clone x` does the same for any type implementing [`RefCounted`](trait.RefCounted.html),
+/// which includes `Arc` and `Rc` as well as your own reference counted handles:
+/// `let x = RefCounted::ref_clone(&x);`. Use [`take_ref_counted!`](macro.take_ref_counted.html)
+/// to take several of them at once.
+///
+/// The `x.clone()` above always means `Clone::clone`: an inherent `clone` method on the type is
+/// never called, and, like every other path in the expansion, it doesn't rely on the caller's
+/// prelude, so `take!` also works under `#[no_implicit_prelude]`. It is still a method call, so
+/// `=x` on a `&T` clones the `T` rather than the reference.
+///
+/// `ref x` and `ref mut x` are another way of spelling `&x` and `&mut x`, for those who read `&x`
+/// as "`x` is a reference" rather than "take a reference to `x`".
+///
+/// Any name can be a raw identifier, as in `take!(=r#type, &mut r#move as m)`, which is how
+/// generated code takes variables named after keywords.
+///
 /// ## Changing Ownership and Renaming
 /// You can also rename one or more of the variables using `as`:
 ///
@@ -139,6 +635,7 @@
 /// # #[macro_use] extern crate taken;
 /// # fn main() {
 /// let (var_a, mut var_b, var_c, var_d, var_e, var_f) = (1, 2, 3, 4, 5, 6);
+/// let (var_g, var_h) = (Box::new(7), Box::new(8));
 /// take!(
 ///     &var_a as a,     // let a = &var_a;
 ///     &mut var_b as b, // let b = &mut var_b;
@@ -146,293 +643,9249 @@
 ///     mut var_d as d,  // let mut d = var_d;
 ///     =var_e as e,     // let e = var_e.clone();
 ///     =mut var_f as f, // let mut f = var_f.clone();
+///     *var_g as g,     // let g = *var_g;
+///     *mut var_h as h, // let mut h = *var_h;
 /// );
 /// # }
 /// ```
 ///
-/// ## Usecase: Threads
-/// Threads are another primary use case, as threads use closures. Threads in particular are always
-/// `FnOnce` and often find themselves cloning and moving specific variables.
+/// `self` can be taken as well, but since `let self = ...` isn't allowed it always needs a new
+/// name: `take!(=self as this)` is the usual `let this = self.clone();` before a closure in a
+/// method. With a `&self` receiver, `=self` clones the value behind the reference.
 ///
 /// ```rust
-/// use std::thread::spawn;
-/// use std::sync::mpsc::channel;
-/// #[macro_use] extern crate taken;
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// #[derive(Clone)]
+/// struct Job(Vec<u32>);
+///
+/// impl Job {
+///     fn runner(&self) -> impl FnOnce() -> usize {
+///         take!(=self as this); // let this = self.clone();
+///         move || this.0.len()
+///     }
+/// }
+/// assert_eq!(Job(vec![1, 2]).runner()(), 2);
+/// # }
+/// ```
+///
+/// When several variables are taken the same way, the sigil can be written once in front of a
+/// group. Groups only contain names: take variables with a different sigil separately.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (a, b, c, mut d, mut e) = (vec![1], vec![2], vec![3], 4, 5);
+/// take!(=(a, b, c), &mut (d, e)); // let a = a.clone(); ... let d = &mut d; ...
+/// *d += *e;
+/// # assert_eq!((a, b, c, *d), (vec![1], vec![2], vec![3], 9));
+/// # }
+/// ```
+///
+/// A clone can be fanned out to several names at once with `as [...]`, e.g. to hand a `Sender`
+/// to several workers. The original is left untouched.
 ///
+/// ```rust
+/// # #[macro_use] extern crate taken;
 /// # fn main() {
-/// let (send, recv) = channel();
+/// let tx = std::sync::mpsc::channel::<u32>().0;
+/// take!(=tx as [tx_a, tx_b], =mut tx as [tx_c]); // let tx_a = tx.clone(); ...
+/// # drop((tx_a, tx_b, tx_c));
+/// # }
+/// ```
+///
+/// The same variable can be cloned or borrowed by any number of entries, e.g.
+/// `take!(=x as a, =x as b)`, but it can only be moved by one. `take!(x as a, x as b)` is
+/// reported as "`x` appears twice as a move in `take!` — use `=x` to clone or restructure the
+/// capture list". This includes `Copy` values, which would otherwise be copied twice without a
+/// word. Entries that only read the variable, like method calls and patterns such as
+/// `[first, ..] = x`, aren't moves, and neither is an entry removed by `#[cfg]`.
+///
+/// ## Method Calls
+/// A method call (or chain of them) on a variable can be taken under a new name, so that a closure
+/// captures only the result instead of borrowing the whole variable. The name is mandatory and
+/// arbitrary expressions aren't allowed.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (mut name, buf) = (String::from("liam"), vec![1, 2, 3]);
+/// let greet = {
+///     take!(
+///         name.to_uppercase() as upper, // let upper = name.to_uppercase();
+///         mut buf.len() as len,         // let mut len = buf.len();
+///     );
+///     len += 1;
+///     move || format!("{} {}", upper, len)
+/// };
+/// name.push('!'); // `name` isn't borrowed by the closure
+/// assert_eq!(greet(), "LIAM 4");
+/// # }
+/// ```
+///
+/// ## Field Groups
+/// `cfg.{entries}` takes fields of `cfg` with the usual sigils and `as` names, one `let` per field:
+/// `=token as tok` is `let tok = cfg.token.clone();` and `&host` is `let host = &cfg.host;`. What
+/// happens to `cfg` itself is spelled out by how the group ends:
+///
+/// - `cfg.{host, mut port}` consumes `cfg`. Fields may be moved out, and afterwards `cfg` is
+///   shadowed by a [`NotCaptured`](struct.NotCaptured.html) marker, so that the partially moved
+///   struct can't be used by mistake.
+/// - `cfg.{=host, &port, ..}` leaves `cfg` usable. Every field has to be cloned or borrowed, and a
+///   field that would be moved is a compile error naming it.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// struct Config { host: String, port: u16, token: String, retries: u32 }
+///
+/// let cfg = Config { host: "db".into(), port: 5432, token: "secret".into(), retries: 3 };
+/// let connect = {
+///     take!(cfg.{=host, =token as tok, ..}); // let host = cfg.host.clone(); ...
+///     move || format!("{}:{}", host, tok.len())
+/// };
+/// assert_eq!(connect(), "db:6");
+///
+/// take!(cfg.{host, mut port, retries}); // let host = cfg.host; ...; let cfg = NotCaptured;
+/// port += 1;
+/// assert_eq!((host.as_str(), port, retries), ("db", 5433, 3));
+/// # }
+/// ```
+///
+/// ## Scoped Captures
+/// `take!({ entries } => expr)` takes the entries in a new scope and evaluates to `expr`, which is
+/// the only code that sees them. This keeps a closure and its capture list in one place without
+/// shadowing the originals for the rest of the function. The bindings end with the scope, so a
+/// closure that uses them has to be `move`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (names, sep) = (vec!["a", "b"], String::from(", "));
+/// let join = take!({ =names, &sep } => move || names.join(sep));
+/// assert_eq!(join(), "a, b");
+/// assert_eq!(names.len(), 2); // `names` is still the original here
+/// # }
+/// ```
+///
+/// Without a closure, `take!((entries) { ... })` is a plain block that starts with the entries and
+/// evaluates to its tail expression. `return`, `break` and `?` behave as in any other block, and
+/// a `&mut` entry ends at the closing brace.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (mut stats, snapshot) = (vec![1], vec![2, 3]);
+/// let n = take!((&mut stats, =snapshot) {
+///     stats.extend(snapshot);
+///     stats.len()
+/// });
+/// stats.push(n); // `stats` is no longer borrowed
+/// assert_eq!(stats, vec![1, 2, 3, 3]);
+/// # }
+/// ```
+///
+/// ## Weak Captures
+/// `weak x` downgrades an `Arc` or `Rc` without moving it: `let x = Arc::downgrade(&x);`, or
+/// `Rc::downgrade`, and `weak x as w` keeps `x`. It needs the `alloc` feature.
+///
+/// `take!((entries) move |args| body)` builds a `move` closure with its own capture list, and there
+/// a `weak` entry can say what the closure does when the value is gone: `weak x as w else <expr>`
+/// downgrades outside the closure and starts the body with
+/// `let Some(w) = w.upgrade() else { return <expr> };`. The `else` runs up to the next `,` and
+/// can be `return`, `return value` or just the value to return, which is what keeps a callback
+/// from extending the life of the object that owns it.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// # #[cfg(feature = "alloc")] {
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+///
+/// let (window, title) = (Rc::new(RefCell::new(Vec::new())), String::from("clicked"));
+/// let on_click = take!((weak window as this else return, =title) move |x: i32| {
+///     this.borrow_mut().push(format!("{} at {}", title, x));
+/// });
+/// let count = take!((weak window else 0) move || window.borrow().len());
+/// on_click(3);
+/// assert_eq!(count(), 1);
+/// drop(window);
+/// on_click(4); // nothing to upgrade, so it returns right away
+/// assert_eq!(count(), 0);
+/// # }
+/// # }
+/// ```
+///
+/// ## Capture Sets
+/// Callbacks that all capture the same variables can share a named list of entries defined with
+/// [`capture_set!`](macro.capture_set.html). `take!(use ui_deps; extra)` takes the entries of
+/// `ui_deps` exactly as if they were written inline, followed by the rest of the list.
+///
+/// ## Raw Pointers
+/// `&raw const x as ptr` and `&raw mut x as ptr` bind raw pointers to `x` with
+/// `ptr::addr_of!` and `ptr::addr_of_mut!`, for handing `x` to C as the `void *user_data` of a
+/// callback. No reference is created, and `x` isn't moved. The pointer always needs a new name,
+/// since shadowing `x` with a pointer to it is too easy to misuse. Using the pointer is up to the
+/// caller and needs `unsafe`.
+///
+/// The entries are spelled like the `&raw` borrows of Rust 1.82 rather than `*const x` and
+/// `*mut x`, because `*mut x` already dereferences `x` into a mutable binding. Only the spelling
+/// is borrowed: the expansion uses the macros, so it needs no newer compiler than the rest of the
+/// crate.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::os::raw::c_void;
+///
+/// extern "C" fn on_event(user_data: *mut c_void) {
+///     let count = unsafe { &mut *(user_data as *mut u32) };
+///     *count += 1;
+/// }
+///
+/// let mut count = 0u32;
 /// {
-///     let th = spawn(|| {
-///         take!(send); // let send = send;
-///         send.send("foo").unwrap();
-///     });
-///     th.join().unwrap();
+///     take!(&raw mut count as count_ptr); // let count_ptr = ptr::addr_of_mut!(count);
+///     on_event(count_ptr as *mut c_void);
 /// }
-/// println!("received: {:?}", recv.into_iter().collect::<Vec<_>>());
+/// assert_eq!(count, 1);
 /// # }
 /// ```
-#[macro_export]
-macro_rules! take {
-    // ---------------------
-    // ----- with rest -----
-    [$var:ident, $($rest:tt)*] => {
-        let $var = $var;
-        take![$($rest)*]
-    };
-    [$var:ident as $v:ident, $($rest:tt)*] => {
-        let $v = $var;
-        take![$($rest)*]
-    };
-
-    [mut $var:ident, $($rest:tt)*] => {
-        let mut $var = $var;
-        take![$($rest)*]
-    };
-    [mut $var:ident as $v:ident, $($rest:tt)*] => {
-        let mut $v = $var;
-        take![$($rest)*]
-    };
-
-    [&$var:ident, $($rest:tt)*] => {
-        let $var = &$var;
-        take![$($rest)*]
-    };
-    [&$var:ident as $v:ident, $($rest:tt)*] => {
-        let $v = &$var;
-        take![$($rest)*]
-    };
-
-    [&mut $var:ident, $($rest:tt)*] => {
-        let $var = &mut $var;
-        take![$($rest)*]
-    };
-    [&mut $var:ident as $v:ident, $($rest:tt)*] => {
-        let $v = &mut $var;
-        take![$($rest)*]
-    };
-
-    [=$var:ident, $($rest:tt)*] => {
-        let $var = $var.clone();
-        take![$($rest)*]
-    };
-    [=$var:ident as $v:ident, $($rest:tt)*] => {
-        let $v = $var.clone();
-        take![$($rest)*]
-    };
-
-    [=mut $var:ident, $($rest:tt)*] => {
-        let mut $var = $var.clone();
-        take![$($rest)*]
-    };
-    [=mut $var:ident as $v:ident, $($rest:tt)*] => {
-        let mut $v = $var.clone();
-        take![$($rest)*]
-    };
-
-
-    // ------------------------
-    // ----- without rest -----
-    [$var:ident] => {
-        let $var = $var;
-    };
-    [$var:ident as $v:ident] => {
-        let $v = $var;
-    };
-
-    [mut $var:ident] => {
-        let mut $var = $var;
-    };
-    [mut $var:ident as $v:ident] => {
-        let mut $v = $var;
-    };
-
-    [&$var:ident] => {
-        let $var = &$var;
-    };
-    [&$var:ident as $v:ident] => {
-        let $v = &$var;
-    };
-
-    [&mut $var:ident] => {
-        let $var = &mut $var;
-    };
-    [&mut $var:ident as $v:ident] => {
-        let $v = &mut $var;
-    };
-
-    [=$var:ident] => {
-        let $var = $var.clone();
-    };
-    [=$var:ident as $v:ident] => {
-        let $v = $var.clone();
-    };
-
-    [=mut $var:ident] => {
-        let mut $var = $var.clone();
-    };
-    [=mut $var:ident as $v:ident] => {
-        let mut $v = $var.clone();
-    };
-
-    // trailing comma
-    [] => {};
-}
-
-#[test]
-#[allow(unused_mut, unused_variables, unused_assignments)]
-fn sanity_syntax() {
-    let x = 1;
-    take!(x);
-    assert_eq!(x, 1);
-
-    take!(mut x);
-    x = 2;
-    assert_eq!(x, 2);
-
-    {
+///
+/// ## Reborrowing a View
+/// `&*` and `&mut *` hand out a cheaper view of a smart pointer or owned buffer, such as a `&str`
+/// of a `String` or the `&mut T` behind a `MutexGuard`. The original stays borrowed, not moved. A
+/// type can be given after the name to document (and coerce to) exactly which view is taken.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::Mutex;
+///
+/// let (s, buf, lock) = (String::from("text"), vec![1u8, 2], Mutex::new(0));
+/// let mut guard = lock.lock().unwrap();
+/// {
+///     take!(
+///         &*s as text: &str,        // let text: &str = &*s;
+///         &*buf as bytes: &[u8],    // let bytes: &[u8] = &*buf;
+///         &mut *guard as inner,     // let inner = &mut *guard;
+///     );
+///     *inner += text.len() + bytes.len();
+/// }
+/// assert_eq!(*guard, 6);
+/// # }
+/// ```
+///
+/// ## Holding and Dropping
+/// `hold` takes a value only so that it lives as long as the current scope, such as a lock guard
+/// that has to stay alive for the body of a `move` closure. No unused variable warning is emitted
+/// for it. `drop` does the opposite and drops the value right at the take site, which is how one
+/// clone of a channel `Sender` gets closed inside a worker.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::{mpsc, Mutex};
+///
+/// let lock = Mutex::new(());
+/// let (tx, rx) = mpsc::channel::<()>();
+/// let guard = lock.lock().unwrap();
+/// let work = move || {
+///     take!(
+///         hold guard, // #[allow(unused_variables)] let guard = guard;
+///         drop tx,    // ::std::mem::drop(tx);
+///     );
+///     // ... the lock is held until the end of the closure
+/// };
+/// work();
+/// assert!(lock.try_lock().is_ok());
+/// assert!(rx.recv().is_err());
+/// # }
+/// ```
+///
+/// ## Drop Order
+/// Every entry is its own `let`, so like any other locals the bindings are dropped in the reverse
+/// order of the list: after `take!(guard, logger)` the `logger` is dropped first.
+/// `take!(together: entries)` binds them all in a single `let` instead and drops them in the order
+/// they are listed, so `take!(together: guard, logger)` releases the guard while the logger is
+/// still alive. The values are still taken from first to last.
+///
+/// Any entry that binds names can be taken `together:`, with or without `as`. `drop` and `not`
+/// don't bind anything and `pin` can't be moved out of the place it pins, so they are rejected,
+/// as are field groups, tuple patterns after `as` and attributes.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::Mutex;
+///
+/// let (lock, ctx) = (Mutex::new(0), String::from("ctx"));
+/// let (guard, log) = (lock.lock().unwrap(), vec![String::new()]);
+/// take!(together: mut guard, mut log as logger, =ctx); // let (ctx, mut logger, mut guard) = ...;
+/// *guard += 1;
+/// logger.push(ctx);
+/// # }
+/// ```
+///
+/// ## Excluding Variables
+/// `not x` makes sure that the code after it doesn't use `x`, e.g. to check that a closure doesn't
+/// capture a database handle. `x` is shadowed by a [`NotCaptured`](struct.NotCaptured.html)
+/// marker, so using it is a type error naming `NotCaptured`, while the variable outside the
+/// closure isn't moved or borrowed.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (mut database, label) = (vec![1], String::from("job"));
+/// let report = {
+///     take!(=label, not database); // let database = NotCaptured;
+///     move || label.len()          // `database.len()` would fail to compile
+/// };
+/// database.push(report());
+/// assert_eq!(database, vec![1, 3]);
+/// # }
+/// ```
+///
+/// ## Making Sure a Value is Consumed
+/// `must` wraps the value in a [`Taken`](struct.Taken.html) guard, which panics in debug builds if
+/// it is dropped without being unwrapped with `Taken::into_inner`. This catches callbacks that
+/// were expected to run but never did.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let reply = String::from("done");
+/// let callback = move || {
+///     take!(must reply); // let reply = Taken::new(reply);
+///     move || reply.into_inner()
+/// };
+/// assert_eq!(callback()(), "done");
+/// # }
+/// ```
+///
+/// ## Pinning
+/// `pin x` pins the value on the stack with `core::pin::pin!`, giving a `Pin<&mut T>` as needed to
+/// poll a future in a `select!` loop. Like with `pin!` itself, the pinned binding borrows from the
+/// enclosing scope and can't be returned from it.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::future::Future;
+/// use std::pin::Pin;
+///
+/// let fut = async { 1 };
+/// take!(pin mut fut); // let mut fut = pin!(fut);
+/// let _: Pin<&mut dyn Future<Output = i32>> = fut.as_mut();
+/// # }
+/// ```
+///
+/// `pin box x` pins it on the heap with `Box::pin` instead (`let x = Box::pin(x);`), which can be
+/// moved around freely. It needs the `alloc` feature.
+///
+/// ## Shrinking
+/// `shrink x` trades a `Vec<T>` for an exactly sized `Box<[T]>` with `into_boxed_slice()`, and a
+/// `String` for a `Box<str>` with `into_boxed_str()`, so that a long-lived closure doesn't hold on
+/// to spare capacity. Other types are rejected. It needs the `alloc` feature.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// # #[cfg(feature = "alloc")] {
+/// let mut buf = Vec::with_capacity(1024);
+/// buf.extend_from_slice(b"abc");
+/// let name = String::from("worker");
+/// let describe = {
+///     take!(shrink buf, shrink name as title); // let buf = buf.into_boxed_slice(); ...
+///     move || format!("{}: {} bytes", title, buf.len())
+/// };
+/// assert_eq!(describe(), "worker: 3 bytes");
+/// # }
+/// # }
+/// ```
+///
+/// ## Unwrapping Shared Values
+/// `unwrap x` takes the value out of an `Arc` or `Rc` with `unwrap_or_clone`: it is moved out if
+/// `x` is the last strong reference and cloned otherwise, so the usual unique case costs nothing.
+/// The value has to be `Clone`. It needs the `alloc` feature.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// # #[cfg(feature = "alloc")] {
+/// use std::rc::Rc;
+/// use std::sync::Arc;
+///
+/// let (cfg, doc) = (Arc::new(String::from("verbose")), Rc::new(vec![1]));
+/// let shared = Rc::clone(&doc);
+/// take!(unwrap cfg, unwrap mut doc as document); // let cfg = Arc::unwrap_or_clone(cfg); ...
+/// document.push(2); // `doc` was shared, so `document` is a clone
+/// assert_eq!((cfg.as_str(), document, &*shared), ("verbose", vec![1, 2], &vec![1]));
+/// # }
+/// # }
+/// ```
+///
+/// ## Copy-on-Write
+/// `cow x` wraps a borrow of `x` in a `Cow`: `let x = Cow::Borrowed(&*x);`, so a `String` becomes a
+/// `Cow<str>` and a `Vec<T>` a `Cow<[T]>` while the original stays usable. `cow own x` moves it in
+/// with `Cow::Owned(x)` instead. A type can be given after the name to help inference, and the
+/// `Cow` doesn't have to be imported. It needs the `alloc` feature.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// # #[cfg(feature = "alloc")] {
+/// use std::borrow::Cow;
+///
+/// fn send<'a>(name: impl Into<Cow<'a, str>>, payload: Cow<'a, [u8]>) -> usize {
+///     name.into().len() + payload.len()
+/// }
+/// let (name, data, owned) = (String::from("liam"), vec![1, 2], String::from("neeson"));
+/// take!(
+///     cow name,                     // let name = Cow::Borrowed(&*name);
+///     cow mut data as payload,      // let mut payload = Cow::Borrowed(&*data);
+///     cow own owned: Cow<str>,      // let owned: Cow<str> = Cow::Owned(owned);
+/// );
+/// payload.to_mut().push(3); // clones `data`
+/// assert_eq!(send(name, payload) + send(owned, Cow::Borrowed(&data)), 15);
+/// assert_eq!(data, vec![1, 2]);
+/// # }
+/// # }
+/// ```
+///
+/// ## Thread Safety Guards
+/// `send <entry>` and `sync <entry>` take the entry and require the binding to be `Send` or
+/// `Sync`. When a capture list feeds `thread::spawn`, a value that can't be sent is then reported
+/// at the `take!`, naming its type, instead of at the spawn call. `static <entry>` is the other
+/// half of what `spawn` asks for: a binding that still borrows a local fails with "argument
+/// requires that `x` is borrowed for `'static`", naming the variable. They can be combined with
+/// each other and with any other sigil that ends with the name it binds.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::{Arc, Mutex};
+/// use std::thread;
+///
+/// let (job, registry, name) = (vec![1, 2], Arc::new(Mutex::new(0)), String::from("job"));
+/// let th = thread::spawn({
+///     take!(
+///         send job,                // let job = job; and `job` must be `Send`
+///         sync =registry as reg,   // let reg = registry.clone(); and `reg` must be `Sync`
+///         static send =name,       // let name = name.clone(); and `name` must be `'static + Send`
+///     );
+///     move || *reg.lock().unwrap() += job.len() + name.len()
+/// });
+/// th.join().unwrap();
+/// assert_eq!(*registry.lock().unwrap(), 5);
+/// # }
+/// ```
+///
+/// ## Unwind Safety
+/// `unwind <entry>` takes the entry and wraps the binding in `AssertUnwindSafe`, which is what
+/// `catch_unwind` asks for when a closure captures `&mut` state or a `RefCell`. It combines with
+/// any other sigil that ends with the name it binds, e.g. `unwind =shared`. The wrapper is always
+/// bound with `mut`, so `unwind mut x` isn't needed.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::panic::catch_unwind;
+///
+/// let (mut counters, log) = (vec![0, 0], vec![String::from("start")]);
+/// let result = catch_unwind({
+///     take!(
+///         unwind &mut counters, // let mut counters = AssertUnwindSafe(&mut counters);
+///         unwind =log,          // let mut log = AssertUnwindSafe(log.clone());
+///     );
+///     move || {
+///         counters[0] += 1;
+///         log.push(String::from("halfway"));
+///         if log.len() > 1 {
+///             panic!("oops");
+///         }
+///         counters[1] += 1;
+///     }
+/// });
+/// assert!(result.is_err());
+/// assert_eq!((counters, log.len()), (vec![1, 0], 1));
+/// # }
+/// ```
+///
+/// The wrapper derefs to the value, so methods, indexing and operators keep working. `.0` is needed
+/// where deref doesn't apply: to move the value out, for methods taking `self` and to pass it to a
+/// generic function, e.g. `process(&mut *counters)` or `counters.0`. In a 2021 edition `move`
+/// closure, writing `counters.0` captures just the field, which isn't unwind safe again, so prefer
+/// `*counters` inside the closure.
+///
+/// ## Size Guards
+/// `small[N] <entry>` takes the entry as usual and then fails to compile if the binding is larger
+/// than `N` bytes, naming it in the error. This is a guard rail for futures and closures that keep
+/// growing because something large was moved in instead of boxed. It does nothing at runtime.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (header, marker, buf) = (0u64, (), [0u8; 1024]);
+/// take!(
+///     small[8] header,     // let header = header; plus the check
+///     small[0] marker,
+///     small[8] &buf as b,  // a reference is small, the buffer isn't
+/// );
+/// // take!(small[64] buf); // error: `buf` is larger than the 64 bytes of `small[64]`
+/// # let _ = (header, marker, b);
+/// # }
+/// ```
+///
+/// The entry has to end with the name it binds, so `small[N]` can't be combined with `x?` or
+/// `x else ...`. The check is evaluated after the types are known, which `cargo check` skips: the
+/// error shows up with `cargo build`.
+///
+/// ## Replacing
+/// Use `<-` to take the current value out of a variable and leave a replacement behind, like
+/// `std::mem::replace`. The original variable has to be mutable and stays usable afterwards.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (mut state, mut buf) = ("running", vec![1, 2, 3]);
+/// take!(
+///     state <- "idle",                              // let state = mem::replace(&mut state, "idle");
+///     buf <- Vec::with_capacity(buf.len()) as old,  // let old = mem::replace(&mut buf, ...);
+/// );
+/// assert_eq!(state, "running");
+/// assert_eq!(old, vec![1, 2, 3]);
+/// assert!(buf.is_empty());
+/// # }
+/// ```
+///
+/// The replacement expression is evaluated _before_ the variable is borrowed, so it is allowed to
+/// refer to the variable itself (as `buf.len()` does above). Fields can be replaced as well, in
+/// which case the rename is required: `take!(self.state <- State::Idle as previous)`.
+///
+/// Because the replacement runs until the next `,` or `as <name>`, casts and closures with
+/// several arguments have to be wrapped in parentheses: `take!(x <- (y as u8))`.
+///
+/// ## Spreading Tuples
+/// `..tuple as (...)` destructures a tuple and then takes every element with its own sigil. The
+/// tuple can also be a parenthesized expression, such as a function call.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// fn resources() -> (String, Vec<u8>, u32) {
+///     (String::from("conn"), vec![1, 2], 3)
+/// }
+/// take!(..(resources()) as (conn, &cache, mut count));
+/// // let (conn, cache, count) = (resources());
+/// // let conn = conn;
+/// // let cache = &cache;
+/// // let mut count = count;
+/// count += cache.len() as u32;
+/// assert_eq!((conn.as_str(), count), ("conn", 5));
+/// # }
+/// ```
+///
+/// Elements can't be renamed, since the names in the list already name them.
+///
+/// ## Slice Patterns
+/// `[a, .., z] = v` destructures anything that can be sliced with `v[..]`: arrays, slices,
+/// `Vec`s and boxed slices. The elements are borrowed, since they can't be moved out of a `Vec`,
+/// and `mut [a, .., z] = v` borrows them mutably. A single binding can still opt out with
+/// `ref`. If the length doesn't fit the pattern it panics; use `[...] = v else <diverge>` (see
+/// below) to handle that instead.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (args, mut counts) = (vec!["run", "-v", "main.rs"], [1, 2, 3]);
+/// take!(
+///     [cmd, flags @ .., file] = args, // let [cmd, flags @ .., file] = &args[..] else { panic!() };
+///     mut [first, ref rest @ ..] = counts,
+/// );
+/// *first += rest.len() as i32;
+/// assert_eq!((*cmd, flags, *file), ("run", &["-v"][..], "main.rs"));
+/// assert_eq!(counts, [3, 2, 3]);
+/// # }
+/// ```
+///
+/// Owned arrays can be moved apart with a plain `let [a, b] = array;`.
+///
+/// ## Fallible Takes
+/// Often taking a variable really means unwrapping it. `x?` propagates errors (or `None`) and
+/// `x else <diverge>` unwraps an `Option` with `let ... else`. Other enum variant, struct or slice
+/// patterns can be given explicitly with `<pattern> = x else <diverge>`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// fn connect(
+///     conn: Result<u32, String>,
+///     cfg: Option<&str>,
+///     tokens: Vec<Option<u8>>,
+///     reply: Result<&str, ()>,
+/// ) -> Result<u32, String> {
+///     take!(
+///         conn?,                                // let conn = conn?;
+///         cfg else return Err("no cfg".into()), // let Some(cfg) = cfg else { return ... };
+///         Ok(reply) = reply else return Ok(0),  // let Ok(reply) = reply else { return Ok(0) };
+///     );
+///     let mut total = conn + cfg.len() as u32 + reply.len() as u32;
+///     for token in tokens {
+///         take!(token else continue as tok);    // let Some(tok) = token else { continue };
+///         total += tok as u32;
+///     }
+///     Ok(total)
+/// }
+/// # fn main() {
+/// assert_eq!(connect(Ok(1), Some("ab"), vec![Some(3), None], Ok("c")), Ok(7));
+/// assert_eq!(connect(Ok(1), None, vec![], Ok("c")), Err("no cfg".into()));
+/// # }
+/// ```
+///
+/// The diverging expression runs until the next `,` or `as <name>`. Patterns already name their
+/// bindings, so they can't be renamed with `as`.
+///
+/// The `?` can also be written in front, as a sigil: `?x` is the same as `x?`. `!x` unwraps the
+/// value, panicking if it is `None` or `Err`, and `!x or "message"` gives the panic a message.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (port, host, retries) = (Some(80), Ok::<_, ()>("localhost"), Some(3));
+/// take!(
+///     !port,                           // let port = port.unwrap();
+///     !host or "no host" as addr,      // let addr = host.expect("no host");
+///     !mut retries,                    // let mut retries = retries.unwrap();
+/// );
+/// retries -= 1;
+/// assert_eq!((port, addr, retries), (80, "localhost", 2));
+/// # }
+/// ```
+///
+/// ## Attributes
+/// Outer attributes can be put in front of any entry and are forwarded onto the generated
+/// statement, so `cfg` removes the entry entirely and lints can be silenced per entry.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (debug_handle, metrics) = (1, vec![2]);
+/// take!(
+///     #[allow(unused_variables)] debug_handle,
+///     #[cfg(feature = "metrics")] =metrics, // removed unless the feature is enabled
+/// );
+/// # }
+/// ```
+///
+/// ## Long Lists
+/// Lists whose entries don't contain an expression are expanded in a single pass, so the
+/// recursion depth only grows with the logarithm of the number of entries: a list of 20 mixed
+/// entries needs a `recursion_limit` of 14 and one of 320 needs 20, where each entry used to
+/// take its own level (45 and 531). This covers sigils, `mut` and the other prefix words, `as`
+/// renames, types, method calls, field groups, `!x`, `x?` and `x? else return`, spreads and
+/// `small[N]`. The following forms are expanded one entry at a time, so a list containing them
+/// still needs about one level per entry:
+///
+/// - replacements, `x <- value` and `x.field <- value`, because the value would run into `as`
+/// - `!x or "message"`, because of the message
+/// - `send`, `sync` and `unwind` in front of a sigil, such as `send =tx`
+/// - patterns, such as `[first, ..] = v`, `mut [a, b] = v` and `Some(x) = v else { .. }`
+/// - `else` followed by anything other than words, such as `x? else return Err(e)`
+///
+/// `use` and `together:` lists have their own arms and aren't affected.
+///
+/// ## Usecase: Threads
+/// Threads are another primary use case, as threads use closures. Threads in particular are always
+/// `FnOnce` and often find themselves cloning and moving specific variables.
+///
+/// ```rust
+/// use std::thread::spawn;
+/// use std::sync::mpsc::channel;
+/// #[macro_use] extern crate taken;
+///
+/// # fn main() {
+/// let (send, recv) = channel();
+/// {
+///     let th = spawn(|| {
+///         take!(send); // let send = send;
+///         send.send("foo").unwrap();
+///     });
+///     th.join().unwrap();
+/// }
+/// println!("received: {:?}", recv.into_iter().collect::<Vec<_>>());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take {
+    [$($tokens:tt)*] => {
+        $crate::__take_impl![@check ($) $($tokens)*]
+    };
+}
+
+/// The arms of [`take!`](macro.take.html) and [`own!`](macro.own.html), which both expand through it.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __take_impl {
+    // `mut` is also matched by `$var:ident`, so catch the entries that are missing their variable
+    // before the regular arms turn them into `let mut = mut;`.
+    [$(#[$attr:meta])* mut] => {
+        $crate::__take_impl![@invalid mut]
+    };
+    [$(#[$attr:meta])* mut, $($rest:tt)*] => {
+        $crate::__take_impl![@invalid mut]
+    };
+    [$(#[$attr:meta])* &mut] => {
+        $crate::__take_impl![@invalid &mut]
+    };
+    [$(#[$attr:meta])* &mut, $($rest:tt)*] => {
+        $crate::__take_impl![@invalid &mut]
+    };
+    [$(#[$attr:meta])* ref] => {
+        $crate::__take_impl![@invalid ref]
+    };
+    [$(#[$attr:meta])* ref, $($rest:tt)*] => {
+        $crate::__take_impl![@invalid ref]
+    };
+    [$(#[$attr:meta])* ref mut] => {
+        $crate::__take_impl![@invalid ref mut]
+    };
+    [$(#[$attr:meta])* ref mut, $($rest:tt)*] => {
+        $crate::__take_impl![@invalid ref mut]
+    };
+    [$(#[$attr:meta])* =mut] => {
+        $crate::__take_impl![@invalid =mut]
+    };
+    [$(#[$attr:meta])* =mut, $($rest:tt)*] => {
+        $crate::__take_impl![@invalid =mut]
+    };
+    [$(#[$attr:meta])* *mut] => {
+        $crate::__take_impl![@invalid *mut]
+    };
+    [$(#[$attr:meta])* *mut, $($rest:tt)*] => {
+        $crate::__take_impl![@invalid *mut]
+    };
+    [$(#[$attr:meta])* ?mut] => {
+        $crate::__take_impl![@invalid ?mut]
+    };
+    [$(#[$attr:meta])* ?mut, $($rest:tt)*] => {
+        $crate::__take_impl![@invalid ?mut]
+    };
+    [$(#[$attr:meta])* !mut] => {
+        $crate::__take_impl![@invalid !mut]
+    };
+    [$(#[$attr:meta])* !mut, $($rest:tt)*] => {
+        $crate::__take_impl![@invalid !mut]
+    };
+
+    // `let self = ...` isn't allowed, so `self` has to be renamed. Only the ones without `as` get
+    // here, the others are matched by the regular arms.
+    [$(#[$attr:meta])* self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self self]
+    };
+    [$(#[$attr:meta])* mut self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self mut self]
+    };
+    [$(#[$attr:meta])* &self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self &self]
+    };
+    [$(#[$attr:meta])* &mut self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self &mut self]
+    };
+    [$(#[$attr:meta])* ref self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self ref self]
+    };
+    [$(#[$attr:meta])* ref mut self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self ref mut self]
+    };
+    [$(#[$attr:meta])* =self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self =self]
+    };
+    [$(#[$attr:meta])* =mut self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self =mut self]
+    };
+    [$(#[$attr:meta])* *self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self *self]
+    };
+    [$(#[$attr:meta])* *mut self $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_self *mut self]
+    };
+
+    // a pointer shadowing the value it points to is too easy to misuse
+    [$(#[$attr:meta])* &raw const $var:ident $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_raw &raw const $var]
+    };
+    [$(#[$attr:meta])* &raw mut $var:ident $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@unnamed_raw &raw mut $var]
+    };
+    [@unnamed_raw $($entry:tt)+] => {
+        compile_error!(concat!(
+            "`", stringify!($($entry)+), "` needs a new name for the pointer, i.e. `",
+            stringify!($($entry)+), " as ptr`"
+        ));
+    };
+    [@unnamed_self $($entry:tt)+] => {
+        compile_error!(concat!(
+            "`", stringify!($($entry)+), "` needs a new name, since `self` can't be rebound: ",
+            "use `", stringify!($($entry)+), " as this`"
+        ));
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$(#[$attr:meta])* $var:ident] => {
+        $(#[$attr])* let $var = $var;
+        $crate::__take_trace!($(#[$attr])* "", $var);
+    };
+    [$(#[$attr:meta])* $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $var;
+        $crate::__take_trace!($(#[$attr])* "", $v);
+    };
+
+    [$(#[$attr:meta])* mut $var:ident] => {
+        $(#[$attr])* let mut $var = $var;
+        $crate::__take_trace!($(#[$attr])* "mut", $var);
+    };
+    [$(#[$attr:meta])* mut $var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = $var;
+        $crate::__take_trace!($(#[$attr])* "mut", $v);
+    };
+
+    [$(#[$attr:meta])* &$var:ident] => {
+        $(#[$attr])* let $var = &$var;
+        $crate::__take_trace!($(#[$attr])* "&", $var);
+    };
+    [$(#[$attr:meta])* &$var:ident as $v:ident] => {
+        $(#[$attr])* let $v = &$var;
+        $crate::__take_trace!($(#[$attr])* "&", $v);
+    };
+
+    [$(#[$attr:meta])* &mut $var:ident] => {
+        $(#[$attr])* let $var = &mut $var;
+        $crate::__take_trace!($(#[$attr])* "&mut", $var);
+    };
+    [$(#[$attr:meta])* &mut $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = &mut $var;
+        $crate::__take_trace!($(#[$attr])* "&mut", $v);
+    };
+
+    [$(#[$attr:meta])* &raw const $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::ptr::addr_of!($var);
+        $crate::__take_trace!($(#[$attr])* "&raw const", $v);
+    };
+    [$(#[$attr:meta])* &raw mut $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::ptr::addr_of_mut!($var);
+        $crate::__take_trace!($(#[$attr])* "&raw mut", $v);
+    };
+
+    [$(#[$attr:meta])* ref $var:ident] => {
+        $(#[$attr])* let $var = &$var;
+        $crate::__take_trace!($(#[$attr])* "ref", $var);
+    };
+    [$(#[$attr:meta])* ref $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = &$var;
+        $crate::__take_trace!($(#[$attr])* "ref", $v);
+    };
+
+    [$(#[$attr:meta])* ref mut $var:ident] => {
+        $(#[$attr])* let $var = &mut $var;
+        $crate::__take_trace!($(#[$attr])* "ref mut", $var);
+    };
+    [$(#[$attr:meta])* ref mut $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = &mut $var;
+        $crate::__take_trace!($(#[$attr])* "ref mut", $v);
+    };
+
+    [$(#[$attr:meta])* =$var:ident] => {
+        $(#[$attr])* let $var = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* "=", $var);
+    };
+    [$(#[$attr:meta])* =$var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* "=", $v);
+    };
+
+    [$(#[$attr:meta])* =mut $var:ident] => {
+        $(#[$attr])* let mut $var = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* "=mut", $var);
+    };
+    [$(#[$attr:meta])* =mut $var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* "=mut", $v);
+    };
+
+    [$(#[$attr:meta])* =$var:ident as [$($v:ident),* $(,)?]] => {
+        $crate::__take_impl![@fan [$(#[$attr])*] [] "=" $var [$($v),*]];
+    };
+    [$(#[$attr:meta])* =mut $var:ident as [$($v:ident),* $(,)?]] => {
+        $crate::__take_impl![@fan [$(#[$attr])*] [mut] "=mut" $var [$($v),*]];
+    };
+
+    [$(#[$attr:meta])* =*$var:ident] => {
+        $(#[$attr])* let $var = $crate::__take_clone!(*$var);
+        $crate::__take_trace!($(#[$attr])* "=*", $var);
+    };
+    [$(#[$attr:meta])* =*$var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__take_clone!(*$var);
+        $crate::__take_trace!($(#[$attr])* "=*", $v);
+    };
+
+    [$(#[$attr:meta])* =mut *$var:ident] => {
+        $(#[$attr])* let mut $var = $crate::__take_clone!(*$var);
+        $crate::__take_trace!($(#[$attr])* "=mut *", $var);
+    };
+    [$(#[$attr:meta])* =mut *$var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = $crate::__take_clone!(*$var);
+        $crate::__take_trace!($(#[$attr])* "=mut *", $v);
+    };
+
+    [$(#[$attr:meta])* *$var:ident] => {
+        $(#[$attr])* let $var = *$var;
+        $crate::__take_trace!($(#[$attr])* "*", $var);
+    };
+    [$(#[$attr:meta])* *$var:ident as $v:ident] => {
+        $(#[$attr])* let $v = *$var;
+        $crate::__take_trace!($(#[$attr])* "*", $v);
+    };
+
+    [$(#[$attr:meta])* *mut $var:ident] => {
+        $(#[$attr])* let mut $var = *$var;
+        $crate::__take_trace!($(#[$attr])* "*mut", $var);
+    };
+    [$(#[$attr:meta])* *mut $var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = *$var;
+        $crate::__take_trace!($(#[$attr])* "*mut", $v);
+    };
+
+    [$(#[$attr:meta])* &*$var:ident] => {
+        $(#[$attr])* let $var = &*$var;
+        $crate::__take_trace!($(#[$attr])* "&*", $var);
+    };
+    [$(#[$attr:meta])* &*$var:ident: $t:ty] => {
+        $(#[$attr])* let $var: $t = &*$var;
+        $crate::__take_trace!($(#[$attr])* "&*", $var);
+    };
+    [$(#[$attr:meta])* &*$var:ident as $v:ident] => {
+        $(#[$attr])* let $v = &*$var;
+        $crate::__take_trace!($(#[$attr])* "&*", $v);
+    };
+    [$(#[$attr:meta])* &*$var:ident as $v:ident: $t:ty] => {
+        $(#[$attr])* let $v: $t = &*$var;
+        $crate::__take_trace!($(#[$attr])* "&*", $v);
+    };
+
+    [$(#[$attr:meta])* &mut *$var:ident] => {
+        $(#[$attr])* let $var = &mut *$var;
+        $crate::__take_trace!($(#[$attr])* "&mut *", $var);
+    };
+    [$(#[$attr:meta])* &mut *$var:ident: $t:ty] => {
+        $(#[$attr])* let $var: $t = &mut *$var;
+        $crate::__take_trace!($(#[$attr])* "&mut *", $var);
+    };
+    [$(#[$attr:meta])* &mut *$var:ident as $v:ident] => {
+        $(#[$attr])* let $v = &mut *$var;
+        $crate::__take_trace!($(#[$attr])* "&mut *", $v);
+    };
+    [$(#[$attr:meta])* &mut *$var:ident as $v:ident: $t:ty] => {
+        $(#[$attr])* let $v: $t = &mut *$var;
+        $crate::__take_trace!($(#[$attr])* "&mut *", $v);
+    };
+
+    [$(#[$attr:meta])* hold $var:ident] => {
+        $(#[$attr])* #[allow(unused_variables)]
+        let $var = $var;
+        $crate::__take_trace!($(#[$attr])* "hold", $var);
+    };
+    [$(#[$attr:meta])* hold $var:ident as $v:ident] => {
+        $(#[$attr])* #[allow(unused_variables)]
+        let $v = $var;
+        $crate::__take_trace!($(#[$attr])* "hold", $v);
+    };
+
+    [$(#[$attr:meta])* drop $var:ident] => {
+        $crate::__take_trace!($(#[$attr])* "drop", $var);
+        $(#[$attr])* $crate::__private::mem::drop($var);
+    };
+
+    [$(#[$attr:meta])* not $var:ident] => {
+        $(#[$attr])* #[allow(unused_variables)]
+        let $var = $crate::NotCaptured;
+        $crate::__take_trace!($(#[$attr])* "not", $var);
+    };
+
+    [$(#[$attr:meta])* must $var:ident] => {
+        $(#[$attr])* let $var = $crate::Taken::new($var);
+        $crate::__take_trace!($(#[$attr])* "must", $var);
+    };
+    [$(#[$attr:meta])* must $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::Taken::new($var);
+        $crate::__take_trace!($(#[$attr])* "must", $v);
+    };
+
+    [$(#[$attr:meta])* pin $var:ident] => {
+        $(#[$attr])* let $var = $crate::__private::pin::pin!($var);
+        $crate::__take_trace!($(#[$attr])* "pin", $var);
+    };
+    [$(#[$attr:meta])* pin $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::pin::pin!($var);
+        $crate::__take_trace!($(#[$attr])* "pin", $v);
+    };
+
+    [$(#[$attr:meta])* pin mut $var:ident] => {
+        $(#[$attr])* let mut $var = $crate::__private::pin::pin!($var);
+        $crate::__take_trace!($(#[$attr])* "pin mut", $var);
+    };
+    [$(#[$attr:meta])* pin mut $var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = $crate::__private::pin::pin!($var);
+        $crate::__take_trace!($(#[$attr])* "pin mut", $v);
+    };
+
+    [$(#[$attr:meta])* pin box $var:ident] => {
+        $(#[$attr])* let $var = $crate::__private::Box::pin($var);
+        $crate::__take_trace!($(#[$attr])* "pin box", $var);
+    };
+    [$(#[$attr:meta])* pin box $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::Box::pin($var);
+        $crate::__take_trace!($(#[$attr])* "pin box", $v);
+    };
+
+    [$(#[$attr:meta])* pin box mut $var:ident] => {
+        $(#[$attr])* let mut $var = $crate::__private::Box::pin($var);
+        $crate::__take_trace!($(#[$attr])* "pin box mut", $var);
+    };
+    [$(#[$attr:meta])* pin box mut $var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = $crate::__private::Box::pin($var);
+        $crate::__take_trace!($(#[$attr])* "pin box mut", $v);
+    };
+
+    [$(#[$attr:meta])* cow $var:ident $(: $ty:ty)?] => {
+        $(#[$attr])* let $var $(: $ty)? = $crate::__private::Cow::Borrowed(&*$var);
+        $crate::__take_trace!($(#[$attr])* "cow", $var);
+    };
+    [$(#[$attr:meta])* cow $var:ident as $v:ident $(: $ty:ty)?] => {
+        $(#[$attr])* let $v $(: $ty)? = $crate::__private::Cow::Borrowed(&*$var);
+        $crate::__take_trace!($(#[$attr])* "cow", $v);
+    };
+
+    [$(#[$attr:meta])* cow mut $var:ident $(: $ty:ty)?] => {
+        $(#[$attr])* let mut $var $(: $ty)? = $crate::__private::Cow::Borrowed(&*$var);
+        $crate::__take_trace!($(#[$attr])* "cow mut", $var);
+    };
+    [$(#[$attr:meta])* cow mut $var:ident as $v:ident $(: $ty:ty)?] => {
+        $(#[$attr])* let mut $v $(: $ty)? = $crate::__private::Cow::Borrowed(&*$var);
+        $crate::__take_trace!($(#[$attr])* "cow mut", $v);
+    };
+
+    [$(#[$attr:meta])* cow own $var:ident $(: $ty:ty)?] => {
+        $(#[$attr])* let $var $(: $ty)? = $crate::__private::Cow::Owned($var);
+        $crate::__take_trace!($(#[$attr])* "cow own", $var);
+    };
+    [$(#[$attr:meta])* cow own $var:ident as $v:ident $(: $ty:ty)?] => {
+        $(#[$attr])* let $v $(: $ty)? = $crate::__private::Cow::Owned($var);
+        $crate::__take_trace!($(#[$attr])* "cow own", $v);
+    };
+
+    [$(#[$attr:meta])* cow own mut $var:ident $(: $ty:ty)?] => {
+        $(#[$attr])* let mut $var $(: $ty)? = $crate::__private::Cow::Owned($var);
+        $crate::__take_trace!($(#[$attr])* "cow own mut", $var);
+    };
+    [$(#[$attr:meta])* cow own mut $var:ident as $v:ident $(: $ty:ty)?] => {
+        $(#[$attr])* let mut $v $(: $ty)? = $crate::__private::Cow::Owned($var);
+        $crate::__take_trace!($(#[$attr])* "cow own mut", $v);
+    };
+
+    [$(#[$attr:meta])* shrink $var:ident] => {
+        $(#[$attr])* let $var = $crate::__private::Shrink::shrink($var);
+        $crate::__take_trace!($(#[$attr])* "shrink", $var);
+    };
+    [$(#[$attr:meta])* shrink $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::Shrink::shrink($var);
+        $crate::__take_trace!($(#[$attr])* "shrink", $v);
+    };
+
+    [$(#[$attr:meta])* shrink mut $var:ident] => {
+        $(#[$attr])* let mut $var = $crate::__private::Shrink::shrink($var);
+        $crate::__take_trace!($(#[$attr])* "shrink mut", $var);
+    };
+    [$(#[$attr:meta])* shrink mut $var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = $crate::__private::Shrink::shrink($var);
+        $crate::__take_trace!($(#[$attr])* "shrink mut", $v);
+    };
+
+    [$(#[$attr:meta])* unwrap $var:ident] => {
+        $(#[$attr])* let $var = $crate::__private::UnwrapOrClone::unwrap_or_clone($var);
+        $crate::__take_trace!($(#[$attr])* "unwrap", $var);
+    };
+    [$(#[$attr:meta])* unwrap $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::UnwrapOrClone::unwrap_or_clone($var);
+        $crate::__take_trace!($(#[$attr])* "unwrap", $v);
+    };
+
+    [$(#[$attr:meta])* unwrap mut $var:ident] => {
+        $(#[$attr])* let mut $var = $crate::__private::UnwrapOrClone::unwrap_or_clone($var);
+        $crate::__take_trace!($(#[$attr])* "unwrap mut", $var);
+    };
+    [$(#[$attr:meta])* unwrap mut $var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = $crate::__private::UnwrapOrClone::unwrap_or_clone($var);
+        $crate::__take_trace!($(#[$attr])* "unwrap mut", $v);
+    };
+
+    [$(#[$attr:meta])* arc_clone $var:ident] => {
+        $(#[$attr])* let $var = $crate::__private::ShareClone::share_clone(&$var);
+        $crate::__take_trace!($(#[$attr])* "arc_clone", $var);
+    };
+    [$(#[$attr:meta])* arc_clone $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::ShareClone::share_clone(&$var);
+        $crate::__take_trace!($(#[$attr])* "arc_clone", $v);
+    };
+
+    [$(#[$attr:meta])* rc_clone $var:ident] => {
+        $(#[$attr])* let $var = $crate::RefCounted::ref_clone(&$var);
+        $crate::__take_trace!($(#[$attr])* "rc_clone", $var);
+    };
+    [$(#[$attr:meta])* rc_clone $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::RefCounted::ref_clone(&$var);
+        $crate::__take_trace!($(#[$attr])* "rc_clone", $v);
+    };
+
+    [$(#[$attr:meta])* weak $var:ident] => {
+        $(#[$attr])* let $var = $crate::__private::Downgrade::downgrade(&$var);
+        $crate::__take_trace!($(#[$attr])* "weak", $var);
+    };
+    [$(#[$attr:meta])* weak $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::Downgrade::downgrade(&$var);
+        $crate::__take_trace!($(#[$attr])* "weak", $v);
+    };
+
+    // ---------------------------
+    // ----- fan-out clones -----
+    [@fan [$(#[$attr:meta])*] [$($mut:tt)?] $sigil:literal $var:ident []] => {
+        compile_error!(concat!(
+            "cloning `", stringify!($var), "` requires at least one name in `as [...]`, i.e. `",
+            "as [", stringify!($var), "_a, ", stringify!($var), "_b]`"
+        ));
+    };
+    [@fan [$(#[$attr:meta])*] [$($mut:tt)?] $sigil:literal $var:ident [$v:ident]] => {
+        $(#[$attr])* let $($mut)? $v = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* $sigil, $v);
+    };
+    [@fan [$(#[$attr:meta])*] [$($mut:tt)?] $sigil:literal $var:ident [$v:ident, $($rest:ident),+]] => {
+        $(#[$attr])* let $($mut)? $v = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* $sigil, $v);
+        $crate::__take_impl![@fan [$(#[$attr])*] [$($mut)?] $sigil $var [$($rest),+]];
+    };
+
+    // -------------------
+    // ----- replace -----
+    [$(#[$attr:meta])* $var:ident <- $($rest:tt)*] => {
+        $crate::__take_impl![@replace [$(#[$attr])*] [] [$var] [$var] [] $($rest)*]
+    };
+    [$(#[$attr:meta])* mut $var:ident <- $($rest:tt)*] => {
+        $crate::__take_impl![@replace [$(#[$attr])*] [mut] [$var] [$var] [] $($rest)*]
+    };
+    [$(#[$attr:meta])* $root:ident $(. $field:ident)+ <- $($rest:tt)*] => {
+        $crate::__take_impl![@replace [$(#[$attr])*] [] [] [$root $(. $field)+] [] $($rest)*]
+    };
+    [$(#[$attr:meta])* mut $root:ident $(. $field:ident)+ <- $($rest:tt)*] => {
+        $crate::__take_impl![@replace [$(#[$attr])*] [mut] [] [$root $(. $field)+] [] $($rest)*]
+    };
+
+    // Munch the replacement expression up to the end of the entry, which is either a `,` or an
+    // `as` rename.
+    [@replace [$(#[$attr:meta])*] [$($mut:tt)*] [$($var:ident)*] [$($place:tt)+] [$($new:tt)+] as $v:ident, $($rest:tt)*] => {
+        $crate::__take_impl![@replace [$(#[$attr])*] [$($mut)*] [$v] [$($place)+] [$($new)+]];
+        $crate::__take_impl![$($rest)*]
+    };
+    [@replace [$(#[$attr:meta])*] [$($mut:tt)*] [$($var:ident)*] [$($place:tt)+] [$($new:tt)+] as $v:ident] => {
+        $crate::__take_impl![@replace [$(#[$attr])*] [$($mut)*] [$v] [$($place)+] [$($new)+]]
+    };
+    [@replace [$(#[$attr:meta])*] [$($mut:tt)*] [$($var:ident)*] [$($place:tt)+] [$($new:tt)+], $($rest:tt)*] => {
+        $crate::__take_impl![@replace [$(#[$attr])*] [$($mut)*] [$($var)*] [$($place)+] [$($new)+]];
+        $crate::__take_impl![$($rest)*]
+    };
+    [@replace [$(#[$attr:meta])*] [$($mut:tt)*] [] [$($place:tt)+] [$($new:tt)+]] => {
+        compile_error!(concat!(
+            "replacing `", stringify!($($place)+), "` requires a new name, i.e. `",
+            stringify!($($place)+), " <- ", stringify!($($new)+), " as old`"
+        ));
+    };
+    [@replace [$(#[$attr:meta])*] [$($mut:tt)*] [$var:ident] [$($place:tt)+] [$($new:tt)+]] => {
+        $(#[$attr])* let $($mut)* $var = {
+            let new = $($new)+;
+            $crate::__private::mem::replace(&mut $($place)+, new)
+        };
+        $crate::__take_trace!($(#[$attr])* "<-", $var);
+    };
+    [@replace [$(#[$attr:meta])*] [$($mut:tt)*] [$($var:ident)*] [$($place:tt)+] [$($new:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::__take_impl![@replace [$(#[$attr])*] [$($mut)*] [$($var)*] [$($place)+] [$($new)* $t] $($rest)*]
+    };
+
+    // ------------------
+    // ----- spread -----
+    [..$tuple:ident as ($($entry:tt)*), $($rest:tt)*] => {
+        $crate::__take_impl![@spread [$tuple] [] [$($entry)*] $($entry)*];
+        $crate::__take_impl![$($rest)*]
+    };
+    [..$tuple:ident as ($($entry:tt)*)] => {
+        $crate::__take_impl![@spread [$tuple] [] [$($entry)*] $($entry)*];
+    };
+    [..($($tuple:tt)*) as ($($entry:tt)*), $($rest:tt)*] => {
+        $crate::__take_impl![@spread [($($tuple)*)] [] [$($entry)*] $($entry)*];
+        $crate::__take_impl![$($rest)*]
+    };
+    [..($($tuple:tt)*) as ($($entry:tt)*)] => {
+        $crate::__take_impl![@spread [($($tuple)*)] [] [$($entry)*] $($entry)*];
+    };
+
+    // Collect the name of every element by skipping over the sigils, then destructure the tuple
+    // and apply the entries to the elements.
+    [@spread [$tuple:expr] [$($name:ident)*] [$($entry:tt)*] & $($rest:tt)*] => {
+        $crate::__take_impl![@spread [$tuple] [$($name)*] [$($entry)*] $($rest)*]
+    };
+    [@spread [$tuple:expr] [$($name:ident)*] [$($entry:tt)*] = $($rest:tt)*] => {
+        $crate::__take_impl![@spread [$tuple] [$($name)*] [$($entry)*] $($rest)*]
+    };
+    [@spread [$tuple:expr] [$($name:ident)*] [$($entry:tt)*] * $($rest:tt)*] => {
+        $crate::__take_impl![@spread [$tuple] [$($name)*] [$($entry)*] $($rest)*]
+    };
+    [@spread [$tuple:expr] [$($name:ident)*] [$($entry:tt)*] mut $($rest:tt)*] => {
+        $crate::__take_impl![@spread [$tuple] [$($name)*] [$($entry)*] $($rest)*]
+    };
+    [@spread [$tuple:expr] [$($name:ident)*] [$($entry:tt)*] $next:ident, $($rest:tt)*] => {
+        $crate::__take_impl![@spread [$tuple] [$($name)* $next] [$($entry)*] $($rest)*]
+    };
+    [@spread [$tuple:expr] [$($name:ident)*] [$($entry:tt)*] $next:ident] => {
+        $crate::__take_impl![@spread [$tuple] [$($name)* $next] [$($entry)*]]
+    };
+    [@spread [$tuple:expr] [$($name:ident)*] [$($entry:tt)*]] => {
+        #[allow(unused_mut)]
+        let ($(mut $name,)*) = $tuple;
+        $crate::__take_impl![$($entry)*];
+    };
+
+    // --------------------
+    // ----- fallible -----
+    [$(#[$attr:meta])* $var:ident?, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $var?;
+        $crate::__take_trace!($(#[$attr])* "?", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* $var:ident? as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $var?;
+        $crate::__take_trace!($(#[$attr])* "?", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* mut $var:ident?, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $var?;
+        $crate::__take_trace!($(#[$attr])* "mut?", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* mut $var:ident? as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $var?;
+        $crate::__take_trace!($(#[$attr])* "mut?", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* $var:ident?] => {
+        $(#[$attr])* let $var = $var?;
+        $crate::__take_trace!($(#[$attr])* "?", $var);
+    };
+    [$(#[$attr:meta])* $var:ident? as $v:ident] => {
+        $(#[$attr])* let $v = $var?;
+        $crate::__take_trace!($(#[$attr])* "?", $v);
+    };
+    [$(#[$attr:meta])* mut $var:ident?] => {
+        $(#[$attr])* let mut $var = $var?;
+        $crate::__take_trace!($(#[$attr])* "mut?", $var);
+    };
+    [$(#[$attr:meta])* mut $var:ident? as $v:ident] => {
+        $(#[$attr])* let mut $v = $var?;
+        $crate::__take_trace!($(#[$attr])* "mut?", $v);
+    };
+
+    [$(#[$attr:meta])* ?$var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $var?;
+        $crate::__take_trace!($(#[$attr])* "?", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* ?$var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $var?;
+        $crate::__take_trace!($(#[$attr])* "?", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* ?mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $var?;
+        $crate::__take_trace!($(#[$attr])* "?mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* ?mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $var?;
+        $crate::__take_trace!($(#[$attr])* "?mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* ?$var:ident] => {
+        $(#[$attr])* let $var = $var?;
+        $crate::__take_trace!($(#[$attr])* "?", $var);
+    };
+    [$(#[$attr:meta])* ?$var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $var?;
+        $crate::__take_trace!($(#[$attr])* "?", $v);
+    };
+    [$(#[$attr:meta])* ?mut $var:ident] => {
+        $(#[$attr])* let mut $var = $var?;
+        $crate::__take_trace!($(#[$attr])* "?mut", $var);
+    };
+    [$(#[$attr:meta])* ?mut $var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = $var?;
+        $crate::__take_trace!($(#[$attr])* "?mut", $v);
+    };
+
+    [$(#[$attr:meta])* !$var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__private::Fallible::unwrap($var);
+        $crate::__take_trace!($(#[$attr])* "!", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* !$var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::Fallible::unwrap($var);
+        $crate::__take_trace!($(#[$attr])* "!", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* !mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $crate::__private::Fallible::unwrap($var);
+        $crate::__take_trace!($(#[$attr])* "!mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* !mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $crate::__private::Fallible::unwrap($var);
+        $crate::__take_trace!($(#[$attr])* "!mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* !$var:ident] => {
+        $(#[$attr])* let $var = $crate::__private::Fallible::unwrap($var);
+        $crate::__take_trace!($(#[$attr])* "!", $var);
+    };
+    [$(#[$attr:meta])* !$var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::Fallible::unwrap($var);
+        $crate::__take_trace!($(#[$attr])* "!", $v);
+    };
+    [$(#[$attr:meta])* !mut $var:ident] => {
+        $(#[$attr])* let mut $var = $crate::__private::Fallible::unwrap($var);
+        $crate::__take_trace!($(#[$attr])* "!mut", $var);
+    };
+    [$(#[$attr:meta])* !mut $var:ident as $v:ident] => {
+        $(#[$attr])* let mut $v = $crate::__private::Fallible::unwrap($var);
+        $crate::__take_trace!($(#[$attr])* "!mut", $v);
+    };
+
+    [$(#[$attr:meta])* !$var:ident or $msg:literal, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__private::Fallible::expect($var, $msg);
+        $crate::__take_trace!($(#[$attr])* "! or", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* !$var:ident or $msg:literal as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::Fallible::expect($var, $msg);
+        $crate::__take_trace!($(#[$attr])* "! or", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* !mut $var:ident or $msg:literal, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $crate::__private::Fallible::expect($var, $msg);
+        $crate::__take_trace!($(#[$attr])* "!mut or", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* !mut $var:ident or $msg:literal as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $crate::__private::Fallible::expect($var, $msg);
+        $crate::__take_trace!($(#[$attr])* "!mut or", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* !$var:ident or $msg:literal] => {
+        $(#[$attr])* let $var = $crate::__private::Fallible::expect($var, $msg);
+        $crate::__take_trace!($(#[$attr])* "! or", $var);
+    };
+    [$(#[$attr:meta])* !$var:ident or $msg:literal as $v:ident] => {
+        $(#[$attr])* let $v = $crate::__private::Fallible::expect($var, $msg);
+        $crate::__take_trace!($(#[$attr])* "! or", $v);
+    };
+    [$(#[$attr:meta])* !mut $var:ident or $msg:literal] => {
+        $(#[$attr])* let mut $var = $crate::__private::Fallible::expect($var, $msg);
+        $crate::__take_trace!($(#[$attr])* "!mut or", $var);
+    };
+    [$(#[$attr:meta])* !mut $var:ident or $msg:literal as $v:ident] => {
+        $(#[$attr])* let mut $v = $crate::__private::Fallible::expect($var, $msg);
+        $crate::__take_trace!($(#[$attr])* "!mut or", $v);
+    };
+
+    [$(#[$attr:meta])* $var:ident else $($rest:tt)*] => {
+        $crate::__take_impl![@else [$(#[$attr])*] [] [$var] [$var] [] $($rest)*]
+    };
+    [$(#[$attr:meta])* mut $var:ident else $($rest:tt)*] => {
+        $crate::__take_impl![@else [$(#[$attr])*] [mut] [$var] [$var] [] $($rest)*]
+    };
+
+    // Munch the diverging expression up to the end of the entry, which is either a `,` or an
+    // `as` rename.
+    [@else [$(#[$attr:meta])*] [$($mut:tt)*] [$var:ident] [$src:ident] [$($else:tt)+] as $v:ident, $($rest:tt)*] => {
+        $crate::__take_impl![@else [$(#[$attr])*] [$($mut)*] [$v] [$src] [$($else)+]];
+        $crate::__take_impl![$($rest)*]
+    };
+    [@else [$(#[$attr:meta])*] [$($mut:tt)*] [$var:ident] [$src:ident] [$($else:tt)+] as $v:ident] => {
+        $crate::__take_impl![@else [$(#[$attr])*] [$($mut)*] [$v] [$src] [$($else)+]]
+    };
+    [@else [$(#[$attr:meta])*] [$($mut:tt)*] [$var:ident] [$src:ident] [$($else:tt)+], $($rest:tt)*] => {
+        $crate::__take_impl![@else [$(#[$attr])*] [$($mut)*] [$var] [$src] [$($else)+]];
+        $crate::__take_impl![$($rest)*]
+    };
+    [@else [$(#[$attr:meta])*] [$($mut:tt)*] [$var:ident] [$src:ident] [$($else:tt)+]] => {
+        $(#[$attr])* let $crate::__private::Some($($mut)* $var) = $src else { $($else)+ };
+        $crate::__take_trace!($(#[$attr])* "else", $var);
+    };
+    [@else [$(#[$attr:meta])*] [$($mut:tt)*] [$var:ident] [$src:ident] [$($else:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::__take_impl![@else [$(#[$attr])*] [$($mut)*] [$var] [$src] [$($else)* $t] $($rest)*]
+    };
+
+    [@else_pat [$(#[$attr:meta])*] [$pat:pat] [$src:ident] [$($else:tt)+], $($rest:tt)*] => {
+        $crate::__take_impl![@else_pat [$(#[$attr])*] [$pat] [$src] [$($else)+]];
+        $crate::__take_impl![$($rest)*]
+    };
+    [@else_pat [$(#[$attr:meta])*] [$pat:pat] [$src:ident] [$($else:tt)+]] => {
+        $crate::__take_trace!($(#[$attr])* "else", $src);
+        $(#[$attr])* let $pat = $src else { $($else)+ };
+    };
+    [@else_pat [$(#[$attr:meta])*] [$pat:pat] [$src:ident] [$($else:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::__take_impl![@else_pat [$(#[$attr])*] [$pat] [$src] [$($else)* $t] $($rest)*]
+    };
+
+    // Explicit patterns are restricted to enum variants, structs and slices so that a `pat`
+    // fragment never has to parse (and reject) the other entries.
+    [$(#[$attr:meta])* $($path:ident)::+ ($($fields:tt)*) = $src:ident else $($rest:tt)*] => {
+        $crate::__take_impl![@else_pat [$(#[$attr])*] [$($path)::+ ($($fields)*)] [$src] [] $($rest)*]
+    };
+    [$(#[$attr:meta])* $($path:ident)::+ {$($fields:tt)*} = $src:ident else $($rest:tt)*] => {
+        $crate::__take_impl![@else_pat [$(#[$attr])*] [$($path)::+ {$($fields)*}] [$src] [] $($rest)*]
+    };
+    [$(#[$attr:meta])* [$($elems:tt)*] = $src:ident else $($rest:tt)*] => {
+        $crate::__take_impl![@else_pat [$(#[$attr])*] [[$($elems)*]] [$src] [] $($rest)*]
+    };
+
+    // slice patterns without `else` borrow the elements, and panic if the length doesn't fit
+    [$(#[$attr:meta])* [$($elems:tt)*] = $src:ident $(, $($rest:tt)*)?] => {
+        $crate::__take_trace!($(#[$attr])* "[..] =", $src);
+        $(#[$attr])* let [$($elems)*] = &$src[..] else {
+            $crate::__take_impl![@slice_mismatch [$($elems)*] $src]
+        };
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* mut [$($elems:tt)*] = $src:ident $(, $($rest:tt)*)?] => {
+        $crate::__take_trace!($(#[$attr])* "mut [..] =", $src);
+        $(#[$attr])* let [$($elems)*] = &mut $src[..] else {
+            $crate::__take_impl![@slice_mismatch [$($elems)*] $src]
+        };
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [@slice_mismatch [$($elems:tt)*] $src:ident] => {
+        panic!(
+            concat!("`", stringify!($src), "` doesn't match `[", stringify!($($elems)*), "]`: ",
+                "it has {} elements"),
+            $src.len()
+        )
+    };
+
+    // ------------------
+    // ----- guards -----
+    // `small[N] <entry>` takes the entry and then checks the binding, which is the last name of
+    // the entry.
+    [$(#[$attr:meta])* small[$n:expr] $($rest:tt)*] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [small $n] [] [] $($rest)*]
+    };
+    [$(#[$attr:meta])* static $($rest:tt)*] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [static] [] [] $($rest)*]
+    };
+    // `send`, `sync` and `unwind` are matched at the end of the "with rest" arms, after variables with
+    // those names.
+    [@guard [$(#[$attr:meta])*] [$($check:tt)*] [$($entry:tt)+] [$last:ident] $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![$(#[$attr])* $($entry)+];
+        $crate::__take_impl![@check [$(#[$attr])*] [$($check)*] $last];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [@guard [$(#[$attr:meta])*] [$($check:tt)*] [$($entry:tt)*] [] $(, $($rest:tt)*)?] => {
+        compile_error!(concat!(
+            "`", stringify!($($check)*), "` needs an entry that ends with the name it binds, ",
+            "found `", stringify!($($entry)*), "`"
+        ));
+    };
+    [@guard [$(#[$attr:meta])*] [$($check:tt)*] [$($entry:tt)*] [$($last:ident)?] $t:ident $($rest:tt)*] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [$($check)*] [$($entry)* $t] [$t] $($rest)*]
+    };
+    [@guard [$(#[$attr:meta])*] [$($check:tt)*] [$($entry:tt)*] [$($last:ident)?] $t:tt $($rest:tt)*] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [$($check)*] [$($entry)* $t] [] $($rest)*]
+    };
+
+    // the binding is passed to a function with the bound, so the error is reported at `take!`
+    [@check [$(#[$attr:meta])*] [send] $var:ident] => {
+        $(#[$attr])* $crate::__private::assert_send(&$var);
+    };
+    [@check [$(#[$attr:meta])*] [sync] $var:ident] => {
+        $(#[$attr])* $crate::__private::assert_sync(&$var);
+    };
+    [@check [$(#[$attr:meta])*] [static] $var:ident] => {
+        $(#[$attr])* $crate::__private::assert_static(&$var);
+    };
+    // `mut`, since the wrapper needs it to deref mutably
+    [@check [$(#[$attr:meta])*] [unwind] $var:ident] => {
+        $(#[$attr])* #[allow(unused_mut)]
+        let mut $var = $crate::__private::AssertUnwindSafe($var);
+    };
+
+    // The check is an associated constant, which is evaluated once the type of the binding is
+    // known. Like other errors after monomorphization it is reported by `cargo build`, not by
+    // `cargo check`.
+    [@check [$(#[$attr:meta])*] [small $n:expr] $var:ident] => {
+        $(#[$attr])*
+        {
+            trait __TakenSize {
+                const FITS: ();
+            }
+            impl<T> __TakenSize for T {
+                const FITS: () = assert!(
+                    $crate::__private::mem::size_of::<T>() <= $n,
+                    concat!(
+                        "`", stringify!($var), "` is larger than the ", stringify!($n),
+                        " bytes of `small[", stringify!($n), "]`"
+                    )
+                );
+            }
+            fn __taken_size<T>(_: &T) {
+                let () = <T as __TakenSize>::FITS;
+            }
+            __taken_size(&$var);
+        }
+    };
+
+    // ------------------
+    // ----- groups -----
+    // `&(a, b)` applies the same sigil to every variable in the group.
+    [$(#[$attr:meta])* mut ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [mut] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* &($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [&] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* &mut ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [&mut] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* ref ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [ref] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* ref mut ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [ref mut] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* =($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [=] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* =mut ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [=mut] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* *($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [*] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* *mut ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [*mut] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* &*($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [&*] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* &mut *($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [&mut *] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* hold ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [hold] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* drop ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [drop] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* must ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [must] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* ?($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [?] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* ?mut ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [?mut] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* !($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [!] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* !mut ($($var:ident),+ $(,)?) $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@group [$(#[$attr])*] [!mut] $($var),+];
+        $crate::__take_impl![$($($rest)*)?]
+    };
+    [@group [$(#[$attr:meta])*] [$($sigil:tt)+] $var:ident $(, $rest:ident)*] => {
+        $crate::__take_impl![$(#[$attr])* $($sigil)+ $var];
+        $crate::__take_impl![@group [$(#[$attr])*] [$($sigil)+] $($rest),*];
+    };
+    [@group [$(#[$attr:meta])*] [$($sigil:tt)+]] => {};
+    [$(#[$attr:meta])* mut ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [mut] $($entry)*]
+    };
+    [$(#[$attr:meta])* &($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [&] $($entry)*]
+    };
+    [$(#[$attr:meta])* &mut ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [&mut] $($entry)*]
+    };
+    [$(#[$attr:meta])* ref ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [ref] $($entry)*]
+    };
+    [$(#[$attr:meta])* ref mut ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [ref mut] $($entry)*]
+    };
+    [$(#[$attr:meta])* =($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [=] $($entry)*]
+    };
+    [$(#[$attr:meta])* =mut ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [=mut] $($entry)*]
+    };
+    [$(#[$attr:meta])* *($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [*] $($entry)*]
+    };
+    [$(#[$attr:meta])* *mut ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [*mut] $($entry)*]
+    };
+    [$(#[$attr:meta])* &*($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [&*] $($entry)*]
+    };
+    [$(#[$attr:meta])* &mut *($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [&mut *] $($entry)*]
+    };
+    [$(#[$attr:meta])* hold ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [hold] $($entry)*]
+    };
+    [$(#[$attr:meta])* drop ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [drop] $($entry)*]
+    };
+    [$(#[$attr:meta])* must ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [must] $($entry)*]
+    };
+    [$(#[$attr:meta])* ?($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [?] $($entry)*]
+    };
+    [$(#[$attr:meta])* ?mut ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [?mut] $($entry)*]
+    };
+    [$(#[$attr:meta])* !($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [!] $($entry)*]
+    };
+    [$(#[$attr:meta])* !mut ($($entry:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_group [!mut] $($entry)*]
+    };
+    [@invalid_group [$($sigil:tt)+] $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take!` group `", stringify!($($sigil)+ ($($entry)*)), "`: a group only ",
+            "contains names and applies `", stringify!($($sigil)+), "` to all of them, take the ",
+            "others separately"
+        ));
+    };
+
+    // ------------------------
+    // ----- field groups -----
+    [$(#[$attr:meta])* $recv:ident . { $($field:tt)* } $(, $($rest:tt)*)?] => {
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [] $($field)*];
+        $($crate::__take_impl![$($rest)*];)?
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] mut $f:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let mut $v = $recv.$f;
+        $crate::__take_trace!($(#[$attr])* "mut", $v);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)* $f] $($($rest)*)?];
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] mut $f:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let mut $f = $recv.$f;
+        $crate::__take_trace!($(#[$attr])* "mut", $f);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)* $f] $($($rest)*)?];
+    };
+
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] =mut $f:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let mut $v = $crate::__take_clone!($recv.$f);
+        $crate::__take_trace!($(#[$attr])* "=mut", $v);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] =mut $f:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let mut $f = $crate::__take_clone!($recv.$f);
+        $crate::__take_trace!($(#[$attr])* "=mut", $f);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] =$f:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $v = $crate::__take_clone!($recv.$f);
+        $crate::__take_trace!($(#[$attr])* "=", $v);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] =$f:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $f = $crate::__take_clone!($recv.$f);
+        $crate::__take_trace!($(#[$attr])* "=", $f);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] &mut $f:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $v = &mut $recv.$f;
+        $crate::__take_trace!($(#[$attr])* "&mut", $v);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] &mut $f:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $f = &mut $recv.$f;
+        $crate::__take_trace!($(#[$attr])* "&mut", $f);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] &$f:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $v = &$recv.$f;
+        $crate::__take_trace!($(#[$attr])* "&", $v);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] &$f:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $f = &$recv.$f;
+        $crate::__take_trace!($(#[$attr])* "&", $f);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] ref mut $f:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $v = &mut $recv.$f;
+        $crate::__take_trace!($(#[$attr])* "ref mut", $v);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] ref mut $f:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $f = &mut $recv.$f;
+        $crate::__take_trace!($(#[$attr])* "ref mut", $f);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] ref $f:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $v = &$recv.$f;
+        $crate::__take_trace!($(#[$attr])* "ref", $v);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] ref $f:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $f = &$recv.$f;
+        $crate::__take_trace!($(#[$attr])* "ref", $f);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)*] $($($rest)*)?];
+    };
+
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] $f:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $v = $recv.$f;
+        $crate::__take_trace!($(#[$attr])* "", $v);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)* $f] $($($rest)*)?];
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] $f:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $f = $recv.$f;
+        $crate::__take_trace!($(#[$attr])* "", $f);
+        $crate::__take_impl![@fields [$(#[$attr])*] $recv [$($moved)* $f] $($($rest)*)?];
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*]] => {
+        $(#[$attr])* #[allow(unused_variables)]
+        let $recv = $crate::NotCaptured;
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [] ..] => {};
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)+] ..] => {
+        compile_error!(concat!(
+            "`", stringify!($recv), ".{..}` leaves `", stringify!($recv), "` usable, so its ",
+            "fields can only be cloned or borrowed, but `", stringify!($($moved),+), "` ",
+            "would be moved. Clone them with `=`, or remove the `..` to consume `",
+            stringify!($recv), "`"
+        ));
+    };
+    [@fields [$(#[$attr:meta])*] $recv:ident [$($moved:ident)*] $($entry:tt)+] => {
+        compile_error!(concat!(
+            "invalid field in `", stringify!($recv), ".{}`: expected a field name with an ",
+            "optional sigil and `as` name, i.e. `=token as tok`, and `..` can only come last, ",
+            "found `", stringify!($($entry)+), "`"
+        ));
+    };
+
+    // ------------------------
+    // ----- method calls -----
+    [$(#[$attr:meta])* $recv:ident $(. $seg:ident $(:: <$($gen:ty),+>)? $(($($args:tt)*))?)+ as $v:ident, $($rest:tt)*] => {
+        $crate::__take_impl![$(#[$attr])* $recv $(. $seg $(:: <$($gen),+>)? $(($($args)*))?)+ as $v];
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* $recv:ident $(. $seg:ident $(:: <$($gen:ty),+>)? $(($($args:tt)*))?)+ as $v:ident] => {
+        $(#[$attr])* let $v = $recv $(. $seg $(:: <$($gen),+>)? $(($($args)*))?)+;
+        $crate::__take_trace!($(#[$attr])* ".", $v);
+    };
+    [$(#[$attr:meta])* mut $recv:ident $(. $seg:ident $(:: <$($gen:ty),+>)? $(($($args:tt)*))?)+ as $v:ident, $($rest:tt)*] => {
+        $crate::__take_impl![$(#[$attr])* mut $recv $(. $seg $(:: <$($gen),+>)? $(($($args)*))?)+ as $v];
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* mut $recv:ident $(. $seg:ident $(:: <$($gen:ty),+>)? $(($($args:tt)*))?)+ as $v:ident] => {
+        $(#[$attr])* let mut $v = $recv $(. $seg $(:: <$($gen),+>)? $(($($args)*))?)+;
+        $crate::__take_trace!($(#[$attr])* "mut .", $v);
+    };
+
+    // --------------------
+    // ----- together -----
+    // The entries are taken in order inside the block, and bound by a single pattern that lists
+    // them in reverse. Bindings drop in the reverse order of their declaration, so the first entry
+    // is the first to be dropped.
+    [together: $($entries:tt)*] => {
+        $crate::__take_impl![@together [$($entries)*] [] [] $($entries)*]
+    };
+    [@together [$($all:tt)*] [$($rev:tt)*] [$($cur:tt)*] , $($rest:tt)*] => {
+        $crate::__take_impl![@together [$($all)*] [[$($cur)*] $($rev)*] [] $($rest)*]
+    };
+    [@together [$($all:tt)*] [$($rev:tt)*] [$($cur:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::__take_impl![@together [$($all)*] [$($rev)*] [$($cur)* $t] $($rest)*]
+    };
+    [@together [$($all:tt)*] [$($rev:tt)*] []] => {
+        $crate::__take_impl![@together_let [$($all)*] $($rev)*]
+    };
+    [@together [$($all:tt)*] [$($rev:tt)*] [$($cur:tt)+]] => {
+        $crate::__take_impl![@together_let [$($all)*] [$($cur)+] $($rev)*]
+    };
+    [@together_let [$($all:tt)*] $([$($entry:tt)+])*] => {
+        // `mut` entries are bound mutably by the pattern, so the `let`s in the block don't need it,
+        // and `hold` entries are never used
+        #[allow(unused_mut, unused_variables)]
+        let ($($crate::__take_impl![@bound pat [] $($entry)+],)*) = {
+            $crate::__take_impl![$($all)*];
+            ($($crate::__take_impl![@bound expr [] $($entry)+],)*)
+        };
+    };
+
+    // The names bound by a `together:` entry, as a pattern (`pat`) or an expression (`expr`).
+    // Invalid entries are only reported by the pattern.
+    [@bound expr [$($m:tt)?] drop $($entry:tt)*] => { () };
+    [@bound expr [$($m:tt)?] not $($entry:tt)*] => { () };
+    [@bound expr [$($m:tt)?] pin $($entry:tt)*] => { () };
+    [@bound expr [$($m:tt)?] # $($entry:tt)*] => { () };
+    [@bound pat [$($m:tt)?] drop $($entry:tt)*] => {
+        compile_error!(concat!(
+            "`drop ", stringify!($($entry)*), "` doesn't bind anything, so it can't be taken ",
+            "`together:`"
+        ))
+    };
+    [@bound pat [$($m:tt)?] not $($entry:tt)*] => {
+        compile_error!(concat!(
+            "`not ", stringify!($($entry)*), "` doesn't bind anything, so it can't be taken ",
+            "`together:`"
+        ))
+    };
+    [@bound pat [$($m:tt)?] pin $($entry:tt)*] => {
+        compile_error!(concat!(
+            "`pin ", stringify!($($entry)*), "` is pinned where it is taken, so it can't be taken ",
+            "`together:`"
+        ))
+    };
+    [@bound pat [$($m:tt)?] # $($entry:tt)*] => {
+        compile_error!("attributes can't be used on entries taken `together:`")
+    };
+    [@bound $mode:ident [$($m:tt)?] & raw $kind:ident $($entry:tt)*] => {
+        $crate::__take_impl![@bound $mode [$($m)?] $($entry)*]
+    };
+    [@bound $mode:ident [$($m:tt)?] & mut $($entry:tt)*] => {
+        $crate::__take_impl![@bound $mode [$($m)?] $($entry)*]
+    };
+    [@bound $mode:ident [$($m:tt)?] ref mut $($entry:tt)*] => {
+        $crate::__take_impl![@bound $mode [$($m)?] $($entry)*]
+    };
+    [@bound $mode:ident [$($m:tt)?] & $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] = $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] * $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] ! $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] ? $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] small [$n:expr] $($entry:tt)*] => {
+        $crate::__take_impl![@bound $mode [$($m)?] $($entry)*]
+    };
+    [@bound $mode:ident [$($m:tt)?] mut $($entry:tt)*] => { $crate::__take_impl![@bound $mode [mut] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] ref $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] cow $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] own $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] shrink $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] unwrap $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] weak $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] arc_clone $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] rc_clone $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] hold $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] must $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] send $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] sync $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] static $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] unwind $($entry:tt)*] => { $crate::__take_impl![@bound $mode [$($m)?] $($entry)*] };
+    [@bound $mode:ident [$($m:tt)?] ($($var:ident),+ $(,)?)] => {
+        $crate::__take_impl![@bound_names $mode [$($m)?] $($var)+]
+    };
+    [@bound pat [$($m:tt)?] $var:ident . { $($fields:tt)* } $($rest:tt)*] => {
+        compile_error!(concat!(
+            "`", stringify!($var), ".{ .. }` binds several names, take it before the `together:` ",
+            "entries instead"
+        ))
+    };
+    [@bound $mode:ident [$($m:tt)?] $var:ident $($rest:tt)*] => {
+        $crate::__take_impl![@bound_as $mode [$($m)?] $var $($rest)*]
+    };
+    [@bound expr [$($m:tt)?] $($entry:tt)*] => { () };
+    [@bound pat [$($m:tt)?] $($entry:tt)*] => {
+        compile_error!(concat!(
+            "`", stringify!($($entry)*), "` can't be taken `together:`, which needs entries that ",
+            "bind a name"
+        ))
+    };
+
+    // without `as` the entry binds the variable it takes
+    [@bound_as $mode:ident [$($m:tt)?] $var:ident as $v:ident $(: $($ty:tt)*)?] => {
+        $crate::__take_impl![@bound_names $mode [$($m)?] $v]
+    };
+    [@bound_as $mode:ident [$($m:tt)?] $var:ident as [$($v:ident),+ $(,)?]] => {
+        $crate::__take_impl![@bound_names $mode [$($m)?] $($v)+]
+    };
+    [@bound_as pat [$($m:tt)?] $var:ident as ($($pat:tt)*)] => {
+        compile_error!(concat!(
+            "`as (", stringify!($($pat)*), ")` can't be taken `together:`, give the tuple a single ",
+            "name instead"
+        ))
+    };
+    [@bound_as $mode:ident [$($m:tt)?] $var:ident $t:tt $($rest:tt)*] => {
+        $crate::__take_impl![@bound_as $mode [$($m)?] $var $($rest)*]
+    };
+    [@bound_as $mode:ident [$($m:tt)?] $var:ident] => {
+        $crate::__take_impl![@bound_names $mode [$($m)?] $var]
+    };
+
+    [@bound_names pat [] $v:ident] => { $v };
+    [@bound_names pat [mut] $v:ident] => { mut $v };
+    [@bound_names pat [] $($v:ident)+] => { ($($v),+) };
+    [@bound_names pat [mut] $($v:ident)+] => { ($(mut $v),+) };
+    [@bound_names expr [$($m:tt)?] $v:ident] => { $v };
+    [@bound_names expr [$($m:tt)?] $($v:ident)+] => { ($($v),+) };
+
+    // ------------------------
+    // ----- capture sets -----
+    [use $($set:ident),+ $(; $($rest:tt)*)?] => {
+        $($set!(@taken_capture_set $set);)+
+        $crate::__take_impl![$($($rest)*)?]
+    };
+
+    // ------------------
+    // ----- scoped -----
+    [{ $($entry:tt)* } => $body:expr] => {{
+        $crate::__take_impl![$($entry)*];
+        $body
+    }};
+    [($($entry:tt)*) { $($body:tt)* }] => {{
+        $crate::__take_impl![$($entry)*];
+        $($body)*
+    }};
+    [($($entry:tt)*) move $($closure:tt)+] => {
+        $crate::__take_impl![@closure [] [] [] [$($entry)*] $($closure)+]
+    };
+
+    // `@closure [entries for take!] [current entry] [upgrades] [remaining entries] closure`
+    [@closure [$($taken:tt)*] [] [$($up:tt)*] [weak $var:ident as $v:ident else $($rest:tt)*] $($closure:tt)+] => {
+        $crate::__take_impl![@weak_else [$($taken)* weak $var as $v,] [$($up)*] $v [] [$($rest)*] $($closure)+]
+    };
+    [@closure [$($taken:tt)*] [] [$($up:tt)*] [weak $var:ident else $($rest:tt)*] $($closure:tt)+] => {
+        $crate::__take_impl![@weak_else [$($taken)* weak $var,] [$($up)*] $var [] [$($rest)*] $($closure)+]
+    };
+    [@closure [$($taken:tt)*] [$($cur:tt)*] [$($up:tt)*] [, $($rest:tt)*] $($closure:tt)+] => {
+        $crate::__take_impl![@closure [$($taken)* $($cur)*,] [] [$($up)*] [$($rest)*] $($closure)+]
+    };
+    [@closure [$($taken:tt)*] [$($cur:tt)*] [$($up:tt)*] [$t:tt $($rest:tt)*] $($closure:tt)+] => {
+        $crate::__take_impl![@closure [$($taken)*] [$($cur)* $t] [$($up)*] [$($rest)*] $($closure)+]
+    };
+    [@closure [$($taken:tt)*] [$($cur:tt)*] [$($up:tt)*] [] $($closure:tt)+] => {{
+        $crate::__take_impl![$($taken)* $($cur)*];
+        $crate::__take_impl![@closure_args [$($up)*] [] $($closure)+]
+    }};
+
+    // the `else` of a `weak` entry runs up to the next `,`, and returns unless it already does
+    [@weak_else [$($taken:tt)*] [$($up:tt)*] $v:ident [$($else:tt)+] [$(, $($rest:tt)*)?] $($closure:tt)+] => {
+        $crate::__take_impl![@closure [$($taken)*] [] [$($up)* [$v $($else)+]] [$($($rest)*)?] $($closure)+]
+    };
+    [@weak_else [$($taken:tt)*] [$($up:tt)*] $v:ident [$($else:tt)*] [$t:tt $($rest:tt)*] $($closure:tt)+] => {
+        $crate::__take_impl![@weak_else [$($taken)*] [$($up)*] $v [$($else)* $t] [$($rest)*] $($closure)+]
+    };
+    [@weak_else [$($taken:tt)*] [$($up:tt)*] $v:ident [] [$($rest:tt)*] $($closure:tt)+] => {
+        compile_error!(concat!("`weak ", stringify!($v), " else` needs a value or `return`"));
+    };
+
+    [@closure_args [$($up:tt)*] [] || $($body:tt)+] => {
+        $crate::__take_impl![@closure_args [$($up)*] [] | | $($body)+]
+    };
+    [@closure_args [$($up:tt)*] [] | $($rest:tt)+] => {
+        $crate::__take_impl![@closure_args [$($up)*] [|] $($rest)+]
+    };
+    [@closure_args [$($up:tt)*] [| $($arg:tt)*] | -> $ret:ty $body:block] => {
+        move |$($arg)*| -> $ret {
+            $($crate::__take_impl![@closure_upgrade $up];)*
+            // bound first, so that temporaries in `$body` end before the upgraded values
+            let value: $ret = $body;
+            value
+        }
+    };
+    [@closure_args [$($up:tt)*] [| $($arg:tt)*] | $body:expr] => {
+        move |$($arg)*| {
+            $($crate::__take_impl![@closure_upgrade $up];)*
+            let value = $body;
+            value
+        }
+    };
+    [@closure_args [$($up:tt)*] [| $($arg:tt)*] $t:tt $($rest:tt)+] => {
+        $crate::__take_impl![@closure_args [$($up)*] [| $($arg)* $t] $($rest)+]
+    };
+    [@closure_args [$($up:tt)*] [$($arg:tt)*] $($closure:tt)*] => {
+        compile_error!(concat!(
+            "expected a closure after `move`, found `move ", stringify!($($closure)*), "`"
+        ));
+    };
+
+    [@closure_upgrade [$v:ident return $($value:tt)*]] => {
+        let $crate::__private::Some($v) = $v.upgrade() else { return $($value)* };
+    };
+    [@closure_upgrade [$v:ident $($default:tt)+]] => {
+        let $crate::__private::Some($v) = $v.upgrade() else { return $($default)+ };
+    };
+
+    // trailing comma
+    [] => {};
+
+    // -----------------
+    // ----- moves -----
+    // `take!` and `own!` come in through `@check`, which makes sure that no variable is moved by
+    // two entries before taking them. `@moves` only checks the entries, for the forms that take
+    // them in a block or a closure.
+    [@check $d:tt] => {};
+    [@check $d:tt { $($entry:tt)* } => $($body:tt)*] => {{
+        $crate::__take_impl![@moves $d $($entry)*];
+        $crate::__take_impl![{ $($entry)* } => $($body)*]
+    }};
+    [@check $d:tt ($($entry:tt)*) { $($body:tt)* }] => {{
+        $crate::__take_impl![@moves $d $($entry)*];
+        $crate::__take_impl![($($entry)*) { $($body)* }]
+    }};
+    [@check $d:tt ($($entry:tt)*) move $($closure:tt)+] => {{
+        $crate::__take_impl![@moves $d $($entry)*];
+        $crate::__take_impl![($($entry)*) move $($closure)+]
+    }};
+    [@check $d:tt together: $($entries:tt)*] => {
+        $crate::__take_impl![@moves $d $($entries)*];
+        $crate::__take_impl![together: $($entries)*]
+    };
+    [@check $d:tt use $($set:ident),+ $(; $($rest:tt)*)?] => {
+        $crate::__take_impl![@moves $d $($($rest)*)?];
+        $crate::__take_impl![use $($set),+ $(; $($rest)*)?]
+    };
+    [@moves $d:tt] => {};
+
+    // ----------------
+    // ----- flat -----
+    // Lists whose entries don't contain expressions are split on `,` in a single step and every
+    // entry is expanded on its own, so long lists don't run into the recursion limit. Each entry
+    // is matched as its sigils, its words (names and keywords), and then the pieces that can
+    // follow them, none of which starts with a name. The other lists fall through to the muncher
+    // below, one entry at a time. Coming from `@check` or `@moves`, the split entries are also
+    // checked for moves.
+    [$(@check $check:tt)? $(@moves $moves:tt)? $(
+        $(#[$attr:meta])*
+        $(& $ref:ident)? $(& ($($ref_group:tt)*))? $(&* $ref_deref:ident)?
+        $(= $clone:ident)? $(= ($($clone_group:tt)*))? $(=* $clone_deref:ident)?
+        $(* $deref:ident)? $(* ($($deref_group:tt)*))?
+        $(! $unwrap:ident)? $(! ($($unwrap_group:tt)*))?
+        $(? ($($try_group:tt)*))?
+        $(.. $spread:ident)? $(.. ($($spread_group:tt)*))?
+        $($word:ident)*
+        $([$($bracket:tt)*] $($after_bracket:ident)*)?
+        $(($($group:tt)*) $($after_group:ident)*)?
+        $({ $($block:tt)* })?
+        $(. $seg:tt $(:: <$($gen:ty),+>)? $(($($args:tt)*))? $($after_seg:ident)*)*
+        $(? $($try:ident)*)?
+        $(: $ty:ty)?
+    ),+] => {
+        $crate::__take_impl![@flat [$(check $check)? $(moves $moves)?] $([
+            $(#[$attr])*
+            $(& $ref)? $(& ($($ref_group)*))? $(&* $ref_deref)?
+            $(= $clone)? $(= ($($clone_group)*))? $(=* $clone_deref)?
+            $(* $deref)? $(* ($($deref_group)*))?
+            $(! $unwrap)? $(! ($($unwrap_group)*))?
+            $(? ($($try_group)*))?
+            $(.. $spread)? $(.. ($($spread_group)*))?
+            $($word)*
+            $([$($bracket)*] $($after_bracket)*)?
+            $(($($group)*) $($after_group)*)?
+            $({ $($block)* })?
+            $(. $seg $(:: <$($gen),+>)? $(($($args)*))? $($after_seg)*)*
+            $(? $($try)*)?
+            $(: $ty)?
+        ])+]
+    };
+
+    // A single entry is taken by the arms above.
+    [@flat [check $d:tt] [$($entry:tt)+] $([])?] => {
+        $crate::__take_impl![$($entry)+]
+    };
+    [@flat [check $d:tt] $($entry:tt)+] => {
+        $crate::__take_impl![@halves $d $($entry)+];
+        $crate::__take_impl![@flat [] $($entry)+]
+    };
+    [@flat [moves $d:tt] $($entry:tt)+] => {
+        $crate::__take_impl![@halves $d $($entry)+];
+    };
+
+    // Only the last entry may be empty, which is the trailing comma. Single entries without one
+    // reaching this point didn't match any of the arms above, except for the guards.
+    [@flat [] [$(#[$attr:meta])* send $($entry:tt)+]] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [send] [] [] $($entry)+]
+    };
+    [@flat [] [$(#[$attr:meta])* sync $($entry:tt)+]] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [sync] [] [] $($entry)+]
+    };
+    [@flat [] [$(#[$attr:meta])* unwind $($entry:tt)+]] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [unwind] [] [] $($entry)+]
+    };
+    [@flat [] [$($entry:tt)+]] => {
+        $crate::__take_impl![@error [] $($entry)+]
+    };
+    [@flat [] $([$($entry:tt)+])+ $([])?] => {
+        $($crate::__take_impl![$($entry)+];)+
+    };
+    [@flat [] $([$($entry:tt)*])+] => {
+        $crate::__take_impl![@invalid]
+    };
+
+    // The other lists are split on every `,`, a few tokens at a time, only to be checked.
+    [@check $d:tt $($tokens:tt)+] => {
+        $crate::__take_impl![@split_moves $d [] [] $($tokens)+];
+        $crate::__take_impl![$($tokens)+]
+    };
+    [@moves $d:tt $($tokens:tt)+] => {
+        $crate::__take_impl![@split_moves $d [] [] $($tokens)+];
+    };
+    [@split_moves $d:tt [$($done:tt)*] [$($cur:tt)*] , $($rest:tt)*] => {
+        $crate::__take_impl![@split_moves $d [$($done)* [$($cur)*]] [] $($rest)*]
+    };
+    [@split_moves $d:tt [$($done:tt)*] [$($cur:tt)*] $a:tt , $($rest:tt)*] => {
+        $crate::__take_impl![@split_moves $d [$($done)* [$($cur)* $a]] [] $($rest)*]
+    };
+    [@split_moves $d:tt [$($done:tt)*] [$($cur:tt)*] $a:tt $b:tt , $($rest:tt)*] => {
+        $crate::__take_impl![@split_moves $d [$($done)* [$($cur)* $a $b]] [] $($rest)*]
+    };
+    [@split_moves $d:tt [$($done:tt)*] [$($cur:tt)*] $a:tt $b:tt $c:tt , $($rest:tt)*] => {
+        $crate::__take_impl![@split_moves $d [$($done)* [$($cur)* $a $b $c]] [] $($rest)*]
+    };
+    // none of the four is a `,`, or one of the arms above would have matched
+    [@split_moves $d:tt $done:tt [$($cur:tt)*] $a:tt $b:tt $c:tt $e:tt $($rest:tt)*] => {
+        $crate::__take_impl![@split_moves $d $done [$($cur)* $a $b $c $e] $($rest)*]
+    };
+    [@split_moves $d:tt [$($done:tt)*] [$($cur:tt)*] $($rest:tt)*] => {
+        $crate::__take_impl![@halves $d $($done)* [$($cur)* $($rest)*]]
+    };
+
+    // Every entry is compared with the others by splitting the list in two halves, comparing the
+    // entries of one half with the entries of the other, and then doing the same in each half, so
+    // that the recursion only gets as deep as the number of halvings.
+    [@halves $d:tt $($entry:tt)?] => {};
+    [@halves $d:tt $($a:tt $b:tt)+] => {
+        $crate::__take_impl![@cross $d [$($a)+] [$($b)+]];
+        $crate::__take_impl![@halves $d $($a)+];
+        $crate::__take_impl![@halves $d $($b)+];
+    };
+    [@halves $d:tt $first:tt $($a:tt $b:tt)+] => {
+        $crate::__take_impl![@cross $d [$first $($a)+] [$($b)+]];
+        $crate::__take_impl![@halves $d $first $($a)+];
+        $crate::__take_impl![@halves $d $($b)+];
+    };
+    [@cross $d:tt [$([$($a:tt)*])+] $b:tt] => {
+        $($crate::__take_impl![@moved [@define $d $b] [] $($a)*];)+
+    };
+
+    // `@moved [callback] [attributes] entry` calls back with the attributes of the entry and the
+    // variable it moves out of, if it does. Entries that borrow, clone or copy the variable, or
+    // only read it like method calls, don't call back.
+    [@moved $cb:tt [$($attrs:tt)*] #[$attr:meta] $($entry:tt)*] => {
+        $crate::__take_impl![@moved $cb [$($attrs)* #[$attr]] $($entry)*]
+    };
+    [@moved $cb:tt $attrs:tt ! $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt ? $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt .. $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt small [$($n:tt)*] $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt mut $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt hold $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt drop $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt must $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt pin $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt box $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt cow own $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt shrink $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt unwrap $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt send $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt sync $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt static $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt unwind $($entry:tt)*] => { $crate::__take_impl![@moved $cb $attrs $($entry)*] };
+    [@moved $cb:tt $attrs:tt self $($entry:tt)*] => {};
+    [@moved $cb:tt $attrs:tt ($($var:ident),+ $(,)?) $($entry:tt)*] => {
+        $($crate::__take_impl![@moved $cb $attrs $var];)+
+    };
+    [@moved [$($cb:tt)*] $attrs:tt $var:ident $(as $($v:tt)+)?] => {
+        $crate::__take_impl![$($cb)* $attrs $var];
+    };
+    [@moved [$($cb:tt)*] $attrs:tt $var:ident $(as $v:ident)? : $($ty:tt)+] => {
+        $crate::__take_impl![$($cb)* $attrs $var];
+    };
+    [@moved [$($cb:tt)*] $attrs:tt $var:ident ? $($entry:tt)*] => {
+        $crate::__take_impl![$($cb)* $attrs $var];
+    };
+    [@moved [$($cb:tt)*] $attrs:tt $var:ident or $msg:literal $($entry:tt)*] => {
+        $crate::__take_impl![$($cb)* $attrs $var];
+    };
+    [@moved [$($cb:tt)*] $attrs:tt $var:ident else $($entry:tt)*] => {
+        $crate::__take_impl![$($cb)* $attrs $var];
+    };
+    [@moved $cb:tt $attrs:tt $($entry:tt)*] => {};
+
+    // Each moved variable of one half gets a local macro that only matches its name, which is
+    // called with the moved variables of the other half. The comparisons are made inside `const`
+    // items that carry the attributes of the entries, so that an entry removed by `cfg` isn't
+    // compared. `$d` is a `$`, for the matchers of that macro.
+    [@define ($d:tt) [$([$($b:tt)*])+] [] $var:ident] => {{
+        #[allow(unused_macros)]
+        macro_rules! __taken_moved {
+            ($var) => {
+                compile_error!(concat!(
+                    "`", stringify!($var), "` appears twice as a move in `take!` — use `=",
+                    stringify!($var), "` to clone or restructure the capture list"
+                ));
+            };
+            ($d other:ident) => {};
+        }
+        $($crate::__take_impl![@moved [@twice __taken_moved] [] $($b)*];)+
+    }};
+    [@define $d:tt $b:tt [$($attrs:tt)+] $var:ident] => {
+        $($attrs)+
+        const _: () = {
+            $crate::__take_impl![@define $d $b [] $var];
+        };
+    };
+    [@twice $m:ident [] $var:ident] => {
+        $m!($var);
+    };
+    [@twice $m:ident [$($attrs:tt)+] $var:ident] => {
+        $($attrs)+
+        const _: () = {
+            $m!($var);
+        };
+    };
+
+    // ---------------------
+    // ----- with rest -----
+    [$(#[$attr:meta])* $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $var;
+        $crate::__take_trace!($(#[$attr])* "", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $var;
+        $crate::__take_trace!($(#[$attr])* "", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $var;
+        $crate::__take_trace!($(#[$attr])* "mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $var;
+        $crate::__take_trace!($(#[$attr])* "mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* &$var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = &$var;
+        $crate::__take_trace!($(#[$attr])* "&", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* &$var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = &$var;
+        $crate::__take_trace!($(#[$attr])* "&", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* &mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = &mut $var;
+        $crate::__take_trace!($(#[$attr])* "&mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* &mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = &mut $var;
+        $crate::__take_trace!($(#[$attr])* "&mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* &raw const $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::ptr::addr_of!($var);
+        $crate::__take_trace!($(#[$attr])* "&raw const", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* &raw mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::ptr::addr_of_mut!($var);
+        $crate::__take_trace!($(#[$attr])* "&raw mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* ref $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = &$var;
+        $crate::__take_trace!($(#[$attr])* "ref", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* ref $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = &$var;
+        $crate::__take_trace!($(#[$attr])* "ref", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* ref mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = &mut $var;
+        $crate::__take_trace!($(#[$attr])* "ref mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* ref mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = &mut $var;
+        $crate::__take_trace!($(#[$attr])* "ref mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* =$var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* "=", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* =$var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* "=", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* =mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* "=mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* =mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $crate::__take_clone!($var);
+        $crate::__take_trace!($(#[$attr])* "=mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* =$var:ident as [$($v:ident),* $(,)?], $($rest:tt)*] => {
+        $crate::__take_impl![@fan [$(#[$attr])*] [] "=" $var [$($v),*]];
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* =mut $var:ident as [$($v:ident),* $(,)?], $($rest:tt)*] => {
+        $crate::__take_impl![@fan [$(#[$attr])*] [mut] "=mut" $var [$($v),*]];
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* =*$var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__take_clone!(*$var);
+        $crate::__take_trace!($(#[$attr])* "=*", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* =*$var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__take_clone!(*$var);
+        $crate::__take_trace!($(#[$attr])* "=*", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* =mut *$var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $crate::__take_clone!(*$var);
+        $crate::__take_trace!($(#[$attr])* "=mut *", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* =mut *$var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $crate::__take_clone!(*$var);
+        $crate::__take_trace!($(#[$attr])* "=mut *", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* *$var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = *$var;
+        $crate::__take_trace!($(#[$attr])* "*", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* *$var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = *$var;
+        $crate::__take_trace!($(#[$attr])* "*", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* *mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = *$var;
+        $crate::__take_trace!($(#[$attr])* "*mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* *mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = *$var;
+        $crate::__take_trace!($(#[$attr])* "*mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* &*$var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = &*$var;
+        $crate::__take_trace!($(#[$attr])* "&*", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* &*$var:ident: $t:ty, $($rest:tt)*] => {
+        $(#[$attr])* let $var: $t = &*$var;
+        $crate::__take_trace!($(#[$attr])* "&*", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* &*$var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = &*$var;
+        $crate::__take_trace!($(#[$attr])* "&*", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* &*$var:ident as $v:ident: $t:ty, $($rest:tt)*] => {
+        $(#[$attr])* let $v: $t = &*$var;
+        $crate::__take_trace!($(#[$attr])* "&*", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* &mut *$var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = &mut *$var;
+        $crate::__take_trace!($(#[$attr])* "&mut *", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* &mut *$var:ident: $t:ty, $($rest:tt)*] => {
+        $(#[$attr])* let $var: $t = &mut *$var;
+        $crate::__take_trace!($(#[$attr])* "&mut *", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* &mut *$var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = &mut *$var;
+        $crate::__take_trace!($(#[$attr])* "&mut *", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* &mut *$var:ident as $v:ident: $t:ty, $($rest:tt)*] => {
+        $(#[$attr])* let $v: $t = &mut *$var;
+        $crate::__take_trace!($(#[$attr])* "&mut *", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* hold $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* #[allow(unused_variables)]
+        let $var = $var;
+        $crate::__take_trace!($(#[$attr])* "hold", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* hold $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* #[allow(unused_variables)]
+        let $v = $var;
+        $crate::__take_trace!($(#[$attr])* "hold", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* drop $var:ident, $($rest:tt)*] => {
+        $crate::__take_trace!($(#[$attr])* "drop", $var);
+        $(#[$attr])* $crate::__private::mem::drop($var);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* not $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* #[allow(unused_variables)]
+        let $var = $crate::NotCaptured;
+        $crate::__take_trace!($(#[$attr])* "not", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* must $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::Taken::new($var);
+        $crate::__take_trace!($(#[$attr])* "must", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* must $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::Taken::new($var);
+        $crate::__take_trace!($(#[$attr])* "must", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* pin $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__private::pin::pin!($var);
+        $crate::__take_trace!($(#[$attr])* "pin", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* pin $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::pin::pin!($var);
+        $crate::__take_trace!($(#[$attr])* "pin", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* pin mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $crate::__private::pin::pin!($var);
+        $crate::__take_trace!($(#[$attr])* "pin mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* pin mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $crate::__private::pin::pin!($var);
+        $crate::__take_trace!($(#[$attr])* "pin mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* pin box $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__private::Box::pin($var);
+        $crate::__take_trace!($(#[$attr])* "pin box", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* pin box $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::Box::pin($var);
+        $crate::__take_trace!($(#[$attr])* "pin box", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* pin box mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $crate::__private::Box::pin($var);
+        $crate::__take_trace!($(#[$attr])* "pin box mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* pin box mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $crate::__private::Box::pin($var);
+        $crate::__take_trace!($(#[$attr])* "pin box mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* cow $var:ident $(: $ty:ty)?, $($rest:tt)*] => {
+        $(#[$attr])* let $var $(: $ty)? = $crate::__private::Cow::Borrowed(&*$var);
+        $crate::__take_trace!($(#[$attr])* "cow", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* cow $var:ident as $v:ident $(: $ty:ty)?, $($rest:tt)*] => {
+        $(#[$attr])* let $v $(: $ty)? = $crate::__private::Cow::Borrowed(&*$var);
+        $crate::__take_trace!($(#[$attr])* "cow", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* cow mut $var:ident $(: $ty:ty)?, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var $(: $ty)? = $crate::__private::Cow::Borrowed(&*$var);
+        $crate::__take_trace!($(#[$attr])* "cow mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* cow mut $var:ident as $v:ident $(: $ty:ty)?, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v $(: $ty)? = $crate::__private::Cow::Borrowed(&*$var);
+        $crate::__take_trace!($(#[$attr])* "cow mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* cow own $var:ident $(: $ty:ty)?, $($rest:tt)*] => {
+        $(#[$attr])* let $var $(: $ty)? = $crate::__private::Cow::Owned($var);
+        $crate::__take_trace!($(#[$attr])* "cow own", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* cow own $var:ident as $v:ident $(: $ty:ty)?, $($rest:tt)*] => {
+        $(#[$attr])* let $v $(: $ty)? = $crate::__private::Cow::Owned($var);
+        $crate::__take_trace!($(#[$attr])* "cow own", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* cow own mut $var:ident $(: $ty:ty)?, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var $(: $ty)? = $crate::__private::Cow::Owned($var);
+        $crate::__take_trace!($(#[$attr])* "cow own mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* cow own mut $var:ident as $v:ident $(: $ty:ty)?, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v $(: $ty)? = $crate::__private::Cow::Owned($var);
+        $crate::__take_trace!($(#[$attr])* "cow own mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* shrink $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__private::Shrink::shrink($var);
+        $crate::__take_trace!($(#[$attr])* "shrink", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* shrink $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::Shrink::shrink($var);
+        $crate::__take_trace!($(#[$attr])* "shrink", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* shrink mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $crate::__private::Shrink::shrink($var);
+        $crate::__take_trace!($(#[$attr])* "shrink mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* shrink mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $crate::__private::Shrink::shrink($var);
+        $crate::__take_trace!($(#[$attr])* "shrink mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* unwrap $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__private::UnwrapOrClone::unwrap_or_clone($var);
+        $crate::__take_trace!($(#[$attr])* "unwrap", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* unwrap $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::UnwrapOrClone::unwrap_or_clone($var);
+        $crate::__take_trace!($(#[$attr])* "unwrap", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* unwrap mut $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $var = $crate::__private::UnwrapOrClone::unwrap_or_clone($var);
+        $crate::__take_trace!($(#[$attr])* "unwrap mut", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* unwrap mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let mut $v = $crate::__private::UnwrapOrClone::unwrap_or_clone($var);
+        $crate::__take_trace!($(#[$attr])* "unwrap mut", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* arc_clone $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__private::ShareClone::share_clone(&$var);
+        $crate::__take_trace!($(#[$attr])* "arc_clone", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* arc_clone $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::ShareClone::share_clone(&$var);
+        $crate::__take_trace!($(#[$attr])* "arc_clone", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* rc_clone $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::RefCounted::ref_clone(&$var);
+        $crate::__take_trace!($(#[$attr])* "rc_clone", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* rc_clone $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::RefCounted::ref_clone(&$var);
+        $crate::__take_trace!($(#[$attr])* "rc_clone", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* weak $var:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $var = $crate::__private::Downgrade::downgrade(&$var);
+        $crate::__take_trace!($(#[$attr])* "weak", $var);
+        $crate::__take_impl![$($rest)*]
+    };
+    [$(#[$attr:meta])* weak $var:ident as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $v = $crate::__private::Downgrade::downgrade(&$var);
+        $crate::__take_trace!($(#[$attr])* "weak", $v);
+        $crate::__take_impl![$($rest)*]
+    };
+
+    [$(#[$attr:meta])* send $($rest:tt)+] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [send] [] [] $($rest)+]
+    };
+    [$(#[$attr:meta])* sync $($rest:tt)+] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [sync] [] [] $($rest)+]
+    };
+    [$(#[$attr:meta])* unwind $($rest:tt)+] => {
+        $crate::__take_impl![@guard [$(#[$attr])*] [unwind] [] [] $($rest)+]
+    };
+
+    // --------------------------
+    // ----- invalid syntax -----
+    // Anything that reaches this point is not valid syntax. Split off the offending entry and
+    // try to explain what is wrong with it.
+    [@error [$($entry:tt)*], $($rest:tt)*] => {
+        $crate::__take_impl![@invalid $($entry)*]
+    };
+    [@error [$($entry:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::__take_impl![@error [$($entry)* $t] $($rest)*]
+    };
+    [@error [$($entry:tt)*]] => {
+        $crate::__take_impl![@invalid $($entry)*]
+    };
+
+    [@invalid] => {
+        compile_error!("invalid `take!` entry: found an empty entry, remove the extra `,`");
+    };
+    [@invalid #[$attr:meta] $($entry:tt)*] => {
+        $crate::__take_impl![@invalid $($entry)*]
+    };
+    [@invalid mut & $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!(mut & $($entry)*),
+            "`: `mut` must come after `&`, write `&mut x`"
+        ));
+    };
+    [@invalid mut = $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!(mut = $($entry)*),
+            "`: `mut` must come after `=`, write `=mut x`"
+        ));
+    };
+    [@invalid mut * $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!(mut * $($entry)*),
+            "`: `mut` must come after `*`, write `*mut x`"
+        ));
+    };
+    [@invalid mut mut $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!(mut mut $($entry)*),
+            "`: `mut` can only be given once"
+        ));
+    };
+    [@invalid & mut mut $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!(& mut mut $($entry)*),
+            "`: `mut` can only be given once"
+        ));
+    };
+    [@invalid = mut mut $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!(= mut mut $($entry)*),
+            "`: `mut` can only be given once"
+        ));
+    };
+    [@invalid * mut mut $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!(* mut mut $($entry)*),
+            "`: `mut` can only be given once"
+        ));
+    };
+    [@invalid $($entry:tt)*] => {
+        $crate::__take_impl![@invalid_as [$($entry)*] $($entry)*]
+    };
+
+    // `as` at the very end of the entry
+    [@invalid_as [$($entry:tt)*] as] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!($($entry)*),
+            "`: `as` must be followed by the new name, i.e. `x as y`"
+        ));
+    };
+    [@invalid_as [$($entry:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_as [$($entry)*] $($rest)*]
+    };
+    [@invalid_as [$($entry:tt)*]] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($entry)*]
+    };
+
+    // strip the sigils and look at what is left
+    [@invalid_ident [$($entry:tt)*] & $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] = $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] * $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] ? $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] ! $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] mut $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] ref $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] must $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] pin $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] box $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] cow $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] own $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] shrink $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] unwrap $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] weak $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] arc_clone $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*] rc_clone $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_ident [$($entry)*] $($rest)*]
+    };
+    [@invalid_ident [$($entry:tt)*]] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!($($entry)*),
+            "`: expected the name of a variable after the sigil"
+        ));
+    };
+    [@invalid_ident [$($entry:tt)*] $var:ident . $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_expr [$($entry)*]]
+    };
+    [@invalid_ident [$($entry:tt)*] $var:ident :: $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_expr [$($entry)*]]
+    };
+    [@invalid_ident [$($entry:tt)*] $var:ident ($($args:tt)*) $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_expr [$($entry)*]]
+    };
+    [@invalid_ident [$($entry:tt)*] $var:ident $stray:tt $($rest:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!($($entry)*),
+            "`: unexpected `", stringify!($stray), "` after `", stringify!($var),
+            "`, entries must be separated by `,`"
+        ));
+    };
+    [@invalid_ident [$($entry:tt)*] $($rest:tt)*] => {
+        $crate::__take_impl![@invalid_expr [$($entry)*]]
+    };
+    [@invalid_expr [$($entry:tt)*]] => {
+        compile_error!(concat!(
+            "invalid `take!` entry `", stringify!($($entry)*),
+            "`: expected the name of a variable, found an expression. ",
+            "Bind the expression with `let` first, or give a method call a name, ",
+            "i.e. `x.len() as len`."
+        ));
+    };
+
+    [$($tokens:tt)+] => {
+        $crate::__take_impl![@error [] $($tokens)+]
+    };
+}
+
+/// `own!` is another name for [`take!`](macro.take.html), for codebases where "own" reads better.
+///
+/// Both names expand through the same arms, so `own!` accepts exactly the same entries and
+/// expands exactly the same way.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (x, y, z) = (1, String::from("y"), vec![3]);
+/// own!(x, &y, =mut z as w);
+/// w.push(4);
+/// assert_eq!((x, y.as_str(), w), (1, "y", vec![3, 4]));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! own {
+    [$($tokens:tt)*] => {
+        $crate::__take_impl![@check ($) $($tokens)*]
+    };
+}
+
+/// A stricter [`take!`](macro.take.html) where every entry has to be renamed with `as`.
+///
+/// `renaming_take!` accepts the same entries as `take!`, as long as each one ends in
+/// `as <name>` (with the optional `: Type` of `&*x`), and expands to the same statements. An entry
+/// without `as` is a compile error, and so is `x as x`, which is reported as "`x as x` doesn't
+/// rename `x`, `renaming_take!` needs a new name". A module can opt into the stricter
+/// convention with `use taken::renaming_take as take;`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (tx, items, count) = (String::from("tx"), vec![1, 2], 3);
+/// renaming_take!(=tx as tx_a, &items as list, mut count as n);
+/// n += list.len();
+/// assert_eq!((tx_a.as_str(), n), ("tx", 5));
+/// # }
+/// ```
+///
+/// Because the new name is never the same as the source, the original variable is still in scope
+/// after the entry. When it was moved, using it again is a "use of moved value" error rather than
+/// a silent use of the shadowing binding.
+#[macro_export]
+macro_rules! renaming_take {
+    [@entry [$($done:tt)*] [$($cur:tt)*] $var:ident as $v:ident $(: $t:ty)? $(, $($rest:tt)*)?] => {
+        $crate::renaming_take![@distinct ($) $var $v];
+        $crate::renaming_take![@entry [$($done)* $($cur)* $var as $v $(: $t)?,] [] $($($rest)*)?]
+    };
+    [@entry [$($done:tt)*] [$($cur:tt)+] as $v:tt $(, $($rest:tt)*)?] => {
+        $crate::renaming_take![@entry [$($done)* $($cur)+ as $v,] [] $($($rest)*)?]
+    };
+    [@entry [$($done:tt)*] [$($cur:tt)+] $(, $($rest:tt)*)?] => {
+        compile_error!(concat!(
+            "`renaming_take!` entries must be renamed with `as`, found `", stringify!($($cur)+), "`"
+        ));
+    };
+    [@entry [$($done:tt)*] [$($cur:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::renaming_take![@entry [$($done)*] [$($cur)* $t] $($rest)*]
+    };
+    [@entry [$($done:tt)*] []] => {
+        $crate::take![$($done)*]
+    };
+
+    // keywords can come before `as` (`=self as this`, `x else continue as y`), but can't be names
+    [@distinct ($d:tt) self $v:ident] => {};
+    [@distinct ($d:tt) return $v:ident] => {};
+    [@distinct ($d:tt) continue $v:ident] => {};
+    [@distinct ($d:tt) break $v:ident] => {};
+    // A local macro that only matches the source name tells whether the new name is the same.
+    // `$d` is a `$`, for the matcher of that macro.
+    [@distinct ($d:tt) $var:ident $v:ident] => {
+        {
+            macro_rules! __taken_renamed {
+                ($var) => {
+                    compile_error!(concat!(
+                        "`", stringify!($var), " as ", stringify!($var), "` doesn't rename `",
+                        stringify!($var), "`, `renaming_take!` needs a new name"
+                    ));
+                };
+                ($d other:ident) => {};
+            }
+            __taken_renamed!($v);
+        }
+    };
+
+    [$($tokens:tt)*] => {
+        $crate::renaming_take![@entry [] [] $($tokens)*]
+    };
+}
+
+/// A [`take!`](macro.take.html) that rejects taking the same variable twice.
+///
+/// `take!(=x, y, =x)` happily clones `x` twice, which in a generated capture list usually means
+/// that the generator has a bug. `take_disjoint!` accepts the same entries as `take!`, but each
+/// variable may only appear in one of them, whatever the sigils. A duplicate is reported as
+/// "identifier `x` appears twice in `take_disjoint!` list", or as "`x` appears twice as a move in
+/// `take_disjoint!` — use `=x` to clone or restructure the capture list" when both entries move
+/// it.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (x, y, z) = (String::from("x"), vec![1], 3);
+/// take_disjoint!(=x as name, &y, mut z);
+/// z += y.len();
+/// assert_eq!((name, z), (x, 4));
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (x, y) = (String::from("x"), 2);
+/// take_disjoint!(=x as a, y, &x as b); // error: identifier `x` appears twice
+/// # }
+/// ```
+///
+/// The check is made on the variable an entry takes from, not the names it binds: `=x as [a, b]`
+/// is one entry. Entries on `self` are not checked.
+#[macro_export]
+macro_rules! take_disjoint {
+    [@split [$($done:tt)*] [$($cur:tt)*] , $($rest:tt)*] => {
+        $crate::take_disjoint![@split [$($done)* [$($cur)*]] [] $($rest)*]
+    };
+    [@split [$($done:tt)*] [$($cur:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::take_disjoint![@split [$($done)*] [$($cur)* $t] $($rest)*]
+    };
+    [@split [$([$($entry:tt)*])*] [$($cur:tt)*]] => {
+        $crate::take_disjoint![@check $([$($entry)*])* [$($cur)*]]
+    };
+
+    [@check $([$($entry:tt)*])*] => {
+        $crate::take_disjoint![@names [] $([$($entry)*])*]
+    };
+
+    // Every entry is reduced to `[var kind]`, where the kind is `move` for entries that move out
+    // of the variable (like `x` and `mut x as y`) and `_` for the others.
+    [@names [$($done:tt)*] [$($entry:tt)*] $($rest:tt)*] => {
+        $crate::take_disjoint![@source [$($done)*] [$($rest)*] move $($entry)*]
+    };
+    [@names [$($done:tt)*]] => {
+        $crate::take_disjoint![@pairs ($) $($done)*]
+    };
+
+    // The variable an entry takes from, skipping its attributes, sigils and keywords.
+    [@source $done:tt $rest:tt $kind:tt #[$attr:meta] $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt & $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest _ $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt = $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest _ $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt * $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest _ $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt ! $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt ? $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt small [$($n:tt)*] $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt together : $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt mut $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt ref $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest _ $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt not $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest _ $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt hold $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt drop $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt must $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt pin $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt box $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt cow own $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt cow $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest _ $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt shrink $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt unwrap $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt weak $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest _ $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt arc_clone $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest _ $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt rc_clone $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest _ $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt send $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt sync $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt static $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source $done:tt $rest:tt $kind:tt unwind $($entry:tt)*] => { $crate::take_disjoint![@source $done $rest $kind $($entry)*] };
+    [@source [$($done:tt)*] [$($rest:tt)*] $kind:tt ($($var:ident),+ $(,)?) $($entry:tt)*] => {
+        $crate::take_disjoint![@names [$($done)* $([$var $kind])+] $($rest)*]
+    };
+    [@source [$($done:tt)*] [$($rest:tt)*] $kind:tt [$($elems:tt)*] = $var:ident $($entry:tt)*] => {
+        $crate::take_disjoint![@names [$($done)* [$var $kind]] $($rest)*]
+    };
+    [@source [$($done:tt)*] [$($rest:tt)*] $kind:tt self $($entry:tt)*] => {
+        $crate::take_disjoint![@names [$($done)*] $($rest)*]
+    };
+    [@source [$($done:tt)*] [$($rest:tt)*] $kind:tt $var:ident $(as $($v:tt)+)?] => {
+        $crate::take_disjoint![@names [$($done)* [$var $kind]] $($rest)*]
+    };
+    // method calls, paths and the like only read the variable
+    [@source [$($done:tt)*] [$($rest:tt)*] $kind:tt $var:ident $($entry:tt)*] => {
+        $crate::take_disjoint![@names [$($done)* [$var _]] $($rest)*]
+    };
+    [@source [$($done:tt)*] [$($rest:tt)*] $kind:tt $($entry:tt)*] => {
+        $crate::take_disjoint![@names [$($done)*] $($rest)*]
+    };
+
+    // Each variable is compared with the ones after it by a local macro that only matches its
+    // name, so the error can name the variable. `$d` is a `$`, for the matchers of that macro.
+    [@pairs ($d:tt) [$var:ident $kind:tt] $([$other:ident $other_kind:tt])*] => {
+        {
+            macro_rules! __taken_disjoint {
+                ($var move move) => {
+                    compile_error!(concat!(
+                        "`", stringify!($var), "` appears twice as a move in `take_disjoint!` — use `=",
+                        stringify!($var), "` to clone or restructure the capture list"
+                    ));
+                };
+                ($var $d($d kinds:tt)*) => {
+                    compile_error!(concat!(
+                        "identifier `", stringify!($var), "` appears twice in `take_disjoint!` list"
+                    ));
+                };
+                ($d($d other:tt)*) => {};
+            }
+            $(__taken_disjoint!($other $kind $other_kind);)*
+        }
+        $crate::take_disjoint![@pairs ($d) $([$other $other_kind])*]
+    };
+    [@pairs ($d:tt)] => {};
+
+    // the entries of `{ .. } => body` are checked on their own
+    [{ $($entry:tt)* } => $($body:tt)*] => {{
+        $crate::take_disjoint![@split [] [] $($entry)*];
+        $crate::__take_impl![{ $($entry)* } => $($body)*]
+    }};
+    [$($tokens:tt)*] => {
+        $crate::take_disjoint![@split [] [] $($tokens)*];
+        $crate::__take_impl![$($tokens)*]
+    };
+}
+
+/// Clone variables, with cloning as the default instead of moving.
+///
+/// For code coming from clone-by-default capture macros: every name in `cloned!` is cloned, so
+/// there are no sigils. Each name can be `mut` and renamed with `as`. Followed by `=> expr`, the
+/// clones are made in a scope of their own and only `expr`, usually a `move` closure or an
+/// `async move` block, sees them. Every form is a [`take!`](macro.take.html) in disguise:
+///
+/// | `cloned!`                        | `take!`                              |
+/// |----------------------------------|--------------------------------------|
+/// | `cloned!(tx, config)`            | `take!(=tx, =config)`                |
+/// | `cloned!(mut buf)`               | `take!(=mut buf)`                    |
+/// | `cloned!(tx as sender)`          | `take!(=tx as sender)`               |
+/// | `cloned!(tx, config => closure)` | `take!({ =tx, =config } => closure)` |
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::mpsc;
+///
+/// let (tx, rx) = mpsc::channel();
+/// let prefix = String::from("job");
+/// let mut send = cloned!(tx, prefix as name => move |i: u32| tx.send(format!("{} {}", name, i)).unwrap());
+/// send(1);
+/// send(2);
+///
+/// cloned!(mut prefix);
+/// prefix.push('!');
+/// drop((send, tx));
+/// assert_eq!(rx.iter().collect::<Vec<_>>(), vec!["job 1", "job 2"]);
+/// assert_eq!(prefix, "job!");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! cloned {
+    [@entries [$($entry:tt)*] mut $var:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::cloned![@entries [$($entry)* =mut $var $(as $v)?,] $($($rest)*)?]
+    };
+    [@entries [$($entry:tt)*] mut $var:ident $(as $v:ident)? => $body:expr] => {
+        $crate::take![{ $($entry)* =mut $var $(as $v)? } => $body]
+    };
+    [@entries [$($entry:tt)*] $var:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::cloned![@entries [$($entry)* =$var $(as $v)?,] $($($rest)*)?]
+    };
+    [@entries [$($entry:tt)*] $var:ident $(as $v:ident)? => $body:expr] => {
+        $crate::take![{ $($entry)* =$var $(as $v)? } => $body]
+    };
+    [@entries [$($entry:tt)*] => $body:expr] => {
+        $crate::take![{ $($entry)* } => $body]
+    };
+    [@entries [$($entry:tt)*]] => {
+        $crate::take![$($entry)*]
+    };
+    [@entries [$($entry:tt)*] $($rest:tt)+] => {
+        compile_error!(concat!(
+            "`cloned!` entries are names, optionally `mut` and renamed with `as`, found `",
+            stringify!($($rest)+), "`"
+        ));
+    };
+
+    [$($tokens:tt)*] => {
+        $crate::cloned![@entries [] $($tokens)*]
+    };
+}
+
+/// Clone variables under new names, to compare them later.
+///
+/// `snapshot!(x as before)` expands to `let before = x.clone();`, the same as
+/// `take!(=x as before)`, and leaves `x` itself alone, so it can still be changed or taken
+/// afterwards. The snapshot always has a name of its own, and can be `mut`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let mut items = vec![1, 2];
+/// snapshot!(items as before); // let before = items.clone();
+/// let mut push = || items.push(3);
+/// push();
+/// take!(items as after);
+/// assert_eq!((before, after), (vec![1, 2], vec![1, 2, 3]));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! snapshot {
+    [$(#[$attr:meta])* mut $var:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $crate::take![$(#[$attr])* =mut $var as $v];
+        $crate::snapshot![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* $var:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $crate::take![$(#[$attr])* =$var as $v];
+        $crate::snapshot![$($($rest)*)?]
+    };
+    [] => {};
+    [$($entry:tt)+] => {
+        compile_error!(concat!(
+            "`snapshot!` entries are `x as <name>` or `mut x as <name>`, found `",
+            stringify!($($entry)+), "`"
+        ));
+    };
+}
+
+/// Take a single variable with a given sigil and name, for macros that build on `take!`.
+///
+/// `__expand_take_arm!(sigil, var, alias)` expands to the one `let` that
+/// `take!(<sigil> var as alias)` would, so a closure-building macro can forward the pieces it
+/// parsed itself instead of reassembling `take!` syntax. The sigil is a single token tree: `&`,
+/// `=`, or a parenthesized group for the other sigils and for none at all, such as `(&mut)`,
+/// `(=mut)` or `()`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// macro_rules! on_event {
+///     ($($sigil:tt $var:ident => $alias:ident),* ; $body:expr) => {{
+///         $(taken::__expand_take_arm!($sigil, $var, $alias);)*
+///         move || $body
+///     }};
+/// }
+///
+/// # fn main() {
+/// let (name, mut hits, count) = (String::from("click"), vec![1], 2);
+/// let mut handler = on_event!(= name => label, (&mut) hits => log, () count => n; {
+///     log.push(n);
+///     label.len()
+/// });
+/// assert_eq!(handler(), 5);
+/// assert_eq!((name.as_str(), hits), ("click", vec![1, 2]));
+/// # }
+/// ```
+///
+/// It is part of the public API despite the leading underscores, which only keep it out of the way
+/// of the macros meant to be called directly.
+#[macro_export]
+macro_rules! __expand_take_arm {
+    [($($sigil:tt)*), $var:ident, $alias:ident $(,)?] => {
+        $crate::take![$($sigil)* $var as $alias];
+    };
+    [$sigil:tt, $var:ident, $alias:ident $(,)?] => {
+        $crate::take![$sigil $var as $alias];
+    };
+}
+
+/// Take the value out of an `Option` or `Result`, saying what happens if there is none.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// fn compute_default() -> u32 {
+///     3
+/// }
+/// let (x, y, z): (Option<u32>, Result<u32, ()>, Option<u32>) = (None, Err(()), Some(5));
+/// try_take!(
+///     x or 1,                              // let x = x.unwrap_or(1);
+///     mut y or_else |_| compute_default(), // let mut y = y.unwrap_or_else(|_| ...);
+///     z as w or panic "z is set",          // let w = z.expect("z is set");
+/// );
+/// y += 1;
+/// assert_eq!((x, y, w), (1, 4, 5));
+/// # }
+/// ```
+///
+/// `?x` propagates the error with `?` instead, in a function returning `Option` or `Result`:
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// fn parse(input: Option<&str>, port: Option<&str>) -> Option<u16> {
+///     try_take!(?input, ?mut port as p); // let input = input?; let mut p = port?;
+///     p = p.trim();
+///     input.parse().ok().or_else(|| p.parse().ok())
+/// }
+/// assert_eq!(parse(Some("x"), Some(" 80 ")), Some(80));
+/// assert_eq!(parse(None, Some("80")), None);
+/// # }
+/// ```
+///
+/// Every form can be `mut` and renamed with `as`, which comes before `or` so that it isn't read as
+/// a cast of the default. The default of `or` is evaluated even if the value is there, like
+/// `unwrap_or`; use `or_else` for defaults that are expensive to compute.
+#[macro_export]
+macro_rules! try_take {
+    [@as [$(#[$attr:meta])*] [$($q:tt)?] [$($m:tt)?] $var:ident [$name:ident] as $v:ident $($rest:tt)*] => {
+        $crate::try_take![@as [$(#[$attr])*] [$($q)?] [$($m)?] $var [$v] $($rest)*]
+    };
+    [@as [$(#[$attr:meta])*] [?] [$($m:tt)?] $var:ident [$name:ident] $(, $($rest:tt)*)?] => {
+        $crate::take![$(#[$attr])* ?$($m)? $var as $name];
+        $crate::try_take![$($($rest)*)?]
+    };
+    [@as [$(#[$attr:meta])*] [] [$($m:tt)?] $var:ident [$name:ident] or panic $msg:literal $(, $($rest:tt)*)?] => {
+        $crate::take![$(#[$attr])* !$($m)? $var or $msg as $name];
+        $crate::try_take![$($($rest)*)?]
+    };
+    [@as [$(#[$attr:meta])*] [] [] $var:ident [$name:ident] or_else $f:expr $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $name = $crate::__private::UnwrapOrElse::unwrap_or_else($var, $f);
+        $crate::__take_trace!($(#[$attr])* "or_else", $name);
+        $crate::try_take![$($($rest)*)?]
+    };
+    [@as [$(#[$attr:meta])*] [] [] $var:ident [$name:ident] or $default:expr $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $name = $crate::__private::Fallible::unwrap_or($var, $default);
+        $crate::__take_trace!($(#[$attr])* "or", $name);
+        $crate::try_take![$($($rest)*)?]
+    };
+    [@as [$(#[$attr:meta])*] [] [mut] $var:ident [$name:ident] or_else $f:expr $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let mut $name = $crate::__private::UnwrapOrElse::unwrap_or_else($var, $f);
+        $crate::__take_trace!($(#[$attr])* "mut or_else", $name);
+        $crate::try_take![$($($rest)*)?]
+    };
+    [@as [$(#[$attr:meta])*] [] [mut] $var:ident [$name:ident] or $default:expr $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let mut $name = $crate::__private::Fallible::unwrap_or($var, $default);
+        $crate::__take_trace!($(#[$attr])* "mut or", $name);
+        $crate::try_take![$($($rest)*)?]
+    };
+    [@as [$(#[$attr:meta])*] [$($q:tt)?] [$($m:tt)?] $var:ident [$name:ident] $($rest:tt)*] => {
+        compile_error!(concat!(
+            "`try_take!` entries are `x or <default>`, `x or_else <closure>`, ",
+            "`x or panic \"<message>\"` or `?x`, found `", stringify!($($q)? $($m)? $var $($rest)*), "`"
+        ));
+    };
+
+    [] => {};
+    [$(#[$attr:meta])* ?mut $var:ident $($rest:tt)*] => {
+        $crate::try_take![@as [$(#[$attr])*] [?] [mut] $var [$var] $($rest)*]
+    };
+    [$(#[$attr:meta])* ?$var:ident $($rest:tt)*] => {
+        $crate::try_take![@as [$(#[$attr])*] [?] [] $var [$var] $($rest)*]
+    };
+    [$(#[$attr:meta])* mut $var:ident $($rest:tt)*] => {
+        $crate::try_take![@as [$(#[$attr])*] [] [mut] $var [$var] $($rest)*]
+    };
+    [$(#[$attr:meta])* $var:ident $($rest:tt)*] => {
+        $crate::try_take![@as [$(#[$attr])*] [] [] $var [$var] $($rest)*]
+    };
+}
+
+/// Take the value out of an `Option` or `Result`, or the type's default if there is none.
+///
+/// `take_or_default!(x)` expands to `let x = x.unwrap_or_default();`, which fails to compile if
+/// the value type doesn't implement `Default`. Entries can be `mut` and renamed with `as`, and
+/// several can be listed, which is handy for many optional settings:
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (title, width, retries): (Option<String>, Option<u32>, Result<u8, ()>) = (None, Some(640), Err(()));
+/// take_or_default!(title, mut width as w, retries); // let title = title.unwrap_or_default(); ...
+/// w += 1;
+/// assert_eq!((title.as_str(), w, retries), ("", 641, 0));
+/// # }
+/// ```
+///
+/// Use [`take_or!`](macro.take_or.html) for a fallback other than the default, and
+/// [`try_take!`](macro.try_take.html) for the other ways of handling a missing value.
+#[macro_export]
+macro_rules! take_or_default {
+    [$(#[$attr:meta])* $var:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_or_default![@let [$(#[$attr])*] [] $var [$($v)?]];
+        $crate::take_or_default![$($($rest)*)?]
+    };
+    [$(#[$attr:meta])* mut $var:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_or_default![@let [$(#[$attr])*] [mut] $var [$($v)?]];
+        $crate::take_or_default![$($($rest)*)?]
+    };
+
+    [@let [$(#[$attr:meta])*] [$($m:tt)?] $var:ident []] => {
+        $crate::take_or_default![@let [$(#[$attr])*] [$($m)?] $var [$var]];
+    };
+    [@let [$(#[$attr:meta])*] [] $var:ident [$v:ident]] => {
+        $(#[$attr])* let $v = $crate::__private::Fallible::unwrap_or_default($var);
+        $crate::__take_trace!($(#[$attr])* "or_default", $v);
+    };
+    [@let [$(#[$attr:meta])*] [mut] $var:ident [$v:ident]] => {
+        $(#[$attr])* let mut $v = $crate::__private::Fallible::unwrap_or_default($var);
+        $crate::__take_trace!($(#[$attr])* "mut or_default", $v);
+    };
+
+    [] => {};
+}
+
+/// Take the value out of an `Option` or `Result`, or a fallback if there is none.
+///
+/// `take_or!(x, fallback)` expands to `let x = x.unwrap_or(fallback);`, the same as
+/// `try_take!(x or fallback)`. `mut` and `as` work as usual. Like `unwrap_or`, the fallback is
+/// evaluated even if the value is there.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (theme, scale): (Option<&str>, Result<f32, String>) = (None, Err(String::from("bad")));
+/// take_or!(theme, "dark");             // let theme = theme.unwrap_or("dark");
+/// take_or!(mut scale as factor, 1.0);  // let mut factor = scale.unwrap_or(1.0);
+/// factor *= 2.0;
+/// assert_eq!((theme, factor), ("dark", 2.0));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_or {
+    [$(#[$attr:meta])* mut $var:ident $(as $v:ident)?, $fallback:expr $(,)?] => {
+        $crate::try_take![$(#[$attr])* mut $var $(as $v)? or $fallback]
+    };
+    [$(#[$attr:meta])* $var:ident $(as $v:ident)?, $fallback:expr $(,)?] => {
+        $crate::try_take![$(#[$attr])* $var $(as $v)? or $fallback]
+    };
+}
+
+/// Take a variable, showing it to an inspector closure first.
+///
+/// `take_inspect!(x |v| println!("x = {:?}", v))` expands to
+/// `let x = { let value = x; inspector(&value); value };`, which is handy for logging what a
+/// closure is about to capture. Entries can be `mut` and renamed with `as` (before the closure),
+/// and the closure's argument can be a pattern or a name with a type, as in `|v: &Vec<u8>|`.
+///
+/// The inspector only runs with `debug_assertions`, like `debug_assert!`. In release builds the
+/// closure isn't even compiled in, and `take_inspect!(x |v| ...)` is just `let x = x;`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (path, retries) = (String::from("/tmp"), 3);
+/// let mut seen = Vec::new();
+/// let work = {
+///     take_inspect!(
+///         path |p| seen.push(format!("path = {:?}", p)),
+///         mut retries as left |n: &i32| seen.push(format!("retries = {}", n)),
+///     );
+///     left -= 1;
+///     move || format!("{} {}", path, left)
+/// };
+/// assert_eq!(work(), "/tmp 2");
+/// # if cfg!(debug_assertions) {
+/// assert_eq!(seen, ["path = \"/tmp\"", "retries = 3"]);
+/// # }
+/// # }
+/// ```
+///
+/// [`take_inspect_mut!`](macro.take_inspect_mut.html) passes a `&mut` instead.
+#[macro_export]
+macro_rules! take_inspect {
+    [$($entry:tt)*] => {
+        $crate::__take_inspect![@entries inspect $($entry)*]
+    };
+}
+
+/// [`take_inspect!`](macro.take_inspect.html) with an inspector that gets a `&mut`, which can
+/// change the value before it is taken.
+///
+/// Like the inspector of `take_inspect!`, it only runs with `debug_assertions`, so it is meant for
+/// debugging aids such as poisoning or tagging a value, not for changes the program relies on.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let log = vec![String::from("start")];
+/// take_inspect_mut!(log |log| log.push(String::from("taken")));
+/// # if cfg!(debug_assertions) {
+/// assert_eq!(log, ["start", "taken"]);
+/// # }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_inspect_mut {
+    [$($entry:tt)*] => {
+        $crate::__take_inspect![@entries inspect_mut $($entry)*]
+    };
+}
+
+/// Used by `take_inspect!` and `take_inspect_mut!`, where `$inspect` names the function in
+/// `__private` that calls the inspector.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __take_inspect {
+    [@entries $inspect:ident] => {};
+    [@entries $inspect:ident $(#[$attr:meta])* mut $var:ident $(as $v:ident)? |$p:ident: $t:ty| $body:expr $(, $($rest:tt)*)?] => {
+        $crate::__take_inspect![@let $inspect [$(#[$attr])*] [mut] $var [$($v)?] (|$p: $t| $body)];
+        $crate::__take_inspect![@entries $inspect $($($rest)*)?];
+    };
+    [@entries $inspect:ident $(#[$attr:meta])* mut $var:ident $(as $v:ident)? |$p:pat_param| $body:expr $(, $($rest:tt)*)?] => {
+        $crate::__take_inspect![@let $inspect [$(#[$attr])*] [mut] $var [$($v)?] (|$p| $body)];
+        $crate::__take_inspect![@entries $inspect $($($rest)*)?];
+    };
+    [@entries $inspect:ident $(#[$attr:meta])* $var:ident $(as $v:ident)? |$p:ident: $t:ty| $body:expr $(, $($rest:tt)*)?] => {
+        $crate::__take_inspect![@let $inspect [$(#[$attr])*] [] $var [$($v)?] (|$p: $t| $body)];
+        $crate::__take_inspect![@entries $inspect $($($rest)*)?];
+    };
+    [@entries $inspect:ident $(#[$attr:meta])* $var:ident $(as $v:ident)? |$p:pat_param| $body:expr $(, $($rest:tt)*)?] => {
+        $crate::__take_inspect![@let $inspect [$(#[$attr])*] [] $var [$($v)?] (|$p| $body)];
+        $crate::__take_inspect![@entries $inspect $($($rest)*)?];
+    };
+    [@entries $inspect:ident $($entry:tt)*] => {
+        compile_error!(concat!(
+            "expected `name |value| inspector`, found `", stringify!($($entry)*), "`"
+        ));
+    };
+
+    [@let $inspect:ident [$(#[$attr:meta])*] [$($m:tt)?] $var:ident [] $f:expr] => {
+        $crate::__take_inspect![@let $inspect [$(#[$attr])*] [$($m)?] $var [$var] $f];
+    };
+    [@let $inspect:ident [$(#[$attr:meta])*] [$($m:tt)?] $var:ident [$v:ident] $f:expr] => {
+        $(#[$attr])* let $($m)? $v = {
+            #[allow(unused_mut)]
+            let mut value = $var;
+            #[cfg(debug_assertions)]
+            $crate::__private::$inspect(&mut value, $f);
+            value
+        };
+        $crate::__take_trace!($(#[$attr])* "inspect", $v);
+    };
+}
+
+/// Take a variable only if a condition holds, otherwise bind a default.
+///
+/// `conditional_take!(x if keep else default)` expands to
+/// `let x = if keep { x } else { default };`. With `=x` the value is cloned instead, so
+/// `conditional_take!(=x if shared else x)` only clones when it has to. Entries can be `mut` and
+/// renamed with `as`, which comes before `if`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (verbose, shared) = (false, true);
+/// let (label, name, log) = (String::from("job"), String::from("liam"), vec![1]);
+/// conditional_take!(
+///     label if verbose else String::new(),  // let label = if verbose { label } else { ... };
+///     =name as n if shared else name,       // let n = if shared { name.clone() } else { name };
+///     mut log if !verbose else Vec::new(),  // let mut log = if !verbose { log } else { ... };
+/// );
+/// log.push(2);
+/// assert_eq!((label.as_str(), n.as_str(), log), ("", "liam", vec![1, 2]));
+/// # }
+/// ```
+///
+/// Both branches end up in the same binding, so the default must have the same type as the value.
+/// Without `=`, the value is moved into the `if` branch, and Rust doesn't track which branch was
+/// taken: even when the condition is false, the original `x` counts as moved afterwards. There's
+/// no "keep `x` if it wasn't taken"; to get that, clone with `=x` and keep the original.
+#[macro_export]
+macro_rules! conditional_take {
+    [@as [$(#[$attr:meta])*] [$($c:tt)?] [$($m:tt)?] $var:ident [$name:ident] as $v:ident $($rest:tt)*] => {
+        $crate::conditional_take![@as [$(#[$attr])*] [$($c)?] [$($m)?] $var [$v] $($rest)*]
+    };
+    [@as [$(#[$attr:meta])*] [$($c:tt)?] [$($m:tt)?] $var:ident [$name:ident] if $($rest:tt)*] => {
+        $crate::conditional_take![@if [$(#[$attr])*] [$($c)?] [$($m)?] $var [$name] [] $($rest)*]
+    };
+    [@as [$(#[$attr:meta])*] [$($c:tt)?] [$($m:tt)?] $var:ident [$name:ident] $($rest:tt)*] => {
+        compile_error!(concat!(
+            "`conditional_take!` entries are `x if <condition> else <default>`, found `",
+            stringify!($($c)? $($m)? $var $($rest)*), "`"
+        ));
+    };
+
+    // the condition is everything up to the first `else`
+    [@if [$(#[$attr:meta])*] [$($c:tt)?] [$($m:tt)?] $var:ident [$name:ident] [$($cond:tt)+] else $default:expr $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $($m)? $name = if $($cond)+ {
+            $crate::conditional_take![@value [$($c)?] $var]
+        } else {
+            $default
+        };
+        $crate::__take_trace!($(#[$attr])* "if", $name);
+        $crate::conditional_take![$($($rest)*)?]
+    };
+    [@if [$(#[$attr:meta])*] [$($c:tt)?] [$($m:tt)?] $var:ident [$name:ident] [$($cond:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::conditional_take![@if [$(#[$attr])*] [$($c)?] [$($m)?] $var [$name] [$($cond)* $t] $($rest)*]
+    };
+    [@if [$(#[$attr:meta])*] [$($c:tt)?] [$($m:tt)?] $var:ident [$name:ident] [$($cond:tt)*]] => {
+        compile_error!(concat!(
+            "`conditional_take!` entries need a condition and an `else <default>`, found `",
+            stringify!($($c)? $($m)? $var if $($cond)*), "`"
+        ));
+    };
+
+    [@value [] $var:ident] => { $var };
+    [@value [=] $var:ident] => { $crate::__take_clone!($var) };
+
+    [] => {};
+    [$(#[$attr:meta])* =mut $var:ident $($rest:tt)*] => {
+        $crate::conditional_take![@as [$(#[$attr])*] [=] [mut] $var [$var] $($rest)*]
+    };
+    [$(#[$attr:meta])* =$var:ident $($rest:tt)*] => {
+        $crate::conditional_take![@as [$(#[$attr])*] [=] [] $var [$var] $($rest)*]
+    };
+    [$(#[$attr:meta])* mut $var:ident $($rest:tt)*] => {
+        $crate::conditional_take![@as [$(#[$attr])*] [] [mut] $var [$var] $($rest)*]
+    };
+    [$(#[$attr:meta])* $var:ident $($rest:tt)*] => {
+        $crate::conditional_take![@as [$(#[$attr])*] [] [] $var [$var] $($rest)*]
+    };
+}
+
+/// Define named lists of `take!` entries that can be reused with `take!(use name; ...)`.
+///
+/// `capture_set! { ui_deps = (=model, &assets) }` defines a local macro called `ui_deps`, so the
+/// set can be used by any `take!` after it in the same function or module, just like a
+/// `macro_rules!` macro. The names in the set are resolved where it is used, not where it is
+/// defined, which is what needs the `macros` feature. `take!(use ui_deps; =title)` then expands
+/// to the same statements as `take!(=model, &assets, =title)`. Several sets can be spliced in at
+/// once with `take!(use ui_deps, net_deps; ...)`. Using a name that isn't a set fails with
+/// "cannot find macro `name` in this scope".
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (model, assets) = (vec![1, 2], String::from("icons"));
+/// capture_set! {
+///     ui_deps = (=model, &assets);
+/// }
+///
+/// let (save, open) = (String::from("save"), String::from("open"));
+/// let on_save = {
+///     take!(use ui_deps; save); // let model = model.clone(); let assets = &assets; let save = save;
+///     move || format!("{} {} {}", save, model.len(), assets)
+/// };
+/// let on_open = {
+///     take!(use ui_deps; open);
+///     move || format!("{} {} {}", open, model.len(), assets)
+/// };
+/// assert_eq!((on_save(), on_open()), ("save 2 icons".into(), "open 2 icons".into()));
+/// # }
+/// ```
+#[cfg(feature = "macros")]
+#[macro_export]
+macro_rules! capture_set {
+    [@define ($d:tt) $($name:ident = ($($entry:tt)*));+] => {
+        $(
+            #[allow(unused_macros)]
+            macro_rules! $name {
+                (@taken_capture_set $d set:ident) => {
+                    $crate::__take_set!([$crate::take] $d set $($entry)*);
+                };
+            }
+        )+
+    };
+    [$($name:ident = ($($entry:tt)*));+ $(;)?] => {
+        $crate::capture_set![@define ($) $($name = ($($entry)*));+];
+    };
+}
+
+/// Share a value between several owners by wrapping it in an `Arc`.
+///
+/// The original binding is replaced by an `Arc` and every name after the `=>` gets its own
+/// clone of that `Arc`, ready to be moved into a thread or closure.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let data = vec![1, 2, 3];
+/// share!(data => worker_a, worker_b);
+/// // let data = Arc::new(data);
+/// // let worker_a = Arc::clone(&data);
+/// // let worker_b = Arc::clone(&data);
+/// assert_eq!(*worker_a, *data);
+/// assert_eq!(*worker_b, *data);
+/// # }
+/// ```
+///
+/// ## Usecase: Threads
+/// Handing out the same data to several threads is the main use of `share!`.
+///
+/// ```rust
+/// use std::thread::spawn;
+/// #[macro_use] extern crate taken;
+///
+/// # fn main() {
+/// let config = String::from("verbose");
+/// share!(config => for_a, for_b);
+/// let a = spawn(move || for_a.len());
+/// let b = spawn(move || for_b.len());
+/// assert_eq!(a.join().unwrap(), config.len());
+/// assert_eq!(b.join().unwrap(), config.len());
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! share {
+    [$var:ident => $($to:ident),+] => {
+        let $var = $crate::__private::Arc::new($var);
+        $(
+            let $to = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);
+        )+
+    };
+    [$var:ident => $($to:ident,)+] => {
+        $crate::share![$var => $($to),+]
+    };
+}
+
+/// Leak heap allocated values, turning them into `&'static mut` references.
+///
+/// `leak!(x)` expands to `let x: &'static mut _ = Box::leak(Box::new(x));`. The usual `mut` and
+/// `as` forms are supported.
+///
+/// # Safety Note
+/// This is not `unsafe`, but the memory is **never freed** and the destructor of the value is
+/// never run. Only use it for values that should live for the rest of the program, such as
+/// configuration handed to long running threads or to FFI code.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// fn needs_static(config: &'static str) -> usize { config.len() }
+///
+/// let (config, counter) = (String::from("verbose"), 0);
+/// leak!(
+///     config,      // let config: &'static mut _ = Box::leak(Box::new(config));
+///     mut counter, // let mut counter: &'static mut _ = Box::leak(Box::new(counter));
+/// );
+/// *counter += needs_static(config);
+/// assert_eq!(*counter, 7);
+/// # }
+/// ```
+///
+/// Use [`leak_box!`](macro.leak_box.html) for values that are already boxed.
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! leak {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var: &'static mut _ = $crate::__private::Box::leak($crate::__private::Box::new($var));
+        $crate::leak![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v: &'static mut _ = $crate::__private::Box::leak($crate::__private::Box::new($var));
+        $crate::leak![$($rest)*]
+    };
+    [mut $var:ident, $($rest:tt)*] => {
+        let mut $var: &'static mut _ = $crate::__private::Box::leak($crate::__private::Box::new($var));
+        $crate::leak![$($rest)*]
+    };
+    [mut $var:ident as $v:ident, $($rest:tt)*] => {
+        let mut $v: &'static mut _ = $crate::__private::Box::leak($crate::__private::Box::new($var));
+        $crate::leak![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var: &'static mut _ = $crate::__private::Box::leak($crate::__private::Box::new($var));
+    };
+    [$var:ident as $v:ident] => {
+        let $v: &'static mut _ = $crate::__private::Box::leak($crate::__private::Box::new($var));
+    };
+    [mut $var:ident] => {
+        let mut $var: &'static mut _ = $crate::__private::Box::leak($crate::__private::Box::new($var));
+    };
+    [mut $var:ident as $v:ident] => {
+        let mut $v: &'static mut _ = $crate::__private::Box::leak($crate::__private::Box::new($var));
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Leak values that are already a `Box<T>`, turning them into `&'static mut T` references.
+///
+/// `leak_box!(x)` expands to `let x: &'static mut _ = Box::leak(x);`, avoiding boxing the value
+/// a second time. See [`leak!`](macro.leak.html) for the supported forms and the safety note.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let names: Box<[&str]> = vec!["a", "b"].into_boxed_slice();
+/// leak_box!(names as static_names);
+/// let names: &'static [&str] = static_names;
+/// assert_eq!(names.len(), 2);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! leak_box {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var: &'static mut _ = $crate::__private::Box::leak($var);
+        $crate::leak_box![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v: &'static mut _ = $crate::__private::Box::leak($var);
+        $crate::leak_box![$($rest)*]
+    };
+    [mut $var:ident, $($rest:tt)*] => {
+        let mut $var: &'static mut _ = $crate::__private::Box::leak($var);
+        $crate::leak_box![$($rest)*]
+    };
+    [mut $var:ident as $v:ident, $($rest:tt)*] => {
+        let mut $v: &'static mut _ = $crate::__private::Box::leak($var);
+        $crate::leak_box![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var: &'static mut _ = $crate::__private::Box::leak($var);
+    };
+    [$var:ident as $v:ident] => {
+        let $v: &'static mut _ = $crate::__private::Box::leak($var);
+    };
+    [mut $var:ident] => {
+        let mut $var: &'static mut _ = $crate::__private::Box::leak($var);
+    };
+    [mut $var:ident as $v:ident] => {
+        let mut $v: &'static mut _ = $crate::__private::Box::leak($var);
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Move values into a `Box`, `Arc`, `Rc` or `Mutex`, picked by a type hint.
+///
+/// `move_into!(x: Arc<_>)` expands to `let x: Arc<_> = Arc::new(x);`, and likewise for `Box`, `Rc`
+/// and `Mutex`. The type inside the wrapper can be `_` or anything that the value coerces to, such
+/// as a trait object. `mut` and `as` apply to the wrapped value. `Mutex` needs the `std` feature.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// # #[cfg(feature = "std")] {
+/// use std::thread::spawn;
+///
+/// let (counter, label, callback) = (0u32, "count", || 42);
+/// move_into!(
+///     counter: Mutex<_>,              // let counter: Mutex<_> = Mutex::new(counter);
+///     label as shared: Arc<_>,        // let shared: Arc<_> = Arc::new(label);
+///     callback: Box<dyn Fn() -> u32>, // let callback: Box<dyn Fn() -> u32> = Box::new(callback);
+/// );
+/// move_into!(counter: Arc<_>); // the wrappers don't have to be imported
+/// let th = spawn({
+///     let (counter, shared) = (counter.clone(), shared.clone());
+///     move || *counter.lock().unwrap() += shared.len() as u32
+/// });
+/// th.join().unwrap();
+/// assert_eq!(*counter.lock().unwrap() + callback(), 47);
+/// # }
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! move_into {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident: $w:ident<$inner:ty>, $($rest:tt)*] => {
+        $crate::move_into![$var: $w<$inner>];
+        $crate::move_into![$($rest)*]
+    };
+    [$var:ident as $v:ident: $w:ident<$inner:ty>, $($rest:tt)*] => {
+        $crate::move_into![$var as $v: $w<$inner>];
+        $crate::move_into![$($rest)*]
+    };
+    [mut $var:ident: $w:ident<$inner:ty>, $($rest:tt)*] => {
+        $crate::move_into![mut $var: $w<$inner>];
+        $crate::move_into![$($rest)*]
+    };
+    [mut $var:ident as $v:ident: $w:ident<$inner:ty>, $($rest:tt)*] => {
+        $crate::move_into![mut $var as $v: $w<$inner>];
+        $crate::move_into![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident: $w:ident<$inner:ty>] => {
+        $crate::move_into![@let () $var $var $w<$inner>];
+    };
+    [$var:ident as $v:ident: $w:ident<$inner:ty>] => {
+        $crate::move_into![@let () $var $v $w<$inner>];
+    };
+    [mut $var:ident: $w:ident<$inner:ty>] => {
+        $crate::move_into![@let (mut) $var $var $w<$inner>];
+    };
+    [mut $var:ident as $v:ident: $w:ident<$inner:ty>] => {
+        $crate::move_into![@let (mut) $var $v $w<$inner>];
+    };
+
+    // the wrappers
+    [@let ($($mut:tt)?) $var:ident $v:ident Box<$inner:ty>] => {
+        let $($mut)? $v: $crate::__private::Box<$inner> = $crate::__private::Box::new($var);
+    };
+    [@let ($($mut:tt)?) $var:ident $v:ident Arc<$inner:ty>] => {
+        let $($mut)? $v: $crate::__private::Arc<$inner> = $crate::__private::Arc::new($var);
+    };
+    [@let ($($mut:tt)?) $var:ident $v:ident Rc<$inner:ty>] => {
+        let $($mut)? $v: $crate::__private::Rc<$inner> = $crate::__private::Rc::new($var);
+    };
+    [@let ($($mut:tt)?) $var:ident $v:ident Mutex<$inner:ty>] => {
+        let $($mut)? $v: $crate::__private::Mutex<$inner> = $crate::__private::Mutex::new($var);
+    };
+    [@let ($($mut:tt)?) $var:ident $v:ident $w:ident<$inner:ty>] => {
+        compile_error!(concat!(
+            "`move_into!` can't move into `", stringify!($w), "`, expected `Box`, `Arc`, `Rc` ",
+            "or `Mutex`"
+        ));
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Move values into a `Mutex`.
+///
+/// `take_into_mutex!(x)` expands to `let x = Mutex::new(x);`, and `take_into_mutex!(x as m)` to
+/// `let m = Mutex::new(x);`. To share the `Mutex` between threads it still has to be put in an
+/// `Arc`, e.g. with `move_into!(x: Arc<_>)`; [`take_shared!`](macro.take_shared.html) does both
+/// at once.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::thread;
+///
+/// let (hits, misses) = (0u32, vec![]);
+/// take_into_mutex!(hits, misses as missed); // let hits = Mutex::new(hits); ...
+/// thread::scope(|s| {
+///     s.spawn(|| *hits.lock().unwrap() += 1);
+///     s.spawn(|| missed.lock().unwrap().push("key"));
+/// });
+/// assert_eq!((hits.into_inner().unwrap(), missed.into_inner().unwrap()), (1, vec!["key"]));
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! take_into_mutex {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = $crate::__private::Mutex::new($var);
+        $crate::take_into_mutex![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = $crate::__private::Mutex::new($var);
+        $crate::take_into_mutex![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = $crate::__private::Mutex::new($var);
+    };
+    [$var:ident as $v:ident] => {
+        let $v = $crate::__private::Mutex::new($var);
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Move values into an `Arc<Mutex<_>>` to share them mutably between threads.
+///
+/// `take_shared!(x)` expands to `let x = Arc::new(Mutex::new(x));`, and `take_shared!(x as s)` to
+/// `let s = Arc::new(Mutex::new(x));`. Use [`take_shared_rw!`](macro.take_shared_rw.html) for an
+/// `RwLock`, and [`take_arc_clone!`](macro.take_arc_clone.html) to hand out the clones.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::thread::spawn;
+///
+/// let log = vec![];
+/// take_shared!(log); // let log = Arc::new(Mutex::new(log));
+/// let threads: Vec<_> = (0..2)
+///     .map(|i| {
+///         take_arc_clone!(log);
+///         spawn(move || log.lock().unwrap().push(i))
+///     })
+///     .collect();
+/// threads.into_iter().for_each(|th| th.join().unwrap());
+/// log.lock().unwrap().sort();
+/// assert_eq!(*log.lock().unwrap(), vec![0, 1]);
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! take_shared {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = $crate::__private::Arc::new($crate::__private::Mutex::new($var));
+        $crate::take_shared![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = $crate::__private::Arc::new($crate::__private::Mutex::new($var));
+        $crate::take_shared![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = $crate::__private::Arc::new($crate::__private::Mutex::new($var));
+    };
+    [$var:ident as $v:ident] => {
+        let $v = $crate::__private::Arc::new($crate::__private::Mutex::new($var));
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Move values into an `Arc<RwLock<_>>` to share them between threads that mostly read them.
+///
+/// `take_shared_rw!(x)` expands to `let x = Arc::new(RwLock::new(x));`, with the same forms as
+/// [`take_shared!`](macro.take_shared.html).
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::thread::spawn;
+///
+/// let config = String::from("verbose");
+/// take_shared_rw!(config as shared); // let shared = Arc::new(RwLock::new(config));
+/// let reader = spawn({
+///     take_arc_clone!(shared);
+///     move || shared.read().unwrap().len()
+/// });
+/// assert_eq!(reader.join().unwrap(), 7);
+/// shared.write().unwrap().push_str(",color");
+/// assert_eq!(*shared.read().unwrap(), "verbose,color");
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! take_shared_rw {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = $crate::__private::Arc::new($crate::__private::RwLock::new($var));
+        $crate::take_shared_rw![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = $crate::__private::Arc::new($crate::__private::RwLock::new($var));
+        $crate::take_shared_rw![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = $crate::__private::Arc::new($crate::__private::RwLock::new($var));
+    };
+    [$var:ident as $v:ident] => {
+        let $v = $crate::__private::Arc::new($crate::__private::RwLock::new($var));
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Move values into a `Box`.
+///
+/// `take_box!(x)` expands to `let x = Box::new(x);`, and `take_box!(mut x as b)` to
+/// `let mut b = Box::new(x);`. Use [`move_into!`](macro.move_into.html) to box into a trait
+/// object, and [`unbox!`](macro.unbox.html) to move the value back out.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (state, big) = ([0u8; 64], vec![1, 2]);
+/// take_box!(state, mut big as boxed); // let state = Box::new(state); ...
+/// boxed.push(3);
+/// assert_eq!((state.len(), *boxed), (64, vec![1, 2, 3]));
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_box {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = $crate::__private::Box::new($var);
+        $crate::take_box![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = $crate::__private::Box::new($var);
+        $crate::take_box![$($rest)*]
+    };
+
+    [mut $var:ident, $($rest:tt)*] => {
+        let mut $var = $crate::__private::Box::new($var);
+        $crate::take_box![$($rest)*]
+    };
+    [mut $var:ident as $v:ident, $($rest:tt)*] => {
+        let mut $v = $crate::__private::Box::new($var);
+        $crate::take_box![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = $crate::__private::Box::new($var);
+    };
+    [$var:ident as $v:ident] => {
+        let $v = $crate::__private::Box::new($var);
+    };
+
+    [mut $var:ident] => {
+        let mut $var = $crate::__private::Box::new($var);
+    };
+    [mut $var:ident as $v:ident] => {
+        let mut $v = $crate::__private::Box::new($var);
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Move values out of a `Box`.
+///
+/// `unbox!(x)` expands to `let x = *x;`, the same as `take!(*x)`. Moving out of a `Box` is
+/// allowed, unlike moving out of other smart pointers, and frees the allocation. The value has to
+/// be `Sized`, so a `Box<dyn Trait>` or `Box<[T]>` can't be unboxed. `mut` and `as` are supported,
+/// and [`unbox_clone!`](macro.unbox_clone.html) clones the value instead, keeping the `Box`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (name, list) = (Box::new(String::from("liam")), Box::new(vec![1]));
+/// unbox!(name, mut list as items); // let name = *name; let mut items = *list;
+/// items.push(2);
+/// let _: (String, Vec<i32>) = (name, items);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! unbox {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = *$var;
+        $crate::unbox![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = *$var;
+        $crate::unbox![$($rest)*]
+    };
+
+    [mut $var:ident, $($rest:tt)*] => {
+        let mut $var = *$var;
+        $crate::unbox![$($rest)*]
+    };
+    [mut $var:ident as $v:ident, $($rest:tt)*] => {
+        let mut $v = *$var;
+        $crate::unbox![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = *$var;
+    };
+    [$var:ident as $v:ident] => {
+        let $v = *$var;
+    };
+
+    [mut $var:ident] => {
+        let mut $var = *$var;
+    };
+    [mut $var:ident as $v:ident] => {
+        let mut $v = *$var;
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Clone the values inside `Box`es, keeping the `Box`es.
+///
+/// `unbox_clone!(x as v)` expands to `let v = (*x).clone();`, the `Box` spelling of
+/// [`take_deref_clone!`](macro.take_deref_clone.html). Without `as`, `x` is shadowed by the
+/// clone, and the `Box` is only kept alive until the end of the scope. `mut` is supported.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let template = Box::new(vec![String::from("header")]);
+/// unbox_clone!(mut template as page); // let mut page = (*template).clone();
+/// page.push(String::from("body"));
+/// assert_eq!((template.len(), page.len()), (1, 2));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! unbox_clone {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = $crate::__take_clone!(*$var);
+        $crate::unbox_clone![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = $crate::__take_clone!(*$var);
+        $crate::unbox_clone![$($rest)*]
+    };
+
+    [mut $var:ident, $($rest:tt)*] => {
+        let mut $var = $crate::__take_clone!(*$var);
+        $crate::unbox_clone![$($rest)*]
+    };
+    [mut $var:ident as $v:ident, $($rest:tt)*] => {
+        let mut $v = $crate::__take_clone!(*$var);
+        $crate::unbox_clone![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = $crate::__take_clone!(*$var);
+    };
+    [$var:ident as $v:ident] => {
+        let $v = $crate::__take_clone!(*$var);
+    };
+
+    [mut $var:ident] => {
+        let mut $var = $crate::__take_clone!(*$var);
+    };
+    [mut $var:ident as $v:ident] => {
+        let mut $v = $crate::__take_clone!(*$var);
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Clone an `Arc`, making it obvious that only the reference count is incremented.
+///
+/// `take!(=handle)` works just as well, but reads like the inner data is being cloned.
+/// `take_arc_clone!(handle)` expands to `let handle = Arc::clone(&handle);`, the form recommended
+/// by clippy. It fails to compile if `handle` is not an `Arc`. The usual `mut` and `as` forms are
+/// supported, as is `as [a, b]` to clone into several names.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::Arc;
+/// use std::thread::spawn;
+///
+/// let handle = Arc::new(vec![1, 2, 3]);
+/// let th = spawn({
+///     take_arc_clone!(handle as for_thread); // let for_thread = Arc::clone(&handle);
+///     move || for_thread.len()
+/// });
+/// assert_eq!(th.join().unwrap(), 3);
+/// assert_eq!(Arc::strong_count(&handle), 1);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_arc_clone {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);
+        $crate::take_arc_clone![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);
+        $crate::take_arc_clone![$($rest)*]
+    };
+
+    [mut $var:ident, $($rest:tt)*] => {
+        let mut $var = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);
+        $crate::take_arc_clone![$($rest)*]
+    };
+    [mut $var:ident as $v:ident, $($rest:tt)*] => {
+        let mut $v = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);
+        $crate::take_arc_clone![$($rest)*]
+    };
+
+    [$var:ident as [$($v:ident),+ $(,)?], $($rest:tt)*] => {
+        $(let $v = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);)+
+        $crate::take_arc_clone![$($rest)*]
+    };
+    [mut $var:ident as [$($v:ident),+ $(,)?], $($rest:tt)*] => {
+        $(let mut $v = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);)+
+        $crate::take_arc_clone![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);
+    };
+    [$var:ident as $v:ident] => {
+        let $v = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);
+    };
+
+    [mut $var:ident] => {
+        let mut $var = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);
+    };
+    [mut $var:ident as $v:ident] => {
+        let mut $v = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);
+    };
+
+    [$var:ident as [$($v:ident),+ $(,)?]] => {
+        $(let $v = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);)+
+    };
+    [mut $var:ident as [$($v:ident),+ $(,)?]] => {
+        $(let mut $v = <$crate::__private::Arc<_> as $crate::__private::Clone>::clone(&$var);)+
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Clone an `Rc`, making it obvious that only the reference count is incremented.
+///
+/// The single threaded sibling of [`take_arc_clone!`](macro.take_arc_clone.html):
+/// `take_rc_clone!(handle)` expands to `let handle = Rc::clone(&handle);` and fails to compile if
+/// `handle` is not an `Rc`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::rc::Rc;
+///
+/// let handle = Rc::new(String::from("shared"));
+/// let closure = {
+///     take_rc_clone!(handle); // let handle = Rc::clone(&handle);
+///     move || handle.len()
+/// };
+/// assert_eq!(Rc::strong_count(&handle), 2);
+/// assert_eq!(closure(), 6);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_rc_clone {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);
+        $crate::take_rc_clone![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);
+        $crate::take_rc_clone![$($rest)*]
+    };
+
+    [mut $var:ident, $($rest:tt)*] => {
+        let mut $var = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);
+        $crate::take_rc_clone![$($rest)*]
+    };
+    [mut $var:ident as $v:ident, $($rest:tt)*] => {
+        let mut $v = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);
+        $crate::take_rc_clone![$($rest)*]
+    };
+
+    [$var:ident as [$($v:ident),+ $(,)?], $($rest:tt)*] => {
+        $(let $v = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);)+
+        $crate::take_rc_clone![$($rest)*]
+    };
+    [mut $var:ident as [$($v:ident),+ $(,)?], $($rest:tt)*] => {
+        $(let mut $v = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);)+
+        $crate::take_rc_clone![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);
+    };
+    [$var:ident as $v:ident] => {
+        let $v = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);
+    };
+
+    [mut $var:ident] => {
+        let mut $var = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);
+    };
+    [mut $var:ident as $v:ident] => {
+        let mut $v = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);
+    };
+
+    [$var:ident as [$($v:ident),+ $(,)?]] => {
+        $(let $v = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);)+
+    };
+    [mut $var:ident as [$($v:ident),+ $(,)?]] => {
+        $(let mut $v = <$crate::__private::Rc<_> as $crate::__private::Clone>::clone(&$var);)+
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Clone any reference counted handle: an `Arc`, an `Rc` or your own type implementing
+/// [`RefCounted`](trait.RefCounted.html).
+///
+/// [`take_arc_clone!`](macro.take_arc_clone.html) and [`take_rc_clone!`](macro.take_rc_clone.html)
+/// only accept their own pointer. `take_ref_counted!(handle)` expands to
+/// `let handle = RefCounted::ref_clone(&handle);`, so one macro covers all of them, including
+/// interned strings and arena handles from other crates. It supports the same forms as
+/// `take_arc_clone!`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use taken::RefCounted;
+///
+/// struct Interned(&'static str);
+///
+/// impl RefCounted for Interned {
+///     fn ref_clone(&self) -> Self {
+///         Interned(self.0)
+///     }
+/// }
+///
+/// let name = Interned("name");
+/// # #[cfg(feature = "alloc")] {
+/// let config = std::rc::Rc::new(vec![1, 2]);
+/// let closure = {
+///     take_ref_counted!(name as label, config); // let config = RefCounted::ref_clone(&config);
+///     move || (label.0, config.len())
+/// };
+/// assert_eq!(closure(), ("name", 2));
+/// # }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_ref_counted {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = $crate::RefCounted::ref_clone(&$var);
+        $crate::take_ref_counted![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = $crate::RefCounted::ref_clone(&$var);
+        $crate::take_ref_counted![$($rest)*]
+    };
+
+    [mut $var:ident, $($rest:tt)*] => {
+        let mut $var = $crate::RefCounted::ref_clone(&$var);
+        $crate::take_ref_counted![$($rest)*]
+    };
+    [mut $var:ident as $v:ident, $($rest:tt)*] => {
+        let mut $v = $crate::RefCounted::ref_clone(&$var);
+        $crate::take_ref_counted![$($rest)*]
+    };
+
+    [$var:ident as [$($v:ident),+ $(,)?], $($rest:tt)*] => {
+        $(let $v = $crate::RefCounted::ref_clone(&$var);)+
+        $crate::take_ref_counted![$($rest)*]
+    };
+    [mut $var:ident as [$($v:ident),+ $(,)?], $($rest:tt)*] => {
+        $(let mut $v = $crate::RefCounted::ref_clone(&$var);)+
+        $crate::take_ref_counted![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = $crate::RefCounted::ref_clone(&$var);
+    };
+    [$var:ident as $v:ident] => {
+        let $v = $crate::RefCounted::ref_clone(&$var);
+    };
+
+    [mut $var:ident] => {
+        let mut $var = $crate::RefCounted::ref_clone(&$var);
+    };
+    [mut $var:ident as $v:ident] => {
+        let mut $v = $crate::RefCounted::ref_clone(&$var);
+    };
+
+    [$var:ident as [$($v:ident),+ $(,)?]] => {
+        $(let $v = $crate::RefCounted::ref_clone(&$var);)+
+    };
+    [mut $var:ident as [$($v:ident),+ $(,)?]] => {
+        $(let mut $v = $crate::RefCounted::ref_clone(&$var);)+
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Downgrade an `Arc` into a `Weak` for a callback, keeping the `Arc` itself.
+///
+/// `take_arc_weak!(handle as weak)` expands to `let weak = Arc::downgrade(&handle);`. The new name
+/// is required, so that `handle` keeps being the strong reference. Use
+/// [`take_weak!`](macro.take_weak.html) to replace `handle` with its `Weak` instead. It fails to
+/// compile if `handle` is not an `Arc`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::{Arc, Mutex};
+///
+/// let label = Arc::new(Mutex::new(String::from("idle")));
+/// let on_click = {
+///     take_arc_weak!(label as weak_label); // let weak_label = Arc::downgrade(&label);
+///     move || {
+///         if let Some(label) = weak_label.upgrade() {
+///             *label.lock().unwrap() = String::from("clicked");
+///         }
+///     }
+/// };
+/// on_click();
+/// assert_eq!(*label.lock().unwrap(), "clicked");
+/// drop(label);
+/// on_click(); // the label is gone, nothing happens
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_arc_weak {
+    [$($var:ident as $v:ident),* $(,)?] => {
+        $(let $v = $crate::__private::Arc::downgrade(&$var);)*
+    };
+    [$($entry:tt)*] => {
+        compile_error!(concat!(
+            "`take_arc_weak!` entries are `handle as weak`, found `", stringify!($($entry)*), "`. ",
+            "Use `take_weak!` to replace `handle` with its `Weak`."
+        ));
+    };
+}
+
+/// Replace an `Arc` with its `Weak`.
+///
+/// `take_weak!(handle)` expands to `let handle = Arc::downgrade(&{ handle });`, which moves the
+/// `Arc` and drops it once downgraded. The strong reference has to be kept somewhere else, e.g. by
+/// the caller, or the `Weak` can never be upgraded.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::Arc;
+///
+/// let owner = Arc::new(5);
+/// let handle = Arc::clone(&owner);
+/// let read = {
+///     take_weak!(handle); // let handle = Arc::downgrade(&{ handle });
+///     move || handle.upgrade().map(|v| *v)
+/// };
+/// assert_eq!(read(), Some(5));
+/// drop(owner);
+/// assert_eq!(read(), None);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_weak {
+    [$($var:ident),* $(,)?] => {
+        $(let $var = $crate::__private::Arc::downgrade(&{ $var });)*
+    };
+    [$($entry:tt)*] => {
+        compile_error!(concat!(
+            "`take_weak!` entries are names of `Arc`s, found `", stringify!($($entry)*), "`. ",
+            "Use `take_arc_weak!(handle as weak)` to keep `handle`."
+        ));
+    };
+}
+
+/// Downgrade an `Rc` into a `Weak` for a callback, keeping the `Rc` itself.
+///
+/// The single threaded sibling of [`take_arc_weak!`](macro.take_arc_weak.html):
+/// `take_rc_weak!(handle as weak)` expands to `let weak = Rc::downgrade(&handle);` and fails to
+/// compile if `handle` is not an `Rc`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+///
+/// let clicks = Rc::new(RefCell::new(0));
+/// let on_click = {
+///     take_rc_weak!(clicks as weak_clicks); // let weak_clicks = Rc::downgrade(&clicks);
+///     move || weak_clicks.upgrade().map(|c| *c.borrow_mut() += 1)
+/// };
+/// on_click();
+/// assert_eq!((*clicks.borrow(), Rc::strong_count(&clicks)), (1, 1));
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_rc_weak {
+    [$($var:ident as $v:ident),* $(,)?] => {
+        $(let $v = $crate::__private::Rc::downgrade(&$var);)*
+    };
+    [$($entry:tt)*] => {
+        compile_error!(concat!(
+            "`take_rc_weak!` entries are `handle as weak`, found `", stringify!($($entry)*), "`. ",
+            "Use `take_weak_rc!` to replace `handle` with its `Weak`."
+        ));
+    };
+}
+
+/// Replace an `Rc` with its `Weak`.
+///
+/// The single threaded sibling of [`take_weak!`](macro.take_weak.html): `take_weak_rc!(handle)`
+/// expands to `let handle = Rc::downgrade(&{ handle });`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::rc::Rc;
+///
+/// let owner = Rc::new(String::from("parent"));
+/// let parent = Rc::clone(&owner);
+/// take_weak_rc!(parent); // let parent = Rc::downgrade(&{ parent });
+/// assert_eq!(Rc::strong_count(&owner), 1);
+/// assert_eq!(parent.upgrade().as_deref().map(String::as_str), Some("parent"));
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_weak_rc {
+    [$($var:ident),* $(,)?] => {
+        $(let $var = $crate::__private::Rc::downgrade(&{ $var });)*
+    };
+    [$($entry:tt)*] => {
+        compile_error!(concat!(
+            "`take_weak_rc!` entries are names of `Rc`s, found `", stringify!($($entry)*), "`. ",
+            "Use `take_rc_weak!(handle as weak)` to keep `handle`."
+        ));
+    };
+}
+
+/// Clone the value behind a smart pointer or reference.
+///
+/// `take_deref_clone!(x as s)` expands to `let s = (*x).clone();`, the same as `take!(=*x as s)`.
+/// For an `Arc<String>` this gives a standalone `String`, where `take_arc_clone!` would give
+/// another `Arc<String>`. The usual `mut` and `as` forms are supported.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::Arc;
+///
+/// let config = Arc::new(String::from("verbose"));
+/// let mut edit = {
+///     take_deref_clone!(mut config as flags); // let mut flags = (*config).clone();
+///     move || {
+///         flags.push_str(",color");
+///         flags
+///     }
+/// };
+/// assert_eq!(edit(), "verbose,color");
+/// assert_eq!(*config, "verbose");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_deref_clone {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = $crate::__take_clone!(*$var);
+        $crate::take_deref_clone![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = $crate::__take_clone!(*$var);
+        $crate::take_deref_clone![$($rest)*]
+    };
+
+    [mut $var:ident, $($rest:tt)*] => {
+        let mut $var = $crate::__take_clone!(*$var);
+        $crate::take_deref_clone![$($rest)*]
+    };
+    [mut $var:ident as $v:ident, $($rest:tt)*] => {
+        let mut $v = $crate::__take_clone!(*$var);
+        $crate::take_deref_clone![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = $crate::__take_clone!(*$var);
+    };
+    [$var:ident as $v:ident] => {
+        let $v = $crate::__take_clone!(*$var);
+    };
+
+    [mut $var:ident] => {
+        let mut $var = $crate::__take_clone!(*$var);
+    };
+    [mut $var:ident as $v:ident] => {
+        let mut $v = $crate::__take_clone!(*$var);
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Take the elements of a tuple, each in its own way.
+///
+/// `take_tuple!((conn, =cache, &logger) = get_resources())` evaluates `get_resources()` once into
+/// a temporary, then takes each element with a `take!` sigil: `conn` is moved out, `cache` is a
+/// clone and `logger` a reference into the temporary, which lives until the end of the enclosing
+/// scope but can't be named. Elements are moves, `mut`, `=`, `=mut`, `&`, `&mut`, `ref`,
+/// `ref mut` or `_` to skip one, and a nested tuple is taken with a nested list.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// fn get_resources() -> (String, Vec<u8>, (u32, String)) {
+///     (String::from("conn"), vec![1, 2], (3, String::from("log")))
+/// }
+/// take_tuple!((conn, =cache, (mut retries, &logger)) = get_resources());
+/// // let mut tuple = get_resources();
+/// // let (conn, ref cache, (mut retries, ref logger)) = tuple;
+/// // let cache = cache.clone();
+/// retries += cache.len() as u32;
+/// assert_eq!((conn.as_str(), retries, logger.as_str()), ("conn", 5, "log"));
+/// # }
+/// ```
+///
+/// Unlike `take!(..tuple as (...))`, which moves every element out first, the references and clones
+/// are taken from the tuple itself.
+#[macro_export]
+macro_rules! take_tuple {
+    [($($entry:tt)*) = $value:expr] => {
+        #[allow(unused_mut)]
+        let mut __taken_tuple = $value;
+        let $crate::take_tuple![@pat [] $($entry)*] = __taken_tuple;
+        $crate::take_tuple![@clones $($entry)*];
+    };
+
+    // The pattern binds references for the entries that borrow or clone, so that nothing else is
+    // moved out of the tuple.
+    [@pat [$($acc:tt)*] =mut $var:ident $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [ref $var]] $($($rest)*)?]
+    };
+    [@pat [$($acc:tt)*] =$var:ident $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [ref $var]] $($($rest)*)?]
+    };
+    [@pat [$($acc:tt)*] &mut $var:ident $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [ref mut $var]] $($($rest)*)?]
+    };
+    [@pat [$($acc:tt)*] &$var:ident $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [ref $var]] $($($rest)*)?]
+    };
+    [@pat [$($acc:tt)*] ref mut $var:ident $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [ref mut $var]] $($($rest)*)?]
+    };
+    [@pat [$($acc:tt)*] ref $var:ident $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [ref $var]] $($($rest)*)?]
+    };
+    [@pat [$($acc:tt)*] mut $var:ident $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [mut $var]] $($($rest)*)?]
+    };
+    [@pat [$($acc:tt)*] _ $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [_]] $($($rest)*)?]
+    };
+    [@pat [$($acc:tt)*] $var:ident $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [$var]] $($($rest)*)?]
+    };
+    [@pat [$($acc:tt)*] ($($inner:tt)*) $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@pat [$($acc)* [$crate::take_tuple![@pat [] $($inner)*]]] $($($rest)*)?]
+    };
+    [@pat [$([$($pat:tt)*])*]] => {
+        ($($($pat)*,)*)
+    };
+    [@pat [$($acc:tt)*] $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take_tuple!` element `", stringify!($($entry)*), "`: expected a name with ",
+            "one of the sigils `mut`, `=`, `=mut`, `&`, `&mut`, `ref` or `ref mut`, `_`, or a ",
+            "nested tuple"
+        ))
+    };
+
+    [@clones =mut $var:ident $(, $($rest:tt)*)?] => {
+        let mut $var = $crate::__take_clone!($var);
+        $crate::take_tuple![@clones $($($rest)*)?]
+    };
+    [@clones =$var:ident $(, $($rest:tt)*)?] => {
+        let $var = $crate::__take_clone!($var);
+        $crate::take_tuple![@clones $($($rest)*)?]
+    };
+    [@clones ($($inner:tt)*) $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@clones $($inner)*];
+        $crate::take_tuple![@clones $($($rest)*)?]
+    };
+    [@clones $($skip:ident)+ $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@clones $($($rest)*)?]
+    };
+    [@clones & $($skip:ident)+ $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@clones $($($rest)*)?]
+    };
+    [@clones _ $(, $($rest:tt)*)?] => {
+        $crate::take_tuple![@clones $($($rest)*)?]
+    };
+    [@clones] => {};
+    // invalid elements are reported by `@pat`
+    [@clones $($entry:tt)*] => {};
+}
+
+/// Take fields of a struct into standalone bindings.
+///
+/// `take_field!(s => x, =y)` expands to `let x = s.x; let y = s.y.clone();`. Each field takes the
+/// sigils of a `take!` entry (`mut`, `=`, `=mut`, `&`, `&mut`, `ref` and `ref mut`) and can be
+/// renamed with `as`. With a borrowed source, `take_field!(&mut s => x, y)` borrows every field
+/// instead: `let x = &mut s.x; let y = &mut s.y;`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// struct Request {
+///     id: u32,
+///     path: String,
+///     headers: Vec<String>,
+/// }
+/// let req = Request { id: 7, path: String::from("/"), headers: vec![] };
+/// take_field!(req => id, =path as route, mut headers); // let id = req.id; ...
+/// headers.push(route);
+/// assert_eq!((id, req.path.as_str(), headers), (7, "/", vec![String::from("/")]));
+///
+/// let mut req = Request { id: 8, path: String::from("/a"), headers: vec![] };
+/// take_field!(&mut req => id as n, headers); // let n = &mut req.id; let headers = &mut req.headers;
+/// *n += 1;
+/// headers.push(String::from("x"));
+/// assert_eq!((req.id, req.headers.len()), (9, 1));
+/// # }
+/// ```
+///
+/// A call that moves some fields out of the struct and borrows others fails to compile: the
+/// struct is partially moved afterwards, so the borrows can't outlive the call site in any useful
+/// way. Clone the moved fields with `=`, or use two calls.
+#[macro_export]
+macro_rules! take_field {
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*] mut $f:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_field![@let [mut] $src $f $($v)?];
+        $crate::take_field![@fields $src [$($moved)* $f] [$($borrowed)*] $($($rest)*)?];
+    };
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*] =mut $f:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_field![@let [=mut] $src $f $($v)?];
+        $crate::take_field![@fields $src [$($moved)*] [$($borrowed)*] $($($rest)*)?];
+    };
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*] =$f:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_field![@let [=] $src $f $($v)?];
+        $crate::take_field![@fields $src [$($moved)*] [$($borrowed)*] $($($rest)*)?];
+    };
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*] &mut $f:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_field![@let [&mut] $src $f $($v)?];
+        $crate::take_field![@fields $src [$($moved)*] [$($borrowed)* $f] $($($rest)*)?];
+    };
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*] &$f:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_field![@let [&] $src $f $($v)?];
+        $crate::take_field![@fields $src [$($moved)*] [$($borrowed)* $f] $($($rest)*)?];
+    };
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*] ref mut $f:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_field![@let [&mut] $src $f $($v)?];
+        $crate::take_field![@fields $src [$($moved)*] [$($borrowed)* $f] $($($rest)*)?];
+    };
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*] ref $f:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_field![@let [&] $src $f $($v)?];
+        $crate::take_field![@fields $src [$($moved)*] [$($borrowed)* $f] $($($rest)*)?];
+    };
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*] $f:ident $(as $v:ident)? $(, $($rest:tt)*)?] => {
+        $crate::take_field![@let [] $src $f $($v)?];
+        $crate::take_field![@fields $src [$($moved)* $f] [$($borrowed)*] $($($rest)*)?];
+    };
+    [@fields $src:ident [$($moved:ident)+] [$($borrowed:ident)+]] => {
+        compile_error!(concat!(
+            "`take_field!` moves `", stringify!($($moved),+), "` out of `", stringify!($src),
+            "` but borrows `", stringify!($($borrowed),+), "`. Clone the moved fields with `=`, ",
+            "or take them in a separate call"
+        ));
+    };
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*]] => {};
+    [@fields $src:ident [$($moved:ident)*] [$($borrowed:ident)*] $($entry:tt)+] => {
+        compile_error!(concat!(
+            "`take_field!` entries are fields with an optional sigil and `as`, found `",
+            stringify!($($entry)+), "`"
+        ));
+    };
+
+    [@let [$($sigil:tt)*] $src:ident $f:ident] => {
+        $crate::take_field![@let [$($sigil)*] $src $f $f];
+    };
+    [@let [] $src:ident $f:ident $v:ident] => {
+        let $v = $src.$f;
+        $crate::__take_trace!("", $v);
+    };
+    [@let [mut] $src:ident $f:ident $v:ident] => {
+        let mut $v = $src.$f;
+        $crate::__take_trace!("mut", $v);
+    };
+    [@let [=] $src:ident $f:ident $v:ident] => {
+        let $v = $crate::__take_clone!($src.$f);
+        $crate::__take_trace!("=", $v);
+    };
+    [@let [=mut] $src:ident $f:ident $v:ident] => {
+        let mut $v = $crate::__take_clone!($src.$f);
+        $crate::__take_trace!("=mut", $v);
+    };
+    [@let [&] $src:ident $f:ident $v:ident] => {
+        let $v = &$src.$f;
+        $crate::__take_trace!("&", $v);
+    };
+    [@let [&mut] $src:ident $f:ident $v:ident] => {
+        let $v = &mut $src.$f;
+        $crate::__take_trace!("&mut", $v);
+    };
+
+    // a borrowed source borrows every field the same way
+    [&mut $src:ident => $($f:ident $(as $v:ident)?),+ $(,)?] => {
+        $($crate::take_field![@let [&mut] $src $f $($v)?];)+
+    };
+    [& $src:ident => $($f:ident $(as $v:ident)?),+ $(,)?] => {
+        $($crate::take_field![@let [&] $src $f $($v)?];)+
+    };
+    [$src:ident => $($entry:tt)+] => {
+        $crate::take_field![@fields $src [] [] $($entry)+];
+    };
+}
+
+/// Take the value out of a known enum variant.
+///
+/// `take_enum_variant!(x as Some(inner))` expands to
+/// `let inner = match x { Some(inner) => inner, _ => panic!(..) };`. The variant can be any tuple
+/// variant with a single field, such as `Ok(v)`, `Err(e)` or `MyEnum::Variant(data)`, and
+/// `mut x as ..` binds the extracted value mutably.
+///
+/// Instead of panicking, an `else` clause provides the value when `x` is another variant. It is
+/// either an expression, which may diverge with `return`, `break` or `continue`, or a closure that
+/// receives the whole value that didn't match. The closure's parameter is a pattern, or a name
+/// with a type as in `|other: Result<u16, ParseIntError>|`:
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// enum Event {
+///     Key(char),
+///     Click(u32, u32),
+/// }
+///
+/// let (name, port, event) = (Some(String::from("srv")), "80".parse::<u16>(), Event::Key('q'));
+/// take_enum_variant!(
+///     name as Some(name),                          // let name = match name { Some(name) => name, ...
+///     mut port as Ok(port) else |_other| 8080,     // the closure gets the `Err`
+///     event as Event::Key(key) else return,        // _ => return,
+/// );
+/// port += 1;
+/// assert_eq!((name.as_str(), port, key), ("srv", 81, 'q'));
+///
+/// let click = Event::Click(1, 2);
+/// take_enum_variant!(click as Event::Key(key) else |other| match other {
+///     Event::Click(..) => 'c',
+///     Event::Key(_) => unreachable!(),
+/// });
+/// assert_eq!(key, 'c');
+/// # }
+/// ```
+///
+/// Without `else`, the panic message names the variable and the expected variant:
+///
+/// ```rust,should_panic
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let missing: Option<u32> = None;
+/// take_enum_variant!(missing as Some(value)); // panics: `missing` is not `Some(..)`
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_enum_variant {
+    [$var:ident as $($p:ident)::+ ($v:ident) else |$e:ident: $t:ty| $body:expr $(, $($rest:tt)*)?] => {
+        let $v = $crate::take_enum_variant![@match $var [$($p)::+] $v else |$e: $t| $body];
+        $crate::take_enum_variant![$($($rest)*)?]
+    };
+    [$var:ident as $($p:ident)::+ ($v:ident) else |$e:pat_param| $body:expr $(, $($rest:tt)*)?] => {
+        let $v = $crate::take_enum_variant![@match $var [$($p)::+] $v else |$e| $body];
+        $crate::take_enum_variant![$($($rest)*)?]
+    };
+    [$var:ident as $($p:ident)::+ ($v:ident) else $else:expr $(, $($rest:tt)*)?] => {
+        let $v = $crate::take_enum_variant![@match $var [$($p)::+] $v _ => $else];
+        $crate::take_enum_variant![$($($rest)*)?]
+    };
+    [$var:ident as $($p:ident)::+ ($v:ident) $(, $($rest:tt)*)?] => {
+        let $v = $crate::take_enum_variant![@match $var [$($p)::+] $v];
+        $crate::take_enum_variant![$($($rest)*)?]
+    };
+
+    [mut $var:ident as $($p:ident)::+ ($v:ident) else |$e:ident: $t:ty| $body:expr $(, $($rest:tt)*)?] => {
+        let mut $v = $crate::take_enum_variant![@match $var [$($p)::+] $v else |$e: $t| $body];
+        $crate::take_enum_variant![$($($rest)*)?]
+    };
+    [mut $var:ident as $($p:ident)::+ ($v:ident) else |$e:pat_param| $body:expr $(, $($rest:tt)*)?] => {
+        let mut $v = $crate::take_enum_variant![@match $var [$($p)::+] $v else |$e| $body];
+        $crate::take_enum_variant![$($($rest)*)?]
+    };
+    [mut $var:ident as $($p:ident)::+ ($v:ident) else $else:expr $(, $($rest:tt)*)?] => {
+        let mut $v = $crate::take_enum_variant![@match $var [$($p)::+] $v _ => $else];
+        $crate::take_enum_variant![$($($rest)*)?]
+    };
+    [mut $var:ident as $($p:ident)::+ ($v:ident) $(, $($rest:tt)*)?] => {
+        let mut $v = $crate::take_enum_variant![@match $var [$($p)::+] $v];
+        $crate::take_enum_variant![$($($rest)*)?]
+    };
+
+    [@match $var:ident [$($p:tt)+] $v:ident else $f:expr] => {
+        match $var {
+            $($p)+($v) => $v,
+            #[allow(unreachable_patterns)]
+            other => $crate::__private::call_else($f, other),
+        }
+    };
+    [@match $var:ident [$($p:tt)+] $v:ident _ => $else:expr] => {
+        match $var {
+            $($p)+($v) => $v,
+            #[allow(unreachable_patterns)]
+            _ => $else,
+        }
+    };
+    [@match $var:ident [$first:ident $(:: $p:ident)*] $v:ident] => {
+        match $var {
+            $first $(:: $p)*($v) => $v,
+            #[allow(unreachable_patterns)]
+            _ => panic!(concat!(
+                "`", stringify!($var), "` is not `", stringify!($first), $("::", stringify!($p),)* "(..)`"
+            )),
+        }
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Name the first elements of an iterator.
+///
+/// `take_iter!(iter => first, second, ..rest)` expands to
+///
+/// ```rust,ignore
+/// let mut it = IntoIterator::into_iter(iter);
+/// let first = it.next().expect(..);
+/// let second = it.next().expect(..);
+/// let rest: Vec<_> = it.collect();
+/// ```
+///
+/// Each name can be `mut`, `_` skips an element, and a `?` after the name returns `None` from
+/// the enclosing function instead of panicking when the iterator runs out. Like in `take!`,
+/// `name as alias` (or `name? as alias`) binds the element to `alias` instead, and the panic
+/// still names `name`. The `..rest` tail
+/// collects whatever is left into a `Vec` (with the `alloc` feature), and without it the remaining
+/// elements are dropped along with the iterator.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// fn parse(row: &str) -> Option<(String, u32)> {
+///     take_iter!(row.split(',') => name?, _?, age?);
+///     Some((name.to_uppercase(), age.parse().ok()?))
+/// }
+/// assert_eq!(parse("ann,x,31"), Some((String::from("ANN"), 31)));
+/// assert_eq!(parse("bob"), None);
+///
+/// # #[cfg(feature = "alloc")] {
+/// let words = "the quick brown fox".split(' ').map(String::from);
+/// take_iter!(words => mut first, second as adjective, ..rest);
+/// first.push('!');
+/// assert_eq!((first.as_str(), adjective.as_str(), rest.len()), ("the!", "quick", 2));
+/// # }
+/// # }
+/// ```
+///
+/// Use [`take_iter_array!`](macro.take_iter_array.html) when the iterator has a fixed length.
+#[macro_export]
+macro_rules! take_iter {
+    [@entries $it:ident _ ? $(, $($rest:tt)*)?] => {
+        $crate::__private::Iterator::next(&mut $it)?;
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident _ $(, $($rest:tt)*)?] => {
+        $crate::take_iter![@next $it _];
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident $v:ident ? $(, $($rest:tt)*)?] => {
+        let $v = $crate::__private::Iterator::next(&mut $it)?;
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident mut $v:ident ? $(, $($rest:tt)*)?] => {
+        let mut $v = $crate::__private::Iterator::next(&mut $it)?;
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident $v:ident $(, $($rest:tt)*)?] => {
+        let $v = $crate::take_iter![@next $it $v];
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident mut $v:ident $(, $($rest:tt)*)?] => {
+        let mut $v = $crate::take_iter![@next $it $v];
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident $v:ident ? as $w:ident $(, $($rest:tt)*)?] => {
+        let $w = $crate::__private::Iterator::next(&mut $it)?;
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident mut $v:ident ? as $w:ident $(, $($rest:tt)*)?] => {
+        let mut $w = $crate::__private::Iterator::next(&mut $it)?;
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident $v:ident as $w:ident $(, $($rest:tt)*)?] => {
+        let $w = $crate::take_iter![@next $it $v];
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident mut $v:ident as $w:ident $(, $($rest:tt)*)?] => {
+        let mut $w = $crate::take_iter![@next $it $v];
+        $crate::take_iter![@entries $it $($($rest)*)?];
+    };
+    [@entries $it:ident .. $v:ident as $w:ident $(,)?] => {
+        let $w: $crate::__private::Vec<_> = $crate::__private::Iterator::collect($it);
+    };
+    [@entries $it:ident mut .. $v:ident as $w:ident $(,)?] => {
+        let mut $w: $crate::__private::Vec<_> = $crate::__private::Iterator::collect($it);
+    };
+    [@entries $it:ident .. $v:ident $(,)?] => {
+        let $v: $crate::__private::Vec<_> = $crate::__private::Iterator::collect($it);
+    };
+    [@entries $it:ident mut .. $v:ident $(,)?] => {
+        let mut $v: $crate::__private::Vec<_> = $crate::__private::Iterator::collect($it);
+    };
+    [@entries $it:ident] => {};
+    [@entries $it:ident $(mut)? .. $v:ident $(as $w:ident)?, $($rest:tt)+] => {
+        compile_error!(concat!("`..", stringify!($v), "` has to be the last entry of `take_iter!`"));
+    };
+    [@entries $it:ident $($entry:tt)+] => {
+        compile_error!(concat!(
+            "invalid `take_iter!` entries `", stringify!($($entry)+),
+            "`: expected `name`, `mut name`, `_`, `name?`, `name as alias` or `..rest`"
+        ));
+    };
+
+    [@next $it:ident $v:tt] => {
+        match $crate::__private::Iterator::next(&mut $it) {
+            $crate::__private::Some(value) => value,
+            $crate::__private::None => panic!(concat!(
+                "`take_iter!` ran out of elements before `", stringify!($v), "`"
+            )),
+        }
+    };
+
+    [$src:expr => $($entry:tt)*] => {
+        #[allow(unused_mut)]
+        let mut it = $crate::__private::IntoIterator::into_iter($src);
+        $crate::take_iter![@entries it $($entry)*];
+    };
+}
+
+/// Collect exactly `N` elements of an iterator into named bindings.
+///
+/// `take_iter_array!(iter => [a, b, c])` expands to `let [a, b, c] = [..];` with one element of
+/// `iter` for each name, and panics if `iter` has fewer or more than three elements. The names can
+/// be `mut` or `_`, as in any array pattern.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let version = "1.42.7";
+/// take_iter_array!(version.split('.').map(|n| n.parse::<u32>().unwrap()) => [major, mut minor, _]);
+/// minor += 1;
+/// assert_eq!((major, minor), (1, 43));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_iter_array {
+    [@elems $it:ident [$($elems:tt)*] mut $v:tt $(, $($rest:tt)*)?] => {
+        $crate::take_iter_array![@elems $it [$($elems)* $crate::take_iter![@next $it $v],] $($($rest)*)?]
+    };
+    [@elems $it:ident [$($elems:tt)*] $v:tt $(, $($rest:tt)*)?] => {
+        $crate::take_iter_array![@elems $it [$($elems)* $crate::take_iter![@next $it $v],] $($($rest)*)?]
+    };
+    [@elems $it:ident [$($elems:tt)*]] => {
+        [$($elems)*]
+    };
+
+    [$src:expr => [$($pat:tt)*]] => {
+        let [$($pat)*] = {
+            let mut it = $crate::__private::IntoIterator::into_iter($src);
+            let item = $crate::__private::item_of(&it);
+            let elems = $crate::__private::items(item, $crate::take_iter_array![@elems it [] $($pat)*]);
+            if $crate::__private::Option::is_some(&$crate::__private::Iterator::next(&mut it)) {
+                panic!(concat!(
+                    "`take_iter_array!` has more elements than `[", stringify!($($pat)*), "]`"
+                ));
+            }
+            elems
+        };
+    };
+}
+
+/// Take a leaf of nested fields into a standalone binding.
+///
+/// `take_path!(config.database.url as db_url, =config.timeout)` expands to
+/// `let db_url = config.database.url; let timeout = config.timeout.clone();`. It is
+/// [`take_field!`](macro.take_field.html) for fields at any depth: each entry is a chain of field
+/// accesses, including tuple fields such as `pair.0`, with the same sigils (`mut`, `=`, `=mut`,
+/// `&`, `&mut`, `ref` and `ref mut`). Without `as`, the binding is named after the last field.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// struct Database {
+///     url: String,
+///     pool: (u32, u32),
+/// }
+/// struct Config {
+///     database: Database,
+///     timeout: u64,
+/// }
+///
+/// let mut config = Config {
+///     database: Database { url: String::from("db://"), pool: (1, 8) },
+///     timeout: 30,
+/// };
+/// take_path!(=config.database.url as db_url, &mut config.database.pool.1 as max, mut config.timeout);
+/// *max += 1;
+/// timeout *= 2;
+/// assert_eq!((db_url.as_str(), config.database.pool, timeout), ("db://", (1, 9), 60));
+/// # }
+/// ```
+///
+/// The base can also be a parenthesized expression, which is evaluated once into a temporary:
+/// `take_path!((load()).database.url as url)` is `let base = load(); let url = base.database.url;`.
+/// Method calls in the middle of the path are not supported; use a method call entry of
+/// [`take!`](macro.take.html) for those.
+#[macro_export]
+macro_rules! take_path {
+    [@entry =mut $($entry:tt)*] => {
+        $crate::take_path![@path [=mut] [] [] $($entry)*]
+    };
+    [@entry = $($entry:tt)*] => {
+        $crate::take_path![@path [=] [] [] $($entry)*]
+    };
+    [@entry &mut $($entry:tt)*] => {
+        $crate::take_path![@path [&mut] [] [] $($entry)*]
+    };
+    [@entry ref mut $($entry:tt)*] => {
+        $crate::take_path![@path [&mut] [] [] $($entry)*]
+    };
+    [@entry & $($entry:tt)*] => {
+        $crate::take_path![@path [&] [] [] $($entry)*]
+    };
+    [@entry ref $($entry:tt)*] => {
+        $crate::take_path![@path [&] [] [] $($entry)*]
+    };
+    [@entry mut $($entry:tt)*] => {
+        $crate::take_path![@path [mut] [] [] $($entry)*]
+    };
+    [@entry  $($entry:tt)*] => {
+        $crate::take_path![@path [] [] [] $($entry)*]
+    };
+
+    [@path [$($sigil:tt)*] [] [] $var:ident $($rest:tt)*] => {
+        $crate::take_path![@path [$($sigil)*] [$var] [] $($rest)*]
+    };
+    [@path [$($sigil:tt)*] [] [] ($base:expr) $($rest:tt)*] => {
+        $crate::take_path![@path [$($sigil)*] [($base)] [] $($rest)*]
+    };
+    [@path [$($sigil:tt)*] [$($place:tt)+] [$($last:tt)?] . $seg:tt $($rest:tt)*] => {
+        $crate::take_path![@path [$($sigil)*] [$($place)+ . $seg] [$seg] $($rest)*]
+    };
+    [@path [$($sigil:tt)*] [$($place:tt)+] [$last:tt] as $v:ident $(, $($rest:tt)*)?] => {
+        $crate::take_path![@base [$($sigil)*] [$($place)+] $v];
+        $crate::take_path![$($($rest)*)?];
+    };
+    [@path [$($sigil:tt)*] [$($place:tt)+] [$last:ident] $(, $($rest:tt)*)?] => {
+        $crate::take_path![@base [$($sigil)*] [$($place)+] $last];
+        $crate::take_path![$($($rest)*)?];
+    };
+    [@path [$($sigil:tt)*] [$($place:tt)*] [$($last:tt)?] $($rest:tt)*] => {
+        compile_error!(concat!(
+            "invalid `take_path!` entry at `", stringify!($($place)* $($rest)*),
+            "`: expected a variable followed by `.field`s, and `as <name>` when the last field is ",
+            "a tuple field"
+        ));
+    };
+
+    [@base [$($sigil:tt)*] [($base:expr) $($seg:tt)+] $v:ident] => {
+        let base = $base;
+        $crate::take_path![@let [$($sigil)*] [base $($seg)+] $v];
+    };
+    [@base [$($sigil:tt)*] [$($place:tt)+] $v:ident] => {
+        $crate::take_path![@let [$($sigil)*] [$($place)+] $v];
+    };
+
+    [@let [] [$($place:tt)+] $v:ident] => {
+        let $v = $($place)+;
+        $crate::__take_trace!("", $v);
+    };
+    [@let [mut] [$($place:tt)+] $v:ident] => {
+        let mut $v = $($place)+;
+        $crate::__take_trace!("mut", $v);
+    };
+    [@let [=] [$($place:tt)+] $v:ident] => {
+        let $v = $crate::__take_clone!($($place)+);
+        $crate::__take_trace!("=", $v);
+    };
+    [@let [=mut] [$($place:tt)+] $v:ident] => {
+        let mut $v = $crate::__take_clone!($($place)+);
+        $crate::__take_trace!("=mut", $v);
+    };
+    [@let [&] [$($place:tt)+] $v:ident] => {
+        let $v = &$($place)+;
+        $crate::__take_trace!("&", $v);
+    };
+    [@let [&mut] [$($place:tt)+] $v:ident] => {
+        let $v = &mut $($place)+;
+        $crate::__take_trace!("&mut", $v);
+    };
+
+    [$($entry:tt)+] => {
+        $crate::take_path![@entry $($entry)+]
+    };
+    // trailing comma
+    [] => {};
+}
+
+/// `take!` that also counts its entries in a constant.
+///
+/// `take_count!(=a, b, &c)` expands to `take!(=a, b, &c)` followed by
+/// `const TAKEN_COUNT: usize = 3;`, so code generated alongside the capture list, such as an array
+/// or a tuple that has to have one element per entry, can check its arity against it. The constant
+/// is an item, so it is visible in the whole enclosing block, and there can only be one
+/// `take_count!` per block.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (a, b, c) = (String::from("a"), 2, vec![3]);
+/// take_count!(=a, b, &c);
+/// let names: [&str; TAKEN_COUNT] = ["a", "b", "c"];
+/// assert_eq!((TAKEN_COUNT, names.len(), a.len() + b + c.len()), (3, 3, 4));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_count {
+    // an empty entry, after a trailing comma, isn't counted
+    [@count [$($n:tt)*] [] , $($rest:tt)*] => {
+        $crate::take_count![@count [$($n)*] [] $($rest)*]
+    };
+    [@count [$($n:tt)*] [$($cur:tt)+] , $($rest:tt)*] => {
+        $crate::take_count![@count [$($n)* + 1] [] $($rest)*]
+    };
+    [@count [$($n:tt)*] [$($cur:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::take_count![@count [$($n)*] [$($cur)* $t] $($rest)*]
+    };
+    [@count [$($n:tt)*] [$($cur:tt)+]] => {
+        const TAKEN_COUNT: usize = 0 $($n)* + 1;
+    };
+    [@count [$($n:tt)*] []] => {
+        const TAKEN_COUNT: usize = 0 $($n)*;
+    };
+
+    [$($entry:tt)*] => {
+        $crate::take![$($entry)*];
+        $crate::take_count![@count [] [] $($entry)*]
+    };
+}
+
+/// Take variables and collect them into an array.
+///
+/// `take_into_array!([=a, b, mut c])` takes the entries like `take!` in a block of its own and
+/// evaluates to `[a, b, c]`, with the names the entries bind (so `x as y` puts `y` in the array).
+/// Every entry has to bind a single value of the same type; anything else is a "mismatched types"
+/// error on the first entry of a different type.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (a, b, c) = (String::from("a"), String::from("b"), String::from("c"));
+/// let all = take_into_array!([=a, b as second, c]); // { let a = a.clone(); ...; [a, second, c] }
+/// assert_eq!((a.as_str(), all), ("a", [String::from("a"), "b".into(), "c".into()]));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_into_array {
+    [@split [$($all:tt)*] [$($done:tt)*] [$($cur:tt)*] , $($rest:tt)*] => {
+        $crate::take_into_array![@split [$($all)*] [$($done)* [$($cur)*]] [] $($rest)*]
+    };
+    [@split [$($all:tt)*] [$($done:tt)*] [$($cur:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::take_into_array![@split [$($all)*] [$($done)*] [$($cur)* $t] $($rest)*]
+    };
+    [@split [$($all:tt)*] [$($done:tt)*] []] => {
+        $crate::take_into_array![@array [$($all)*] $($done)*]
+    };
+    [@split [$($all:tt)*] [$($done:tt)*] [$($cur:tt)+]] => {
+        $crate::take_into_array![@array [$($all)*] $($done)* [$($cur)+]]
+    };
+    [@array [$($all:tt)*] $([$($entry:tt)+])*] => {{
+        $crate::take![$($all)*];
+        [$($crate::__take_impl![@bound expr [] $($entry)+]),*]
+    }};
+
+    [[$($entry:tt)*]] => {
+        $crate::take_into_array![@split [$($entry)*] [] [] $($entry)*]
+    };
+}
+
+/// Take variables by passing them through a function.
+///
+/// `take_map!(x => f)` expands to `let x = (f)(x);`, for when the right way to take a variable is
+/// neither a move, a clone nor a reference, but some conversion. The function can be any
+/// expression, including a closure. `mut` works as usual and a new name is given with `as`
+/// _before_ the `=>`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (id, name, bytes) = (7, "name", vec![1u8, 2, 3]);
+/// take_map!(
+///     id => |id: u32| id.to_string(),          // let id = (|id: u32| ...)(id);
+///     mut name as owned => String::from,       // let mut owned = (String::from)(name);
+///     bytes => |b: Vec<u8>| b.into_iter().map(u32::from).sum::<u32>(),
+/// );
+/// owned.push('!');
+/// assert_eq!((id.as_str(), owned.as_str(), bytes), ("7", "name!", 6));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_map {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident => $f:expr, $($rest:tt)*] => {
+        let $var = ($f)($var);
+        $crate::take_map![$($rest)*]
+    };
+    [$var:ident as $v:ident => $f:expr, $($rest:tt)*] => {
+        let $v = ($f)($var);
+        $crate::take_map![$($rest)*]
+    };
+    [mut $var:ident => $f:expr, $($rest:tt)*] => {
+        let mut $var = ($f)($var);
+        $crate::take_map![$($rest)*]
+    };
+    [mut $var:ident as $v:ident => $f:expr, $($rest:tt)*] => {
+        let mut $v = ($f)($var);
+        $crate::take_map![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident => $f:expr] => {
+        let $var = ($f)($var);
+    };
+    [$var:ident as $v:ident => $f:expr] => {
+        let $v = ($f)($var);
+    };
+    [mut $var:ident => $f:expr] => {
+        let mut $var = ($f)($var);
+    };
+    [mut $var:ident as $v:ident => $f:expr] => {
+        let mut $v = ($f)($var);
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Reborrow references, making it explicit that the original stays usable afterwards.
+///
+/// `reborrow!(x)` expands to `let x = &mut *x;` and `reborrow!(&x)` to `let x = &*x;`. This is the
+/// same as `take!(&mut *x)` and `take!(&*x)`, but reads as what it is: using a `&mut` for a while
+/// without giving it up. `as` renames the reborrow.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// fn fill(out: &mut Vec<u32>) {
+///     {
+///         reborrow!(out as inner); // let inner = &mut *out;
+///         let mut push = |v| inner.push(v);
+///         push(1);
+///         push(2);
+///     }
+///     out.push(3); // `out` was not moved into the closure
+/// }
+/// let mut v = Vec::new();
+/// fill(&mut v);
+/// assert_eq!(v, vec![1, 2, 3]);
+/// # }
+/// ```
+///
+/// Reborrowing a `&mut MutexGuard<T>` gives a `&mut MutexGuard<T>` again and never moves the
+/// guard, so the lock is held until the original guard is dropped.
+#[macro_export]
+macro_rules! reborrow {
+    // ---------------------
+    // ----- with rest -----
+    [$var:ident, $($rest:tt)*] => {
+        let $var = &mut *$var;
+        $crate::reborrow![$($rest)*]
+    };
+    [$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = &mut *$var;
+        $crate::reborrow![$($rest)*]
+    };
+
+    [&$var:ident, $($rest:tt)*] => {
+        let $var = &*$var;
+        $crate::reborrow![$($rest)*]
+    };
+    [&$var:ident as $v:ident, $($rest:tt)*] => {
+        let $v = &*$var;
+        $crate::reborrow![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$var:ident] => {
+        let $var = &mut *$var;
+    };
+    [$var:ident as $v:ident] => {
+        let $v = &mut *$var;
+    };
+
+    [&$var:ident] => {
+        let $var = &*$var;
+    };
+    [&$var:ident as $v:ident] => {
+        let $v = &*$var;
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Borrow variables, making it obvious that nothing is moved or cloned.
+///
+/// `borrow!(x)` expands to `take!(&x)` and `borrow!(mut x)` to `take!(&mut x)`, so a plain name
+/// is a shared borrow and `mut` a unique one. `as` renames the borrow. Any other sigil is a compile
+/// error: moving and cloning is what `take!` is for.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (config, mut buffer, registry) = (2, Vec::new(), vec!["a", "b"]);
+/// {
+///     borrow!(
+///         config,          // let config = &config;
+///         mut buffer,      // let buffer = &mut buffer;
+///         registry as reg, // let reg = &registry;
+///     );
+///     buffer.extend(reg.iter().map(|r| r.repeat(*config)));
+/// }
+/// assert_eq!(buffer, vec!["aa", "bb"]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! borrow {
+    // ---------------------
+    // ----- with rest -----
+    [$(#[$attr:meta])* $var:ident, $($rest:tt)*] => {
+        $crate::take![$(#[$attr])* &$var];
+        $crate::borrow![$($rest)*]
+    };
+    [$(#[$attr:meta])* $var:ident as $v:ident, $($rest:tt)*] => {
+        $crate::take![$(#[$attr])* &$var as $v];
+        $crate::borrow![$($rest)*]
+    };
+    [$(#[$attr:meta])* mut $var:ident, $($rest:tt)*] => {
+        $crate::take![$(#[$attr])* &mut $var];
+        $crate::borrow![$($rest)*]
+    };
+    [$(#[$attr:meta])* mut $var:ident as $v:ident, $($rest:tt)*] => {
+        $crate::take![$(#[$attr])* &mut $var as $v];
+        $crate::borrow![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$(#[$attr:meta])* $var:ident] => {
+        $crate::take![$(#[$attr])* &$var];
+    };
+    [$(#[$attr:meta])* $var:ident as $v:ident] => {
+        $crate::take![$(#[$attr])* &$var as $v];
+    };
+    [$(#[$attr:meta])* mut $var:ident] => {
+        $crate::take![$(#[$attr])* &mut $var];
+    };
+    [$(#[$attr:meta])* mut $var:ident as $v:ident] => {
+        $crate::take![$(#[$attr])* &mut $var as $v];
+    };
+
+    // trailing comma
+    [] => {};
+
+    // anything else moves, clones or isn't an entry at all
+    [$(#[$attr:meta])* $first:tt $($entry:tt)*] => {
+        compile_error!(concat!(
+            "`borrow!` can only borrow, found `", stringify!($first $($entry)*), "`. ",
+            "Entries are `x` (`&x`), `mut x` (`&mut x`) and `x as y`; ",
+            "use `take!` to move or clone."
+        ));
+    };
+}
+
+/// Shadow variables with new values, making it obvious that nothing is taken.
+///
+/// `shadow!(x = x + 1)` expands to `let x = x + 1;`. The expression can be anything and use the
+/// variable it shadows. `mut` makes the new binding mutable and `as` binds it under another name
+/// instead.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (x, y, old_x) = (1, "  padded ", 10);
+/// shadow!(
+///     x = x + 1,                   // let x = x + 1;
+///     y = y.trim().to_string(),    // let y = y.trim().to_string();
+///     mut count = old_x + 1,       // let mut count = old_x + 1;
+///     y = y.to_uppercase() as z,   // let z = y.to_uppercase();
+/// );
+/// count += x;
+/// assert_eq!((count, y.as_str(), z.as_str()), (13, "padded", "PADDED"));
+/// # }
+/// ```
+///
+/// Like with `<-` in `take!`, the expression runs until the next `,` or `as <name>`, so casts have
+/// to be wrapped in parentheses: `shadow!(x = (x as u64))`.
+#[macro_export]
+macro_rules! shadow {
+    [$(#[$attr:meta])* mut $var:ident = $($rest:tt)*] => {
+        $crate::shadow![@expr [$(#[$attr])*] [mut] $var [] $($rest)*]
+    };
+    [$(#[$attr:meta])* $var:ident = $($rest:tt)*] => {
+        $crate::shadow![@expr [$(#[$attr])*] [] $var [] $($rest)*]
+    };
+
+    // collect the expression up to the next `,` or `as <name>`
+    [@expr [$(#[$attr:meta])*] [$($mut:tt)?] $var:ident [$($e:tt)+] as $v:ident, $($rest:tt)*] => {
+        $(#[$attr])* let $($mut)? $v = $($e)+;
+        $crate::shadow![$($rest)*]
+    };
+    [@expr [$(#[$attr:meta])*] [$($mut:tt)?] $var:ident [$($e:tt)+] as $v:ident] => {
+        $(#[$attr])* let $($mut)? $v = $($e)+;
+    };
+    [@expr [$(#[$attr:meta])*] [$($mut:tt)?] $var:ident [$($e:tt)+], $($rest:tt)*] => {
+        $(#[$attr])* let $($mut)? $var = $($e)+;
+        $crate::shadow![$($rest)*]
+    };
+    [@expr [$(#[$attr:meta])*] [$($mut:tt)?] $var:ident [$($e:tt)+]] => {
+        $(#[$attr])* let $($mut)? $var = $($e)+;
+    };
+    [@expr [$(#[$attr:meta])*] [$($mut:tt)?] $var:ident [] $(, $($rest:tt)*)?] => {
+        compile_error!(concat!("`shadow!` is missing the new value of `", stringify!($var), "`"));
+    };
+    [@expr [$(#[$attr:meta])*] [$($mut:tt)?] $var:ident [$($e:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::shadow![@expr [$(#[$attr])*] [$($mut)?] $var [$($e)* $t] $($rest)*]
+    };
+
+    // trailing comma
+    [] => {};
+
+    [$(#[$attr:meta])* $($entry:tt)*] => {
+        compile_error!(concat!(
+            "invalid `shadow!` entry in `", stringify!($($entry)*), "`: entries are ",
+            "`name = expr`, `mut name = expr` and `name = expr as other`"
+        ));
+    };
+}
+
+/// Lend variables read-only to a block.
+///
+/// `protect!(x, y { ... })` expands to `take!((&x, &y) { ... })`: inside the block `x` and `y` are
+/// shared references, so modifying them is a compile error, and outside they are untouched. The
+/// macro evaluates to the value of the block.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (mut config, items) = (vec![1, 2], vec![3]);
+/// let total = protect!(config, items {
+///     // config.push(3); // error: `config` is a `&` reference
+///     config.iter().chain(items.iter()).sum::<i32>()
+/// });
+/// config.push(total);
+/// assert_eq!(config, vec![1, 2, 6]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! protect {
+    [$($var:ident),+ $(,)? { $($body:tt)* }] => {
+        $crate::take![($(&$var),+) { $($body)* }]
+    };
+}
+
+/// Lend variables mutably to a block.
+///
+/// `protect_mut!(x, y { ... })` expands to `take!((&mut x, &mut y) { ... })`, keeping the mutation
+/// of `x` and `y` inside the block. Like [`protect!`](macro.protect.html), it evaluates to the
+/// value of the block.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let mut log = Vec::new();
+/// let len = protect_mut!(log {
+///     log.push("start");
+///     log.len()
+/// });
+/// assert_eq!((len, log), (1, vec!["start"]));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! protect_mut {
+    [$($var:ident),+ $(,)? { $($body:tt)* }] => {
+        $crate::take![($(&mut $var),+) { $($body)* }]
+    };
+}
+
+/// Swap the values of two places.
+///
+/// `swap!(a, b)` expands to `core::mem::swap(&mut a, &mut b);`. The places can be any mutable
+/// place expressions, such as fields or indexes, not just variables.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// struct Pair {
+///     left: String,
+///     right: String,
+/// }
+/// let mut pair = Pair { left: "l".into(), right: "r".into() };
+/// swap!(pair.left, pair.right);
+/// assert_eq!((pair.left.as_str(), pair.right.as_str()), ("r", "l"));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! swap {
+    [$a:expr, $b:expr $(,)?] => {
+        $crate::__private::mem::swap(&mut $a, &mut $b);
+    };
+}
+
+/// Rotate the values of two or more places to the left.
+///
+/// `rotate!(a, b, c)` moves the value of `b` into `a`, `c` into `b` and `a` into `c`. It is a
+/// chain of `swap!`s, so the values are only moved: they don't need to implement `Clone` or
+/// `Default`. Like with `swap!`, the places can be fields or indexes.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (mut a, mut b, mut c) = (String::from("a"), String::from("b"), String::from("c"));
+/// rotate!(a, b, c); // mem::swap(&mut a, &mut b); mem::swap(&mut b, &mut c);
+/// assert_eq!((a.as_str(), b.as_str(), c.as_str()), ("b", "c", "a"));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! rotate {
+    [$first:expr, $second:expr $(, $rest:expr)* $(,)?] => {
+        $crate::__private::mem::swap(&mut $first, &mut $second);
+        $crate::rotate![@swap $second $(, $rest)*];
+    };
+    [@swap $prev:expr, $next:expr $(, $rest:expr)*] => {
+        $crate::__private::mem::swap(&mut $prev, &mut $next);
+        $crate::rotate![@swap $next $(, $rest)*];
+    };
+    [@swap $last:expr] => {};
+    [$($places:tt)*] => {
+        compile_error!("`rotate!` needs at least two places, i.e. `rotate!(a, b, c)`");
+    };
+}
+
+/// Bind values that are only computed the first time they are used.
+///
+/// `lazy_take!(x = || init())` expands to `let x = LazyCell::new(|| init());`, so a closure that is
+/// created often but rarely called only pays for `init()` when it runs. `clone x` defers a clone
+/// the same way: `lazy_take!(clone x)` is `let x = LazyCell::new(|| x.clone());`. The closure is
+/// not `move`, so it borrows the original until the first use and the original stays usable,
+/// e.g. for `clone x as a, clone x as b`. To hand the original to the cell instead, write
+/// `x = move || x.clone()` or just `x = move || x`. Both forms can be `mut`, and `clone x as y`
+/// renames.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::cell::Cell;
+///
+/// let (calls, names) = (Cell::new(0), vec![String::from("a")]);
+/// let handler = |verbose: bool| {
+///     lazy_take!(
+///         table = || { calls.set(calls.get() + 1); vec![0u8; 1024] }, // LazyCell::new(|| ...)
+///         clone names as owned,                                     // LazyCell::new(|| names.clone())
+///     );
+///     if verbose {
+///         table.len() + owned.len()
+///     } else {
+///         0
+///     }
+/// };
+/// assert_eq!((handler(false), handler(false), calls.get()), (0, 0, 0));
+/// assert_eq!((handler(true), calls.get()), (1025, 1));
+/// # }
+/// ```
+///
+/// The cell is `core::cell::LazyCell`, which can't be shared between threads. Use
+/// [`lazy_take_sync!`](macro.lazy_take_sync.html) for `LazyLock`.
+#[macro_export]
+macro_rules! lazy_take {
+    [@with [$($cell:tt)*]] => {};
+    [@with [$($cell:tt)*] $(#[$attr:meta])* mut clone $var:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let mut $v = $($cell)*::new(|| $crate::__take_clone!($var));
+        $crate::__take_trace!($(#[$attr])* "lazy mut clone", $v);
+        $crate::lazy_take![@with [$($cell)*] $($($rest)*)?]
+    };
+    [@with [$($cell:tt)*] $(#[$attr:meta])* mut clone $var:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let mut $var = $($cell)*::new(|| $crate::__take_clone!($var));
+        $crate::__take_trace!($(#[$attr])* "lazy mut clone", $var);
+        $crate::lazy_take![@with [$($cell)*] $($($rest)*)?]
+    };
+    [@with [$($cell:tt)*] $(#[$attr:meta])* mut $var:ident = $init:expr $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let mut $var = $($cell)*::new($init);
+        $crate::__take_trace!($(#[$attr])* "lazy mut", $var);
+        $crate::lazy_take![@with [$($cell)*] $($($rest)*)?]
+    };
+    [@with [$($cell:tt)*] $(#[$attr:meta])* clone $var:ident as $v:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $v = $($cell)*::new(|| $crate::__take_clone!($var));
+        $crate::__take_trace!($(#[$attr])* "lazy clone", $v);
+        $crate::lazy_take![@with [$($cell)*] $($($rest)*)?]
+    };
+    [@with [$($cell:tt)*] $(#[$attr:meta])* clone $var:ident $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $var = $($cell)*::new(|| $crate::__take_clone!($var));
+        $crate::__take_trace!($(#[$attr])* "lazy clone", $var);
+        $crate::lazy_take![@with [$($cell)*] $($($rest)*)?]
+    };
+    [@with [$($cell:tt)*] $(#[$attr:meta])* $var:ident = $init:expr $(, $($rest:tt)*)?] => {
+        $(#[$attr])* let $var = $($cell)*::new($init);
+        $crate::__take_trace!($(#[$attr])* "lazy", $var);
+        $crate::lazy_take![@with [$($cell)*] $($($rest)*)?]
+    };
+    [@with [$($cell:tt)*] $($entry:tt)+] => {
+        compile_error!(concat!(
+            "lazy entries are `x = <init>`, `clone x` and `clone x as y`, optionally `mut`, found `",
+            stringify!($($entry)+), "`"
+        ));
+    };
+
+    [$($entry:tt)*] => {
+        $crate::lazy_take![@with [$crate::__private::LazyCell] $($entry)*]
+    };
+}
+
+/// Like [`lazy_take!`](macro.lazy_take.html), but with `std::sync::LazyLock`, so that the values
+/// can be shared between threads.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::thread;
+///
+/// let config = String::from("verbose");
+/// lazy_take_sync!(parsed = || config.len(), clone config as copy);
+/// thread::scope(|s| {
+///     for _ in 0..2 {
+///         s.spawn(|| assert_eq!(*parsed, copy.len()));
+///     }
+/// });
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! lazy_take_sync {
+    [$($entry:tt)*] => {
+        $crate::lazy_take![@with [$crate::__private::LazyLock] $($entry)*]
+    };
+}
+
+/// Fail to compile unless variables have exactly the given types.
+///
+/// `capture_assert_type!(x: Vec<i32>, w: &mut i32)` does nothing at runtime. If a refactor
+/// changes what a capture list binds, the error says "expected `Vec<i32>`, found `Vec<u8>`" at
+/// the assertion. The types have to match exactly: `&String` is not accepted for `&str`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (mut w, x) = (1, vec![2]);
+/// take!(&mut w, =x as y);
+/// capture_assert_type!(w: &mut i32, y: Vec<i32>);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! capture_assert_type {
+    [$($var:ident: $ty:ty),* $(,)?] => {
+        $($crate::__private::assert_type::<$ty, _>(&$var);)*
+    };
+}
+
+/// Fail to compile unless variables implement the given traits.
+///
+/// `bound_check!(x: Send + Sync, y: Clone + Debug)` does nothing at runtime and leaves `x` and `y`
+/// untouched. Each variable is passed by reference to a function with its bounds, so the error
+/// points at the bound that isn't satisfied. Lifetimes such as `'static` work too.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::fmt::Debug;
+/// use std::sync::Arc;
+///
+/// let (shared, name) = (Arc::new(vec![1]), "name");
+/// take!(=shared, =name as label);
+/// bound_check!(shared: Send + Sync + 'static, label: Clone + Debug);
+/// std::thread::spawn(move || println!("{:?} {}", shared, label)).join().unwrap();
+/// # }
+/// ```
+///
+/// The bounds are checked in a nested function, so they can't name the generic parameters of the
+/// enclosing function. Use [`capture_assert_type!`](macro.capture_assert_type.html) to check the
+/// exact type instead.
+#[macro_export]
+macro_rules! bound_check {
+    [@bounds $var:ident [$($bound:tt)+] $(, $($rest:tt)*)?] => {
+        {
+            #[allow(dead_code)]
+            fn bound_check<T: $($bound)+>(_: &T) {}
+            bound_check(&$var);
+        }
+        $crate::bound_check![$($($rest)*)?]
+    };
+    [@bounds $var:ident [$($bound:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::bound_check![@bounds $var [$($bound)* $t] $($rest)*]
+    };
+    [$var:ident : $($rest:tt)+] => {
+        $crate::bound_check![@bounds $var [] $($rest)+]
+    };
+    [] => {};
+}
+
+/// Take variables like [`take!`](macro.take.html) and assert the type of each binding.
+///
+/// Every entry is followed by the type it should bind, which is checked with
+/// [`capture_assert_type!`](macro.capture_assert_type.html):
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (mut w, x) = (1, vec![2]);
+/// take_and_assert!(
+///     &mut w: &mut i32,       // take!(&mut w); capture_assert_type!(w: &mut i32);
+///     =x as y: Vec<i32>,      // take!(=x as y); capture_assert_type!(y: Vec<i32>);
+/// );
+/// *w += y[0];
+/// # }
+/// ```
+///
+/// Unlike the `: Type` that some `take!` entries accept, which annotates the binding and allows
+/// coercions, this only checks.
+#[macro_export]
+macro_rules! take_and_assert {
+    [] => {};
+    [@entry [$($entry:tt)+] [$last:ident] : $ty:ty $(, $($rest:tt)*)?] => {
+        $crate::take![$($entry)+];
+        $crate::capture_assert_type![$last: $ty];
+        $crate::take_and_assert![$($($rest)*)?]
+    };
+    [@entry [$($entry:tt)*] [] : $($rest:tt)*] => {
+        compile_error!(concat!(
+            "`take_and_assert!` needs an entry that ends with the name it binds, found `",
+            stringify!($($entry)*), "`"
+        ));
+    };
+    [@entry [$($entry:tt)*] [$($last:ident)?] $(, $($rest:tt)*)?] => {
+        compile_error!(concat!(
+            "`", stringify!($($entry)*), "` needs the type it binds, i.e. `",
+            stringify!($($entry)*), ": Type`"
+        ));
+    };
+    [@entry [$($entry:tt)*] [$($last:ident)?] $t:ident $($rest:tt)*] => {
+        $crate::take_and_assert![@entry [$($entry)* $t] [$t] $($rest)*]
+    };
+    [@entry [$($entry:tt)*] [$($last:ident)?] $t:tt $($rest:tt)*] => {
+        $crate::take_and_assert![@entry [$($entry)* $t] [] $($rest)*]
+    };
+    [$($entry:tt)+] => {
+        $crate::take_and_assert![@entry [] [] $($entry)+]
+    };
+}
+
+/// Take a reference into a lazily initialized cell, initializing it first if needed.
+///
+/// `take_once!(CELL => x)` expands to `let x = CELL.get_or_init(Default::default);` and
+/// `take_once!(CELL with init => x)` to `let x = CELL.get_or_init(|| init);`. Only the methods are
+/// used, so it works with `std::sync::OnceLock`, `std::cell::OnceCell` and the `OnceCell`s of the
+/// `once_cell` crate alike. The `once-cell` feature re-exports that crate as `taken::once_cell`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::{Arc, OnceLock};
+/// use std::thread::spawn;
+///
+/// static CONFIG: OnceLock<Arc<String>> = OnceLock::new();
+/// let th = spawn(|| {
+///     take_once!(CONFIG with Arc::new(String::from("verbose")) => config);
+///     config.len()
+/// });
+/// assert_eq!(th.join().unwrap(), 7);
+/// take_once!(CONFIG => config); // already initialized: `Default` is not used
+/// assert_eq!(config.as_str(), "verbose");
+/// # }
+/// ```
+///
+/// With `mut` the cell is initialized and then borrowed through `get_mut`, binding `x` as a
+/// `&mut T`. This needs mutable access to the cell, so it works for local cells but not statics.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::OnceLock;
+///
+/// let mut cell: OnceLock<Vec<u32>> = OnceLock::new();
+/// take_once!(mut cell with vec![1] => v); // let v: &mut Vec<u32> = ...;
+/// v.push(2);
+/// assert_eq!(cell.get(), Some(&vec![1, 2]));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! take_once {
+    // ---------------------
+    // ----- with rest -----
+    [$cell:ident => $v:ident, $($rest:tt)*] => {
+        $crate::take_once![$cell => $v];
+        $crate::take_once![$($rest)*]
+    };
+    [$cell:ident with $init:expr => $v:ident, $($rest:tt)*] => {
+        $crate::take_once![$cell with $init => $v];
+        $crate::take_once![$($rest)*]
+    };
+    [mut $cell:ident => $v:ident, $($rest:tt)*] => {
+        $crate::take_once![mut $cell => $v];
+        $crate::take_once![$($rest)*]
+    };
+    [mut $cell:ident with $init:expr => $v:ident, $($rest:tt)*] => {
+        $crate::take_once![mut $cell with $init => $v];
+        $crate::take_once![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$cell:ident => $v:ident] => {
+        let $v = $cell.get_or_init($crate::__private::Default::default);
+    };
+    [$cell:ident with $init:expr => $v:ident] => {
+        let $v = $cell.get_or_init(|| $init);
+    };
+    [mut $cell:ident => $v:ident] => {
+        $crate::take_once![mut $cell with $crate::__private::Default::default() => $v];
+    };
+    [mut $cell:ident with $init:expr => $v:ident] => {
+        let $v = {
+            $cell.get_or_init(|| $init);
+            match $cell.get_mut() {
+                $crate::__private::Some(value) => value,
+                $crate::__private::None => unreachable!("the cell was just initialized"),
+            }
+        };
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Make a variable mutable for the duration of a block, then freeze it again.
+///
+/// `mut_scope!(x { ... })` expands to `let x = { let mut x = x; let _ = { ... }; x };`. After the block
+/// `x` is immutable again, unlike after `take!(mut x)`. The value of the block itself is ignored:
+/// the variable is always what gets bound afterwards.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let v = vec![3, 1, 2];
+/// mut_scope!(v {
+///     v.push(0);
+///     v.sort();
+/// });
+/// // v.push(4); // ERROR: cannot borrow `v` as mutable
+/// assert_eq!(v, vec![0, 1, 2, 3]);
+/// # }
+/// ```
+///
+/// Use `as` to bind the result under a new name. Inside the block the variable keeps its original
+/// name, and `Copy` values stay usable under it afterwards.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let n = 6;
+/// mut_scope!(n as doubled {
+///     n *= 2;
+/// });
+/// assert_eq!((n, doubled), (6, 12));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! mut_scope {
+    [$var:ident $body:block] => {
+        let $var = {
+            #[allow(unused_mut)]
+            let mut $var = $var;
+            let _ = $body;
+            $var
+        };
+    };
+    [$var:ident as $v:ident $body:block] => {
+        let $v = {
+            #[allow(unused_mut)]
+            let mut $var = $var;
+            let _ = $body;
+            $var
+        };
+    };
+}
+
+/// Lend variables to a block, restoring them afterwards.
+///
+/// Entries before the `=>` use the same syntax as [`take!`](macro.take.html), except for plain
+/// variables and `*` dereferences (optionally renamed with `as`). Those are _lent_ to the block: the value is swapped
+/// out for `Default::default()` and handed to the block through a [`Restore`](struct.Restore.html)
+/// guard, which puts it back when the block exits, even if it panics. All other entries behave as
+/// in `take!` and only live for the duration of the block.
+///
+/// The macro evaluates to the value of the block.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (mut x, y) = (vec![1, 2], String::from("y"));
+/// let len = with_taken!(x, =y => {
+///     // `x` dereferences to the lent `Vec` and `y` is a clone
+///     x.push(3);
+///     x.len() + y.len()
+/// });
+/// assert_eq!(len, 4);
+/// assert_eq!(x, vec![1, 2, 3]); // x was restored
+/// assert_eq!(y, "y");
+/// # }
+/// ```
+///
+/// Lent variables have to be declared `mut` and their type has to implement `Default`. Values
+/// behind a mutable reference are lent with `*`, which swaps out the pointee instead:
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// fn process(state: &mut Vec<u32>) -> usize {
+///     with_taken!(*state as inner => {
+///         inner.push(4);
+///         inner.len()
+///     })
+/// }
+/// let mut state = vec![1, 2, 3];
+/// assert_eq!(process(&mut state), 4);
+/// assert_eq!(state, vec![1, 2, 3, 4]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! with_taken {
+    // split off the block
+    [@split [$($entries:tt)*] => $body:block] => {{
+        $crate::with_taken![@entry [] $($entries)*];
+        $body
+    }};
+    [@split [$($entries:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::with_taken![@split [$($entries)* $t] $($rest)*]
+    };
+
+    // split the entries on `,`
+    [@entry [$($entry:tt)*], $($rest:tt)*] => {
+        $crate::with_taken![@lend $($entry)*];
+        $crate::with_taken![@entry [] $($rest)*]
+    };
+    [@entry [$($entry:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::with_taken![@entry [$($entry)* $t] $($rest)*]
+    };
+    [@entry [$($entry:tt)*]] => {
+        $crate::with_taken![@lend $($entry)*];
+    };
+
+    [@lend $var:ident] => {
+        #[allow(unused_mut)]
+        let mut $var = $crate::Restore::new(&mut $var);
+    };
+    [@lend $var:ident as $v:ident] => {
+        #[allow(unused_mut)]
+        let mut $v = $crate::Restore::new(&mut $var);
+    };
+    [@lend *$var:ident] => {
+        #[allow(unused_mut)]
+        let mut $var = $crate::Restore::new(&mut *$var);
+    };
+    [@lend *$var:ident as $v:ident] => {
+        #[allow(unused_mut)]
+        let mut $v = $crate::Restore::new(&mut *$var);
+    };
+    [@lend $($entry:tt)*] => {
+        $crate::take![$($entry)*];
+    };
+
+    [$($tokens:tt)+] => {
+        $crate::with_taken![@split [] $($tokens)+]
+    };
+}
+
+/// Spawn a scoped thread with its own `take!` list.
+///
+/// `scope_spawn!(s, (entries) { ... })` expands to `{ take!(entries); s.spawn(move || { ... }) }`
+/// and evaluates to the thread's `ScopedJoinHandle`. Since the closure is only moved into a
+/// [`std::thread::scope`], the entries can borrow locals from outside the scope: several threads
+/// can take `&data` while another takes `&mut` of something else or a clone of a `Sender`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::thread;
+///
+/// let data = vec![1, 2, 3];
+/// let mut total = 0;
+/// let (sum, len) = thread::scope(|s| {
+///     let sum = scope_spawn!(s, (&data) { data.iter().sum::<i32>() });
+///     let len = scope_spawn!(s, (&data, &mut total) { *total = data.len() });
+///     (sum.join().unwrap(), len.join().unwrap())
+/// });
+/// assert_eq!((sum, len, total), (6, (), 3));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! scope_spawn {
+    [$scope:expr, ($($entry:tt)*) $body:block] => {{
+        $crate::take![$($entry)*];
+        $scope.spawn(move || $body)
+    }};
+}
+
+/// Create an `async move` block with its own `take!` list.
+///
+/// `async_capture!([entries] { ... })` expands to `{ take!(entries); async move { ... } }`, so the
+/// future captures exactly what the list says: `=db` moves a clone of `db` into it, `&config`
+/// a reference to `config` and `mut state` the value itself. The entries are evaluated before the
+/// `async move`, otherwise the block would move the originals in to clone them.
+/// `async_capture!([entries] -> Type { ... })` also fixes the output type, which helps when the
+/// block uses `?`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::Arc;
+///
+/// let (db, config, state) = (Arc::new(vec![1, 2]), 10, Vec::<i32>::new());
+/// let fut = async_capture!([=db, &config, mut state] {
+///     state.extend(db.iter().map(|v| v * config));
+///     state
+/// });
+/// let parse = async_capture!([] -> Result<i32, std::num::ParseIntError> {
+///     let n: i32 = "4".parse()?;
+///     Ok(n)
+/// });
+/// assert_eq!(Arc::strong_count(&db), 2); // the clone lives in `fut`
+/// # drop((fut, parse));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! async_capture {
+    [[$($entry:tt)*] -> $ret:ty $body:block] => {{
+        $crate::take![$($entry)*];
+        async move {
+            let __taken_output: $ret = $body;
+            __taken_output
+        }
+    }};
+    [[$($entry:tt)*] $body:block] => {{
+        $crate::take![$($entry)*];
+        async move $body
+    }};
+}
+
+/// Spawn an async task with its own `take!` list, using any spawn function.
+///
+/// `spawn_with!(spawner, (entries) async { ... })` expands to
+/// `{ take!(entries); spawner(async move { ... }) }` and evaluates to whatever the spawner
+/// returns, usually a join handle. The macro doesn't know about any runtime: pass
+/// `tokio::task::spawn`, `async_std::task::spawn` or a function of your own. Spawners
+/// normally require a `'static` future, so an entry like `&config` fails with the usual "borrowed
+/// value does not live long enough" error; clone it with `=config` instead.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::future::Future;
+/// use std::sync::Arc;
+/// use std::thread::{self, JoinHandle};
+///
+/// # fn block_on<F: Future>(fut: F) -> F::Output {
+/// #     use std::task::{Context, Poll, Waker};
+/// #     let mut fut = std::pin::pin!(fut);
+/// #     let mut cx = Context::from_waker(Waker::noop());
+/// #     loop {
+/// #         if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+/// #             return out;
+/// #         }
+/// #     }
+/// # }
+/// /// Runs the future on its own thread.
+/// fn spawn<F: Future + Send + 'static>(fut: F) -> JoinHandle<F::Output>
+/// where
+///     F::Output: Send,
+/// {
+///     thread::spawn(move || block_on(fut))
+/// }
+///
+/// let (db, req) = (Arc::new(vec![1, 2, 3]), 1);
+/// let task = spawn_with!(spawn, (=db, req) async { db[req] });
+/// assert_eq!(task.join().unwrap(), 2);
+/// assert_eq!(db.len(), 3); // only a clone was moved into the task
+/// # }
+/// ```
+#[macro_export]
+macro_rules! spawn_with {
+    [$spawner:expr, ($($entry:tt)*) async $body:block] => {{
+        $crate::take![$($entry)*];
+        ($spawner)(async move $body)
+    }};
+}
+
+/// Spawn a task onto the tokio runtime with its own `take!` list.
+///
+/// `spawn_task!((entries) async { ... })` is
+/// [`spawn_with!(::tokio::task::spawn, ...)`](macro.spawn_with.html) and evaluates to the
+/// task's `JoinHandle`. It needs the `tokio` feature, and the calling crate needs a dependency on
+/// `tokio` with the `rt` feature.
+///
+/// ```rust,ignore
+/// let db = Arc::new(Db::connect());
+/// for req in requests {
+///     spawn_task!((=db, req) async { handle(&db, req).await });
+/// }
+/// ```
+#[cfg(feature = "tokio")]
+#[macro_export]
+macro_rules! spawn_task {
+    [($($entry:tt)*) async $body:block] => {
+        $crate::spawn_with![::tokio::task::spawn, ($($entry)*) async $body]
+    };
+}
+
+/// Build a boxed callback with its own `take!` list.
+///
+/// `take_callback!(=data, &config; |n: u32| ...)` takes the entries before the `;`, then boxes a
+/// `move` closure:
+///
+/// ```rust,ignore
+/// let callback: Box<dyn Fn(u32) -> _> = {
+///     take!(=data, &config);
+///     Box::new(move |n: u32| ...)
+/// };
+/// ```
+///
+/// Like [`js_closure!`](macro.js_closure.html), the arguments are names with their types, which
+/// make up the `dyn Fn`. The return type is inferred from the body, or can be given with `->`
+/// followed by a block. The box may borrow what the entries borrow, so it only has to be
+/// `'static` when nothing is borrowed. [`take_callback_mut!`](macro.take_callback_mut.html) and
+/// [`take_callback_once!`](macro.take_callback_once.html) build a `Box<dyn FnMut>` and a
+/// `Box<dyn FnOnce>`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::collections::HashMap;
+///
+/// let (names, prefix) = (vec![String::from("ok"), String::from("cancel")], String::from("btn-"));
+/// let mut handlers: HashMap<String, Box<dyn Fn(u32) -> String>> = HashMap::new();
+/// for name in &names {
+///     let handler = take_callback!(=name, =prefix; |clicks: u32| format!("{}{}:{}", prefix, name, clicks));
+///     handlers.insert(name.clone(), handler);
+/// }
+/// assert_eq!(handlers["ok"](2), "btn-ok:2");
+///
+/// let total = take_callback!(&names; || -> usize { names.iter().map(String::len).sum() });
+/// assert_eq!(total(), 8);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_callback {
+    [$($tokens:tt)*] => {
+        $crate::__take_callback![Fn [] $($tokens)*]
+    };
+}
+
+/// [`take_callback!`](macro.take_callback.html) for a `Box<dyn FnMut>`.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let mut log = Vec::new();
+/// let mut push = take_callback_mut!(&mut log; |line: &'static str| log.push(line));
+/// push("a");
+/// push("b");
+/// drop(push);
+/// assert_eq!(log, ["a", "b"]);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_callback_mut {
+    [$($tokens:tt)*] => {
+        $crate::__take_callback![FnMut [] $($tokens)*]
+    };
+}
+
+/// [`take_callback!`](macro.take_callback.html) for a `Box<dyn FnOnce>`, which can move the taken
+/// values out of the closure.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let buf = vec![1, 2, 3];
+/// let finish = take_callback_once!(buf; |extra: i32| -> Vec<i32> {
+///     let mut buf = buf;
+///     buf.push(extra);
+///     buf
+/// });
+/// assert_eq!(finish(4), [1, 2, 3, 4]);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! take_callback_once {
+    [$($tokens:tt)*] => {
+        $crate::__take_callback![FnOnce [] $($tokens)*]
+    };
+}
+
+/// Used by the `take_callback!` family: collects the `take!` entries up to the `;`, then boxes the
+/// closure as a `dyn $kind`.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __take_callback {
+    [$kind:ident [$($entry:tt)*] ; move $($closure:tt)+] => {
+        $crate::__take_callback![$kind [$($entry)*] ; $($closure)+]
+    };
+    [$kind:ident [$($entry:tt)*] ; || $($rest:tt)+] => {
+        $crate::__take_callback![$kind [$($entry)*] ; | | $($rest)+]
+    };
+    [$kind:ident [$($entry:tt)*] ; |$($arg:ident: $ty:ty),* $(,)?| -> $ret:ty $body:block] => {{
+        $crate::take![$($entry)*];
+        let callback: $crate::__private::Box<dyn $crate::__private::$kind($($ty),*) -> $ret + '_> =
+            $crate::__private::Box::new(move |$($arg: $ty),*| -> $ret { $body });
+        callback
+    }};
+    [$kind:ident [$($entry:tt)*] ; |$($arg:ident: $ty:ty),* $(,)?| $body:expr] => {{
+        $crate::take![$($entry)*];
+        let callback: $crate::__private::Box<dyn $crate::__private::$kind($($ty),*) -> _ + '_> =
+            $crate::__private::Box::new(move |$($arg: $ty),*| $body);
+        callback
+    }};
+    [$kind:ident [$($entry:tt)*] ; $($closure:tt)*] => {
+        compile_error!(concat!(
+            "expected a closure with typed arguments, like `|name: Type| body`, found `",
+            stringify!($($closure)*), "`"
+        ));
+    };
+    [$kind:ident [$($entry:tt)*] $t:tt $($rest:tt)*] => {
+        $crate::__take_callback![$kind [$($entry)* $t] $($rest)*]
+    };
+    [$kind:ident [$($entry:tt)*]] => {
+        compile_error!("expected `;` between the `take!` entries and the closure");
+    };
+}
+
+/// Build a `wasm_bindgen` `Closure` for an event handler with its own `take!` list.
+///
+/// `js_closure!((entries) |event: Event| { ... })` takes the entries, then wraps a `move` closure
+/// with `Closure::wrap(Box::new(...) as Box<dyn FnMut(Event)>)`, so that the handler captures the
+/// listed bindings instead of the whole scope. The arguments are names with their types, which
+/// make up the `dyn FnMut`, and a return type can be given with `->`. `once |...| { ... }` builds
+/// a `Closure::once` instead, for a handler that runs at most once. It needs the `wasm` feature,
+/// and the calling crate needs a dependency on `wasm-bindgen`.
+///
+/// ```rust,ignore
+/// let state = Rc::new(RefCell::new(State::default()));
+/// let on_click = js_closure!((=state, =button) |event: web_sys::Event| {
+///     state.borrow_mut().clicks += 1;
+///     button.set_text_content(Some(&event.type_()));
+/// });
+/// target.add_event_listener_with_callback("click", on_click.as_ref().unchecked_ref())?;
+/// on_click.forget();
+/// ```
+#[cfg(feature = "wasm")]
+#[macro_export]
+macro_rules! js_closure {
+    [($($entry:tt)*) once || $(-> $ret:ty)? $body:block] => {
+        $crate::js_closure![($($entry)*) once | | $(-> $ret)? $body]
+    };
+    [($($entry:tt)*) once |$($arg:ident: $ty:ty),* $(,)?| $(-> $ret:ty)? $body:block] => {{
+        $crate::take![$($entry)*];
+        ::wasm_bindgen::closure::Closure::<dyn $crate::__private::FnMut($($ty),*) $(-> $ret)?>::once(
+            move |$($arg: $ty),*| $(-> $ret)? { $body }
+        )
+    }};
+    [($($entry:tt)*) || $(-> $ret:ty)? $body:block] => {
+        $crate::js_closure![($($entry)*) | | $(-> $ret)? $body]
+    };
+    [($($entry:tt)*) |$($arg:ident: $ty:ty),* $(,)?| $(-> $ret:ty)? $body:block] => {{
+        $crate::take![$($entry)*];
+        ::wasm_bindgen::closure::Closure::wrap(
+            $crate::__private::Box::new(move |$($arg: $ty),*| $(-> $ret)? { $body })
+                as $crate::__private::Box<dyn $crate::__private::FnMut($($ty),*) $(-> $ret)?>
+        )
+    }};
+}
+
+/// Take a channel `Sender` that must be used exactly once.
+///
+/// `take_sender!(tx)` wraps `tx` in a [`SendOnce`](struct.SendOnce.html), whose `send` consumes
+/// it. Sending twice is then a "use of moved value" error, and `SendOnce` can't be cloned. In
+/// debug builds, dropping it without sending panics, which catches a worker that returns early
+/// without reporting back. `=tx` wraps a clone, which is what every spawned thread usually gets.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use std::sync::mpsc::channel;
+/// use std::thread;
+///
+/// let (tx, rx) = channel();
+/// let workers: Vec<_> = (0..3)
+///     .map(|i| {
+///         take_sender!(=tx as done); // let done = SendOnce::new(tx.clone());
+///         thread::spawn(move || done.send(i * 2).unwrap())
+///     })
+///     .collect();
+/// take_sender!(tx);
+/// tx.send(6).unwrap();
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+/// let mut results: Vec<u32> = rx.iter().collect();
+/// results.sort();
+/// assert_eq!(results, vec![0, 2, 4, 6]);
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! take_sender {
+    // ---------------------
+    // ----- with rest -----
+    [$(#[$attr:meta])* $var:ident $(as $v:ident)?, $($rest:tt)*] => {
+        $crate::take_sender![$(#[$attr])* $var $(as $v)?];
+        $crate::take_sender![$($rest)*]
+    };
+    [$(#[$attr:meta])* =$var:ident $(as $v:ident)?, $($rest:tt)*] => {
+        $crate::take_sender![$(#[$attr])* =$var $(as $v)?];
+        $crate::take_sender![$($rest)*]
+    };
+
+    // ------------------------
+    // ----- without rest -----
+    [$(#[$attr:meta])* $var:ident] => {
+        $(#[$attr])* let $var = $crate::SendOnce::new($var);
+        $crate::__take_trace!($(#[$attr])* "sender", $var);
+    };
+    [$(#[$attr:meta])* $var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::SendOnce::new($var);
+        $crate::__take_trace!($(#[$attr])* "sender", $v);
+    };
+    [$(#[$attr:meta])* =$var:ident] => {
+        $(#[$attr])* let $var = $crate::SendOnce::new($crate::__take_clone!($var));
+        $crate::__take_trace!($(#[$attr])* "= sender", $var);
+    };
+    [$(#[$attr:meta])* =$var:ident as $v:ident] => {
+        $(#[$attr])* let $v = $crate::SendOnce::new($crate::__take_clone!($var));
+        $crate::__take_trace!($(#[$attr])* "= sender", $v);
+    };
+
+    // trailing comma
+    [] => {};
+}
+
+/// Send a value on a channel, panicking if the receiver is gone.
+///
+/// `send_value!(tx, expr)` is `tx.send(expr).unwrap()`, with a message naming the sender. The
+/// sender is only borrowed, so `tx` can keep being used. The value isn't evaluated until it is
+/// sent. `send` is the one method looked up on `tx`, so that any channel whose `send` returns a
+/// `Result` works.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// send_value!(tx, 1 + 1);
+/// send_value!(tx, 3);
+/// assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![2, 3]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! send_value {
+    [$tx:expr, $value:expr $(,)?] => {
+        $crate::__private::Fallible::expect(
+            $tx.send($value),
+            concat!("the receiver of `", stringify!($tx), "` was dropped"),
+        )
+    };
+}
+
+/// Guard that lends out a value and restores it when dropped.
+///
+/// This is used by [`with_taken!`](macro.with_taken.html). The value is swapped out of its place
+/// for `Default::default()` on creation and written back on drop, including during unwinding.
+///
+/// ```rust
+/// use taken::Restore;
+///
+/// let mut x = vec![1, 2];
+/// {
+///     let mut lent = Restore::new(&mut x);
+///     lent.push(3);
+/// }
+/// assert_eq!(x, vec![1, 2, 3]);
+/// ```
+pub struct Restore<'a, T: 'a> {
+    place: &'a mut T,
+    value: Option<T>,
+}
+
+impl<'a, T: Default> Restore<'a, T> {
+    /// Take the value out of `place`, leaving `Default::default()` until the guard is dropped.
+    pub fn new(place: &'a mut T) -> Restore<'a, T> {
+        let value = ::core::mem::take(place);
+        Restore {
+            place,
+            value: Some(value),
+        }
+    }
+}
+
+impl<'a, T> ::core::ops::Deref for Restore<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("value is present until drop")
+    }
+}
+
+impl<'a, T> ::core::ops::DerefMut for Restore<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("value is present until drop")
+    }
+}
+
+impl<'a, T> Drop for Restore<'a, T> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            *self.place = value;
+        }
+    }
+}
+
+/// Marker that `take!(not x)` binds to `x`, so that using `x` afterwards fails to compile.
+///
+/// It implements no traits and has no methods. Calling a method on an excluded variable fails
+/// with "no method named `len` found for struct `NotCaptured`", and passing it where a value is
+/// expected with "mismatched types ... found `NotCaptured`". Either way, the error names the
+/// variable that wasn't supposed to be used.
+pub struct NotCaptured;
+
+/// A field of a struct that hasn't been taken out of its `take_builder()` yet.
+///
+/// `#[derive(TakeBuilder)]` wraps every field in an `Untaken` when the builder is created from
+/// the struct by value, so that each of them can only be taken once. The fields that are still
+/// wrapped when the builder is built can be unwrapped with `.0`.
+pub struct Untaken<T>(pub T);
+
+/// Guard that makes sure a value is consumed.
+///
+/// In debug builds, dropping a `Taken` without calling [`into_inner`](#method.into_inner) panics
+/// with the location where it was created. Release builds drop the value quietly. The value can
+/// be used through `Deref` in the meantime.
+///
+/// ```rust,should_panic
+/// use taken::Taken;
+///
+/// fn register(_callback: impl FnOnce()) {
+///     // oops, the callback is never called
+/// }
+///
+/// let buf = Taken::new(vec![1, 2, 3]);
+/// register(move || drop(buf.into_inner()));
+/// // panics: value taken at <file>:<line>:<column> was dropped without `Taken::into_inner`
+/// ```
+///
+/// [`take!(must x)`](macro.take.html) is a shorthand for `let x = Taken::new(x);`.
+pub struct Taken<T> {
+    value: Option<T>,
+    location: &'static ::core::panic::Location<'static>,
+}
+
+impl<T> Taken<T> {
+    /// Wrap `value`, remembering where this was called from.
+    #[track_caller]
+    pub fn new(value: T) -> Taken<T> {
+        Taken {
+            value: Some(value),
+            location: ::core::panic::Location::caller(),
+        }
+    }
+
+    /// Consume the guard, returning the value.
+    pub fn into_inner(mut self) -> T {
+        self.value.take().expect("value is present until consumed")
+    }
+}
+
+impl<T> ::core::ops::Deref for Taken<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("value is present until consumed")
+    }
+}
+
+impl<T> ::core::ops::DerefMut for Taken<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("value is present until consumed")
+    }
+}
+
+impl<T> Drop for Taken<T> {
+    fn drop(&mut self) {
+        // don't turn an unrelated panic into an abort
+        #[cfg(feature = "std")]
+        let panicking = ::std::thread::panicking();
+        #[cfg(not(feature = "std"))]
+        let panicking = false;
+
+        if cfg!(debug_assertions) && self.value.is_some() && !panicking {
+            panic!(
+                "value taken at {} was dropped without `Taken::into_inner`",
+                self.location
+            );
+        }
+    }
+}
+
+/// A channel `Sender` that can send exactly one value.
+///
+/// `send` consumes it, so a second send doesn't compile, and it doesn't implement `Clone`. In debug
+/// builds, dropping it without sending panics with the location where it was created, like
+/// [`Taken`](struct.Taken.html).
+///
+/// ```rust,should_panic
+/// use std::sync::mpsc::channel;
+/// use taken::SendOnce;
+///
+/// let (tx, _rx) = channel::<u32>();
+/// let done = SendOnce::new(tx);
+/// let work = move |fail: bool| {
+///     if fail {
+///         return; // oops, `done` is dropped without sending
+///     }
+///     done.send(1).unwrap();
+/// };
+/// work(true);
+/// // panics: sender taken at <file>:<line>:<column> was dropped without sending
+/// ```
+///
+/// [`take_sender!(tx)`](macro.take_sender.html) is a shorthand for `let tx = SendOnce::new(tx);`.
+#[cfg(feature = "std")]
+pub struct SendOnce<T> {
+    tx: Option<::std::sync::mpsc::Sender<T>>,
+    location: &'static ::core::panic::Location<'static>,
+}
+
+#[cfg(feature = "std")]
+impl<T> SendOnce<T> {
+    /// Wrap `tx`, remembering where this was called from.
+    #[track_caller]
+    pub fn new(tx: ::std::sync::mpsc::Sender<T>) -> SendOnce<T> {
+        SendOnce {
+            tx: Some(tx),
+            location: ::core::panic::Location::caller(),
+        }
+    }
+
+    /// Send `value`, consuming the sender. Fails like `Sender::send` if the receiver is gone.
+    pub fn send(mut self, value: T) -> Result<(), ::std::sync::mpsc::SendError<T>> {
+        self.tx.take().expect("sender is present until used").send(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Drop for SendOnce<T> {
+    fn drop(&mut self) {
+        // don't turn an unrelated panic into an abort
+        if cfg!(debug_assertions) && self.tx.is_some() && !::std::thread::panicking() {
+            panic!(
+                "sender taken at {} was dropped without sending",
+                self.location
+            );
+        }
+    }
+}
+
+// Every test below runs twice, through `take!` in the `take` module and through `own!` in the
+// `own` module, where a local `take!` forwards to it.
+#[cfg(test)]
+macro_rules! sanity_tests {
+    [($d:tt) $($tests:tt)*] => {
+        mod take {
+            use super::*;
+            $($tests)*
+        }
+        mod own {
+            use super::*;
+            macro_rules! take {
+                [$d($d tokens:tt)*] => {
+                    $crate::own![$d($d tokens)*]
+                };
+            }
+            $($tests)*
+        }
+    };
+    [$($tests:tt)*] => {
+        sanity_tests![($) $($tests)*];
+    };
+}
+
+#[cfg(test)]
+sanity_tests! {
+
+#[test]
+#[allow(unused_mut, unused_variables, unused_assignments)]
+fn sanity_syntax() {
+    let x = 1;
+    take!(x);
+    assert_eq!(x, 1);
+
+    take!(mut x);
+    x = 2;
+    assert_eq!(x, 2);
+
+    {
         take!(&mut x);
         *x = 1;
         assert_eq!(*x, 1);
     }
 
-    {
-        take!(&x);
-        assert_eq!(*x, 1);
+    {
+        take!(&x);
+        assert_eq!(*x, 1);
+    }
+
+    {
+        take!(=x);
+        assert_eq!(x, 1);
+    }
+
+    {
+        take!(=mut x);
+        x = 2;
+        assert_eq!(x, 2);
+    }
+    assert_eq!(x, 1);
+}
+
+#[test]
+#[allow(unused_mut, unused_variables, unused_assignments)]
+fn sanity_syntax_comma() {
+    // all of these just have trailing commas, which actually tests the `rest` block as well.
+    let x = 1;
+    take!(x,);
+    assert_eq!(x, 1);
+
+    take!(mut x,);
+    x = 2;
+    assert_eq!(x, 2);
+
+    {
+        take!(&mut x,);
+        *x = 1;
+        assert_eq!(*x, 1);
+    }
+
+    {
+        take!(&x,);
+        assert_eq!(*x, 1);
+    }
+
+    {
+        take!(=x,);
+        assert_eq!(x, 1);
+    }
+
+    {
+        take!(=mut x,);
+        x = 2;
+        assert_eq!(x, 2);
+    }
+    assert_eq!(x, 1);
+}
+
+#[test]
+#[allow(unused_mut, unused_variables, unused_assignments)]
+fn sanity_syntax_as() {
+    let mut x = 1;
+    {
+        take!(x as y);
+        assert_eq!(x, y);
+    }
+
+    {
+        take!(mut x as y);
+        y = 2;
+        assert_eq!(y, 2);
+    }
+
+    {
+        take!(&mut x as y);
+        *y = 2;
+        assert_eq!(*y, 2);
+    }
+    assert_eq!(x, 2);
+    x = 1;
+
+    {
+        take!(&x as y);
+        assert_eq!(*y, 1);
+    }
+
+    {
+        take!(=x as y);
+        assert_eq!(x, y);
+    }
+
+    {
+        take!(=mut x as y);
+        y = 2;
+        assert_eq!(y, 2);
+    }
+    assert_eq!(x, 1);
+}
+
+
+#[test]
+#[allow(unused_mut, unused_variables, unused_assignments)]
+fn sanity_multi() {
+    {
+        let (x, y, z) = (1, 2, 3);
+        take!(x, y, z);
+    }
+    {
+        let (x, y, z) = (1, 2, 3);
+        take!(mut x, y, z);
+    }
+    {
+        let (x, y, z) = (1, 2, 3);
+        take!(mut x, mut y, z);
+    }
+    {
+        let (x, y, z) = (1, 2, 3);
+        take!(mut x, mut y, mut z);
+    }
+
+    {
+        let (x, y, z) = (1, 2, 3);
+        take!(
+            mut x,
+            mut y,
+            mut z
+        );
+
+        take!(
+            &x,
+            &mut y,
+            z
+        );
+    }
+
+    {
+        // with trailing comma
+        let (x, y, z) = (1, 2, 3);
+        take!(
+            x,
+            y,
+            z,
+        );
+    }
+}
+
+#[test]
+#[allow(unused_mut, unused_variables, unused_assignments)]
+fn sanity_ref() {
+    let mut x = 1;
+    {
+        take!(ref mut x);
+        *x = 2;
+    }
+    {
+        take!(ref x);
+        assert_eq!(*x, 2);
+    }
+    {
+        take!(ref mut x,);
+        *x = 1;
+    }
+    {
+        take!(ref x,);
+        assert_eq!(*x, 1);
+    }
+    {
+        take!(ref mut x as y);
+        *y = 2;
+    }
+    {
+        take!(ref x as y);
+        assert_eq!(*y, 2);
+    }
+    {
+        take!(ref mut x as y,);
+        *y = 1;
+    }
+    {
+        take!(ref x as y,);
+        assert_eq!(*y, 1);
+    }
+
+    // both spellings expand to the same thing and can be mixed
+    let (mut y, z) = (vec![1], String::from("z"));
+    {
+        take!(ref mut x, &mut y as a, ref z as b, &z as c);
+        let (x, a, b, c): (&mut i32, &mut Vec<i32>, &String, &String) = (x, a, b, c);
+        *x += 1;
+        a.push(2);
+        assert_eq!(b, c);
+    }
+    {
+        take!(&mut x as a, ref mut y, &z as b, ref z);
+        let (a, y, b, z): (&mut i32, &mut Vec<i32>, &String, &String) = (a, y, b, z);
+        *a += 1;
+        y.push(3);
+        assert_eq!(b, z);
+    }
+    assert_eq!((x, y), (3, vec![1, 2, 3]));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sanity_move_into() {
+    use std::fmt::Debug;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    let (a, b, c) = (1, String::from("b"), vec![3]);
+    move_into!(a: Box<_>, b as shared: Arc<_>, mut c: Rc<Vec<u32>>,);
+    let (a, shared): (Box<i32>, Arc<String>) = (a, shared);
+    Rc::make_mut(&mut c).push(4);
+    assert_eq!((*a, shared.as_str(), &*c), (1, "b", &vec![3, 4]));
+
+    #[cfg(feature = "std")]
+    {
+        let d = 4u8;
+        move_into!(d: Mutex<u8>);
+        *d.lock().unwrap() += 1;
+        assert_eq!(*d.lock().unwrap(), 5);
+    }
+
+    let (e, f) = (5u32, "f");
+    move_into!(mut e as boxed: Box<dyn Debug>, f: Arc<dyn Debug + Send + Sync>);
+    assert_eq!(format!("{:?} {:?}", boxed, f), "5 \"f\"");
+    boxed = Box::new(6);
+    assert_eq!(format!("{:?}", boxed), "6");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sanity_share() {
+    use std::sync::Arc;
+
+    let x = vec![1, 2, 3];
+    share!(x => a);
+    assert_eq!(Arc::strong_count(&x), 2);
+    assert_eq!(*a, vec![1, 2, 3]);
+
+    let y = String::from("shared");
+    share!(y => b, c, d,);
+    assert_eq!(Arc::strong_count(&y), 4);
+    assert!(Arc::ptr_eq(&y, &b));
+    assert!(Arc::ptr_eq(&c, &d));
+    drop((b, c, d));
+    assert_eq!(Arc::strong_count(&y), 1);
+}
+
+#[test]
+#[allow(unused_mut, unused_variables, unused_assignments)]
+fn sanity_replace() {
+    let mut x = 1;
+    {
+        take!(x <- 2);
+        assert_eq!(x, 1);
+    }
+    assert_eq!(x, 2);
+
+    {
+        take!(mut x <- 3);
+        x = 10;
+        assert_eq!(x, 10);
+    }
+    assert_eq!(x, 3);
+
+    {
+        take!(x <- 4 as y);
+        assert_eq!(y, 3);
+    }
+    assert_eq!(x, 4);
+
+    {
+        take!(mut x <- 5 as y,);
+        y += 1;
+        assert_eq!(y, 5);
+    }
+    assert_eq!(x, 5);
+
+    // the replacement may refer to the variable being replaced
+    let mut buf = Vec::with_capacity(16);
+    buf.push(1);
+    let mut count = 0;
+    take!(
+        buf <- Vec::with_capacity(buf.capacity()) as old_buf,
+        count <- count + 1,
+    );
+    assert_eq!(old_buf, vec![1]);
+    assert!(buf.is_empty());
+    assert!(buf.capacity() >= 16);
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn sanity_replace_self() {
+    #[derive(Debug, PartialEq)]
+    enum State {
+        Idle,
+        Running(u32),
+    }
+
+    struct Machine {
+        state: State,
+    }
+
+    impl Machine {
+        fn stop(&mut self) -> State {
+            take!(self.state <- State::Idle as previous);
+            previous
+        }
+    }
+
+    let mut machine = Machine { state: State::Running(7) };
+    assert_eq!(machine.stop(), State::Running(7));
+    assert_eq!(machine.state, State::Idle);
+    assert_eq!(machine.stop(), State::Idle);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[allow(unused_mut, unused_variables)]
+fn sanity_leak() {
+    fn assert_static<T: ?Sized>(_: &'static T) {}
+
+    let x = vec![1, 2, 3];
+    leak!(x);
+    assert_static(x);
+    assert_eq!(*x, vec![1, 2, 3]);
+
+    let x = 1;
+    leak!(mut x as y,);
+    *y = 2;
+    assert_static(y);
+    assert_eq!(*y, 2);
+
+    let (a, b) = (String::from("a"), Box::new(String::from("b")));
+    leak!(a as static_a);
+    leak_box!(mut b,);
+    b.push_str(static_a);
+    assert_static(b);
+    assert_eq!(b.as_str(), "ba");
+
+    let c: Box<str> = "c".into();
+    leak_box!(c as static_c);
+    let c: &'static str = static_c;
+    assert_eq!(c, "c");
+}
+
+
+#[test]
+#[allow(unused_mut, unused_variables, unused_assignments)]
+fn sanity_fallible() {
+    fn question(x: Result<u32, ()>, y: Option<u32>) -> Result<u32, ()> {
+        let y = y.ok_or(());
+        take!(x?, mut y? as z);
+        z += x;
+        Ok(z)
+    }
+    assert_eq!(question(Ok(1), Some(2)), Ok(3));
+    assert_eq!(question(Err(()), Some(2)), Err(()));
+    assert_eq!(question(Ok(1), None), Err(()));
+
+    fn option(x: Option<u32>) -> Option<u32> {
+        take!(mut x?,);
+        x += 1;
+        Some(x)
+    }
+    assert_eq!(option(Some(1)), Some(2));
+    assert_eq!(option(None), None);
+
+    fn prefix(x: Result<u32, ()>, y: Result<u32, ()>) -> Result<u32, ()> {
+        take!(?x as a, ?mut y,);
+        y += a;
+        take!(?mut x);
+        x += y;
+        Ok(x)
+    }
+    assert_eq!(prefix(Ok(1), Ok(2)), Ok(4));
+    assert_eq!(prefix(Ok(1), Err(())), Err(()));
+
+    let (x, y) = (Some(1), Ok::<_, ()>(2));
+    {
+        take!(!x, !mut y as z,);
+        z += x;
+        assert_eq!(z, 3);
+    }
+    {
+        take!(!x or "x is set" as a, !mut y or "y is set" as z);
+        z += a;
+        assert_eq!(z, 3);
+    }
+    {
+        take!(!mut x as a, !y or "y is set" as b);
+        a += b;
+        assert_eq!(a, 3);
+    }
+
+    let none: Option<u32> = None;
+    let result = std::panic::catch_unwind(|| {
+        take!(!none or "none is unset");
+    });
+    assert_eq!(*result.unwrap_err().downcast::<String>().unwrap(), "none is unset");
+
+    let mut seen = Vec::new();
+    for (x, y) in [(Some(1), Some(2)), (None, Some(3)), (Some(4), None)] {
+        take!(
+            x else continue,
+            mut y else { seen.push(0); continue } as z,
+        );
+        z += x;
+        seen.push(z);
+    }
+    assert_eq!(seen, vec![3, 0]);
+
+    fn pattern(x: Result<u32, ()>) -> u32 {
+        take!(Ok(mut v) = x else return 0);
+        v += 1;
+        v
+    }
+    assert_eq!(pattern(Ok(1)), 2);
+    assert_eq!(pattern(Err(())), 0);
+
+    fn slice(x: &[u32], y: Option<u32>) -> u32 {
+        take!(
+            [first, .., last] = x else return 0,
+            std::option::Option::Some(y) = y else return 1,
+        );
+        first + last + y
+    }
+    assert_eq!(slice(&[1, 2, 3], Some(1)), 5);
+    assert_eq!(slice(&[], Some(1)), 0);
+    assert_eq!(slice(&[1, 2], None), 1);
+}
+
+#[test]
+#[allow(unused_mut, unused_variables)]
+fn sanity_deref() {
+    let x = Box::new(String::from("boxed"));
+    take!(*x);
+    let x: String = x;
+    assert_eq!(x, "boxed");
+
+    let x = Box::new(vec![1, 2]);
+    take!(*mut x);
+    x.push(3);
+    assert_eq!(x, vec![1, 2, 3]);
+
+    let (x, y) = (Box::new(String::from("x")), Box::new(vec![1]));
+    take!(*x as a, *mut y as b,);
+    b.push(2);
+    assert_eq!(a, "x");
+    assert_eq!(b, vec![1, 2]);
+
+    // references to `Copy` types are copied out
+    let v = 7;
+    let r = &v;
+    take!(*r as copied);
+    assert_eq!(copied, 7);
+}
+
+#[test]
+#[allow(unused_mut, unused_variables)]
+fn sanity_deref_reborrow() {
+    use std::sync::Mutex;
+
+    let s = String::from("text");
+    {
+        take!(&*s);
+        assert_eq!(s, "text");
+    }
+    {
+        take!(&*s as text: &str, &*s: &str,);
+        let text: &str = text;
+        assert_eq!(text, s);
+    }
+    assert_eq!(s, "text");
+
+    let lock = Mutex::new(vec![1]);
+    let mut guard = lock.lock().unwrap();
+    {
+        take!(&mut *guard as inner: &mut Vec<i32>);
+        inner.push(2);
+    }
+    {
+        take!(&mut *guard);
+        guard.push(3);
+    }
+    {
+        take!(&mut *guard: &mut [i32], &*guard as view);
+    }
+    assert_eq!(*guard, vec![1, 2, 3]);
+}
+
+#[test]
+#[allow(unused_variables)]
+fn sanity_must() {
+    use std::panic::catch_unwind;
+
+    let (x, y) = (vec![1], String::from("y"));
+    take!(must x, must y as z,);
+    assert_eq!(x.len(), 1);
+    assert_eq!(x.into_inner(), vec![1]);
+    assert_eq!(z.into_inner(), "y");
+
+    let mut w = Taken::new(1);
+    *w += 1;
+    assert_eq!(w.into_inner(), 2);
+
+    // the panic points at the `take!`
+    let line = line!() + 3;
+    let result = catch_unwind(|| {
+        let never = 1;
+        take!(must never);
+    });
+    let msg = *result.unwrap_err().downcast::<String>().unwrap();
+    let location = format!("{}:{}:", file!(), line);
+    assert!(msg.starts_with(&format!("value taken at {}", location)), "{}", msg);
+}
+
+#[cfg(test)]
+mod recorder {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Records the order in which values are dropped.
+    pub struct Recorder(pub &'static str, pub Rc<RefCell<Vec<&'static str>>>);
+
+    impl Drop for Recorder {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+        }
+    }
+
+    pub fn log() -> Rc<RefCell<Vec<&'static str>>> {
+        Rc::new(RefCell::new(Vec::new()))
+    }
+}
+
+#[cfg(test)]
+mod executor {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    fn noop_raw() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw()
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+
+    /// Polls a future once with a waker that does nothing.
+    pub fn poll<F: Future + ?Sized>(fut: Pin<&mut F>) -> Poll<F::Output> {
+        let waker = unsafe { Waker::from_raw(noop_raw()) };
+        fut.poll(&mut Context::from_waker(&waker))
+    }
+
+    /// Polls a future until it is ready.
+    pub fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = std::pin::pin!(fut);
+        loop {
+            if let Poll::Ready(out) = poll(fut.as_mut()) {
+                return out;
+            }
+        }
+    }
+
+    /// Returns `Pending` once, so that the caller is suspended at the `.await`.
+    pub async fn yield_now() {
+        let mut yielded = false;
+        std::future::poll_fn(|_| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+#[test]
+#[allow(unused_mut)]
+fn sanity_pin() {
+    use executor::poll;
+    use std::task::Poll;
+
+    let (a, b) = (async { 1 }, async { 2 });
+    take!(pin mut a, pin b as pinned_b);
+    assert_eq!(poll(a.as_mut()), Poll::Ready(1));
+    assert_eq!(poll(pinned_b), Poll::Ready(2));
+
+    // the boxed forms can be moved around
+    #[cfg(feature = "alloc")]
+    {
+        use std::future::Future;
+        use std::pin::Pin;
+
+        let (c, d) = (async { 3 }, async { 4 });
+        take!(pin box c, pin box mut d as boxed_d);
+        let mut boxed: Vec<Pin<Box<dyn Future<Output = i32>>>> = vec![c, boxed_d];
+        let polled: Vec<_> = boxed.iter_mut().map(|f| poll(f.as_mut())).collect();
+        assert_eq!(polled, vec![Poll::Ready(3), Poll::Ready(4)]);
+    }
+}
+
+#[test]
+#[allow(deprecated)] // `=db` clones an `Arc` on purpose
+fn sanity_async_capture() {
+    use executor::{block_on, poll, yield_now};
+    use std::sync::Arc;
+    use std::task::Poll;
+
+    let (db, config, state) = (Arc::new(vec![1, 2]), String::from("x"), vec![0]);
+    let fut = async_capture!([=db, &config, mut state] {
+        yield_now().await;
+        let (_, _, _): (&Arc<Vec<i32>>, &String, &mut Vec<i32>) = (&db, config, &mut state);
+        state.extend(db.iter().map(|v| v * config.len() as i32));
+        state
+    });
+    assert_eq!(Arc::strong_count(&db), 2);
+    let mut fut = std::pin::pin!(fut);
+    assert_eq!(poll(fut.as_mut()), Poll::Pending);
+    assert_eq!(poll(fut.as_mut()), Poll::Ready(vec![0, 1, 2]));
+    assert_eq!(Arc::strong_count(&db), 1);
+
+    let parse = |s: &'static str| {
+        async_capture!([s] -> Result<i32, std::num::ParseIntError> {
+            yield_now().await;
+            let n: i32 = s.parse()?;
+            Ok(n * 2)
+        })
+    };
+    assert_eq!(block_on(parse("21")), Ok(42));
+    assert!(block_on(parse("x")).is_err());
+}
+
+#[test]
+#[allow(deprecated)] // `=db` clones an `Arc` on purpose
+fn sanity_spawn_with() {
+    use executor::block_on;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::thread::{self, JoinHandle};
+
+    fn spawn<F: Future + Send + 'static>(fut: F) -> JoinHandle<F::Output>
+    where
+        F::Output: Send,
+    {
+        thread::spawn(move || block_on(fut))
+    }
+
+    let (db, req, log) = (Arc::new(vec![1, 2, 3]), 2, Vec::new());
+    let task = spawn_with!(spawn, (=db, req, mut log) async {
+        executor::yield_now().await;
+        log.push(db[req]);
+        log
+    });
+    assert_eq!(task.join().unwrap(), vec![3]);
+    assert_eq!(Arc::strong_count(&db), 1);
+
+    // any callable works, and the spawner's output is returned as-is
+    let count = spawn_with!(|fut| Some(block_on(fut)), (=db) async { db.len() });
+    assert_eq!(count, Some(3));
+}
+
+#[test]
+fn sanity_hold_drop() {
+    use recorder::{log, Recorder};
+
+    let dropped = log();
+    let (a, b, c) = (
+        Recorder("a", dropped.clone()),
+        Recorder("b", dropped.clone()),
+        Recorder("c", dropped.clone()),
+    );
+    let body = dropped.clone();
+    let closure = move || {
+        take!(hold a, drop b, hold c as held);
+        body.borrow_mut().push("body");
+    };
+    closure();
+    assert_eq!(*dropped.borrow(), vec!["b", "body", "c", "a"]);
+}
+
+#[test]
+#[allow(unused_mut)]
+fn sanity_take_map() {
+    use std::convert::TryInto;
+
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    let (a, b, c) = (1, 2u64, vec![1, 2]);
+    take_map!(a => double, b => |b: u64| -> u8 { b.try_into().unwrap() });
+    let b: u8 = b;
+    assert_eq!((a, b), (2, 2));
+
+    take_map!(
+        mut a as x => double,
+        c as total => |c: Vec<u32>| c.iter().map(|v| v * 10).sum::<u32>(),
+        b as c => |b: u8| vec![u32::from(b)],
+        mut c => |mut c: Vec<u32>| { c.push(3); c },
+    );
+    x += 1;
+    c.push(4);
+    assert_eq!((x, total, c), (5, 30, vec![2, 3, 4]));
+    take_map!(a => Some,);
+    assert_eq!(a, Some(2));
+}
+
+#[test]
+fn sanity_method_call() {
+    struct User {
+        name: String,
+        visits: u32,
+    }
+    impl User {
+        fn name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    let mut user = User {
+        name: String::from("liam"),
+        visits: 0,
+    };
+    let path = std::path::PathBuf::from("/tmp/taken");
+    let greet = {
+        take!(
+            user.name().to_string() as name,
+            path.file_name().unwrap().to_owned() as file,
+            mut user.visits as visits,
+        );
+        visits += 1;
+        move || format!("{} {:?} {}", name, file, visits)
+    };
+    // the closure only captured the extracted values
+    user.name.push_str(" neeson");
+    user.visits += 10;
+    assert_eq!(greet(), "liam \"taken\" 1");
+    assert_eq!((user.name(), user.visits), ("liam neeson", 10));
+
+    let buf = vec![1, 2, 3];
+    take!(#[allow(unused_mut)] mut buf.len() as len, &buf, buf.iter().sum::<u32>() as sum);
+    assert_eq!((len, sum, buf.len()), (3, 6, 3));
+}
+
+#[test]
+fn sanity_scoped() {
+    fn apply<F: Fn(u32) -> u32>(f: F, v: u32) -> u32 {
+        f(v)
+    }
+    fn adder(offset: u32) -> impl Fn(u32) -> u32 {
+        take!({ offset as by } => move |v| v + by)
+    }
+
+    let (names, count) = (vec![String::from("a")], 3);
+    let clone_names = take!({ =names, count as limit, } => move || {
+        let names: Vec<String> = names;
+        names.into_iter().take(limit).collect::<Vec<_>>()
+    });
+    assert_eq!(clone_names(), vec!["a"]);
+    // the originals aren't shadowed
+    let (_, _): (&Vec<String>, &usize) = (&names, &count);
+
+    assert_eq!(apply(take!({ &names } => move |v| v + names.len() as u32), 1), 2);
+    assert_eq!(adder(2)(3), 5);
+    assert_eq!(take!({ =names } => names), vec!["a"]);
+}
+
+#[test]
+fn sanity_scoped_block() {
+    fn first_even(items: &[&str]) -> Result<Option<i32>, std::num::ParseIntError> {
+        for item in items {
+            let v = take!((item as text) {
+                let v: i32 = text.parse()?;
+                if v == 0 {
+                    return Ok(None);
+                }
+                if v % 2 != 0 {
+                    continue;
+                }
+                v
+            });
+            return Ok(Some(v));
+        }
+        Ok(None)
+    }
+
+    let (mut stats, snapshot, items) = (vec![1], vec![2], vec!["a", "b"]);
+    let n = take!((&items) { items.len() });
+    assert_eq!(n, 2);
+    take!((&mut stats, =snapshot as copy,) {
+        let stats: &mut Vec<i32> = stats;
+        stats.extend(copy);
+    });
+    // the `&mut` ended at the brace
+    stats.push(3);
+    assert_eq!((stats, snapshot), (vec![1, 2, 3], vec![2]));
+
+    assert_eq!(first_even(&["1", "4", "x"]), Ok(Some(4)));
+    assert_eq!(first_even(&["3", "0", "4"]), Ok(None));
+    assert!(first_even(&["x"]).is_err());
+}
+
+#[test]
+fn sanity_slice_pattern() {
+    let (v, mut arr, boxed) = (vec![1, 2, 3], [4, 5], vec![6].into_boxed_slice());
+    take!([a] = boxed, [first, .., last] = v, [x, rest @ ..] = v,);
+    let _: (&i32, &i32, &[i32]) = (first, x, rest);
+    assert_eq!((*a, *first, *last, *x, rest), (6, 1, 3, 1, &[2, 3][..]));
+    {
+        take!(mut [y, ref z] = arr);
+        let _: (&mut i32, &i32) = (y, z);
+        *y += *z;
+    }
+    take!(mut [.., w] = arr);
+    *w = 0;
+    assert_eq!(arr, [9, 0]);
+}
+
+#[test]
+#[should_panic(expected = "`v` doesn't match `[a, b]`: it has 3 elements")]
+fn sanity_slice_pattern_mismatch() {
+    let v: &[i32] = &[1, 2, 3];
+    take!([a, b] = v);
+    let _ = (a, b);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[allow(unused_mut)]
+fn sanity_cow() {
+    use std::borrow::Cow;
+
+    fn len<'a>(s: impl Into<Cow<'a, str>>) -> usize {
+        s.into().len()
+    }
+
+    let (a, b, c, d) = (String::from("a"), vec![1u8], String::from("cc"), vec![2u8]);
+    take!(cow a, cow b as bytes, cow own mut c: Cow<str>, cow own d as owned: Cow<[u8]>,);
+    assert!(matches!((&a, &bytes), (Cow::Borrowed(_), Cow::Borrowed(_))));
+    assert!(matches!((&c, &owned), (Cow::Owned(_), Cow::Owned(_))));
+    c.to_mut().push('c');
+    assert_eq!((len(a), len(c), &*bytes, &*owned), (1, 3, &[1][..], &[2][..]));
+
+    let (e, f) = ("e", String::from("f"));
+    take!(cow mut e, cow mut f as g: Cow<str>);
+    let _: (Cow<str>, Cow<str>) = (e.clone(), g.clone());
+    e.to_mut().push('!');
+    assert_eq!((len(e), len(g), f.as_str()), (2, 1, "f"));
+}
+
+#[test]
+fn sanity_shadow() {
+    let (a, b, c) = (1u32, String::from(" b "), vec![1, 2]);
+    shadow!(a = a + 1);
+    assert_eq!(a, 2);
+    shadow!(
+        a = (a as u64) * 2,
+        b = b.trim().to_string() as trimmed,
+        mut c = c.into_iter().map(|v| v * 10).collect::<Vec<_>>(),
+        mut d = [a, 1].len() as len,
+    );
+    c.push(30);
+    len += 1;
+    let _: (u64, &str, &String) = (a, &b, &trimmed);
+    assert_eq!((a, trimmed.as_str(), c, len), (4, "b", vec![10, 20, 30], 3));
+}
+
+#[test]
+fn sanity_protect() {
+    let (mut a, b, mut c) = (vec![1], String::from("b"), 0usize);
+    let n = protect!(a { a.len() });
+    protect!(a, b, {
+        let _: (&Vec<i32>, &String) = (a, b);
+    });
+    protect_mut!(a, c {
+        let _: (&mut Vec<i32>, &mut usize) = (a, c);
+        a.push(2);
+        *c += n;
+    });
+    // nothing was moved
+    a.push(3);
+    assert_eq!((a, b.as_str(), c), (vec![1, 2, 3], "b", 1));
+}
+
+#[test]
+#[allow(unused_mut)]
+fn sanity_small() {
+    let (a, b, c, d) = (1u64, [0u8; 32], String::new(), ());
+    take!(small[8] a, small[32] mut b, small[8] &c as r, small[0] d,);
+    b[0] = 1;
+    let _: (u64, [u8; 32], &String, ()) = (a, b, r, d);
+}
+
+#[test]
+#[allow(unused_mut, deprecated)]
+fn sanity_send_sync() {
+    use std::sync::{Arc, Mutex};
+
+    let (a, b, c, d) = (Arc::new(Mutex::new(1)), vec![2], String::from("c"), 4u8);
+    take!(send a, sync =a as shared, send mut b, sync &c as r, send small[1] d,);
+    b.push(*shared.lock().unwrap());
+    let _: (Arc<Mutex<i32>>, &String, u8) = (a, r, d);
+    // single entries and lists of words go through the flat arms
+    take!(send shared);
+    take!(send b, sync &c);
+    assert_eq!(b, vec![2, 1]);
+
+    // `static` proves the clone no longer borrows anything before it is sent
+    let (name, label) = (String::from("worker"), String::from("label"));
+    let th = std::thread::spawn({
+        take!(static =name as owned_name, static send mut b, static label);
+        move || {
+            b.push(owned_name.len() as i32);
+            (b, label)
+        }
+    });
+    assert_eq!(th.join().unwrap(), (vec![2, 1, 6], String::from("label")));
+    assert_eq!(name, "worker");
+
+    // variables can still be called `send` and `sync`
+    let (send, sync, mut e) = (1, 2, 3u8);
+    take!(send, sync as synced, e <- 0);
+    assert_eq!((send, synced, e), (1, 2, 3));
+}
+
+#[test]
+fn sanity_swap() {
+    struct Node {
+        left: String,
+        right: String,
+    }
+
+    let (mut a, mut b) = (String::from("a"), String::from("b"));
+    swap!(a, b);
+    assert_eq!((a.as_str(), b.as_str()), ("b", "a"));
+
+    let mut node = Node {
+        left: String::from("l"),
+        right: String::from("r"),
+    };
+    swap!(node.left, node.right,);
+    let mut v = [String::from("0"), String::from("1")];
+    swap!(v[0], node.left);
+    assert_eq!((v[0].as_str(), node.left.as_str(), node.right.as_str()), ("r", "0", "l"));
+}
+
+#[test]
+fn sanity_rotate() {
+    let (mut a, mut b) = (String::from("a"), String::from("b"));
+    rotate!(a, b);
+    assert_eq!((a.as_str(), b.as_str()), ("b", "a"));
+
+    let strs = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let mut v = strs(&["0", "1"]);
+    let (mut c, mut d) = (String::from("c"), String::from("d"));
+    rotate!(c, v[0], d, v[1],);
+    assert_eq!((c, d, v), (String::from("0"), String::from("1"), strs(&["d", "c"])));
+}
+
+#[test]
+fn sanity_borrow() {
+    let (a, mut b, c, mut d) = (1, vec![2], String::from("c"), 4);
+
+    // single
+    {
+        borrow!(a);
+        let a: &i32 = a;
+        assert_eq!(*a, 1);
+    }
+    {
+        borrow!(mut b);
+        let b: &mut Vec<i32> = b;
+        b.push(3);
+    }
+
+    // multi, renames and trailing comma
+    {
+        borrow!(a, mut b, c as name, mut d as count,);
+        let (_, b, name, count): (&i32, &mut Vec<i32>, &String, &mut i32) = (a, b, name, count);
+        b.push(*a);
+        *count += name.len() as i32;
+    }
+    assert_eq!((a, b, c.as_str(), d), (1, vec![2, 3, 1], "c", 5));
+}
+
+#[test]
+fn sanity_reborrow() {
+    use std::sync::{Mutex, MutexGuard};
+
+    fn extend(out: &mut Vec<u32>, extra: &mut Vec<u32>) {
+        {
+            reborrow!(out, &extra as view,);
+            out.extend(view.iter());
+        }
+        {
+            reborrow!(extra as inner, out as first);
+            let mut push = move |v| inner.push(v);
+            push(first[0]);
+        }
+        // both are still usable after the nested scopes
+        extra.push(9);
+        out.push(extra.len() as u32);
+    }
+    let (mut out, mut extra) = (vec![1], vec![2, 3]);
+    extend(&mut out, &mut extra);
+    assert_eq!((out, extra), (vec![1, 2, 3, 4], vec![2, 3, 1, 9]));
+
+    fn bump(guard: &mut MutexGuard<u32>) {
+        reborrow!(guard);
+        let guard: &mut MutexGuard<u32> = guard;
+        **guard += 1;
+    }
+    let lock = Mutex::new(0);
+    let mut guard = lock.lock().unwrap();
+    {
+        let g = &mut guard;
+        bump(g);
+        reborrow!(g as again);
+        bump(again);
+        bump(g);
+    }
+    assert_eq!(*guard, 3);
+    assert!(lock.try_lock().is_err());
+    drop(guard);
+    assert_eq!(*lock.lock().unwrap(), 3);
+}
+
+#[test]
+fn sanity_take_once() {
+    use std::cell::OnceCell;
+    use std::sync::OnceLock;
+
+    static NAMES: OnceLock<Vec<&'static str>> = OnceLock::new();
+    take_once!(NAMES => names);
+    assert!(names.is_empty());
+    take_once!(NAMES with vec!["ignored"] => again);
+    assert!(std::ptr::eq(names, again));
+
+    let cell: OnceCell<String> = OnceCell::new();
+    let counted = OnceCell::new();
+    take_once!(cell with String::from("a") => a, counted => count,);
+    let count: &u32 = count;
+    assert_eq!((a.as_str(), *count), ("a", 0));
+
+    let mut cell: OnceCell<u32> = OnceCell::new();
+    take_once!(mut cell => zero);
+    *zero += 1;
+    take_once!(mut cell with 10 => one);
+    *one += 1;
+    assert_eq!(cell.get(), Some(&2));
+}
+
+#[cfg(feature = "once-cell")]
+#[test]
+fn sanity_take_once_cell() {
+    use crate::once_cell::sync::OnceCell;
+
+    static PORT: OnceCell<u16> = OnceCell::new();
+    take_once!(PORT with 8080 => port);
+    take_once!(PORT => again);
+    assert_eq!((*port, *again), (8080, 8080));
+
+    let mut cell: OnceCell<Vec<u8>> = OnceCell::new();
+    take_once!(mut cell with vec![1] => v);
+    v.push(2);
+    assert_eq!(cell.get(), Some(&vec![1, 2]));
+}
+
+#[test]
+fn sanity_mut_scope() {
+    let v = vec![3, 1, 2];
+    mut_scope!(v {
+        v.push(0);
+        v.sort();
+    });
+    let frozen: &Vec<i32> = &v;
+    assert_eq!(*frozen, vec![0, 1, 2, 3]);
+
+    // the block's value is ignored
+    let s = String::from("a");
+    mut_scope!(s as longer {
+        s.push('b');
+        s.len()
+    });
+    let longer: String = longer;
+    assert_eq!(longer, "ab");
+
+    // `Copy` values are still usable under the old name
+    let n = 1u8;
+    mut_scope!(n as m {
+        n += 1;
+    });
+    let (n, m): (u8, u8) = (n, m);
+    assert_eq!((n, m), (1, 2));
+}
+
+#[test]
+#[allow(unused_mut)]
+fn sanity_with_taken() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let (mut x, y, z) = (vec![1], String::from("y"), 3);
+    let out = with_taken!(x, =y, &z, => {
+        x.push(2);
+        assert_eq!(*x, vec![1, 2]);
+        y.len() + *z
+    });
+    assert_eq!(out, 4);
+    assert_eq!(x, vec![1, 2]);
+
+    with_taken!(x as lent => {
+        lent.clear();
+    });
+    assert!(x.is_empty());
+
+    let r = &mut x;
+    with_taken!(*r => {
+        r.push(5);
+    });
+    assert_eq!(*r, vec![5]);
+
+    // the value is restored even if the block panics
+    let mut v = vec![1, 2, 3];
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        with_taken!(v => {
+            v.push(4);
+            panic!("oops");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(v, vec![1, 2, 3, 4]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[allow(unused_mut, unused_variables, unused_assignments)]
+fn sanity_pointer_clone() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    let x = Arc::new(1);
+    {
+        take_arc_clone!(x, x as y);
+        assert_eq!(Arc::strong_count(&x), 3);
+    }
+    {
+        take_arc_clone!(mut x, mut x as y,);
+        y = Arc::new(2);
+        assert_eq!(Arc::strong_count(&x), 2);
+    }
+    assert_eq!(Arc::strong_count(&x), 1);
+    {
+        take_arc_clone!(x as [a, b], mut x as [c]);
+        c = Arc::clone(&a);
+        assert!(Arc::ptr_eq(&b, &c));
+        assert_eq!(Arc::strong_count(&x), 4);
+    }
+
+    let x = Rc::new(1);
+    {
+        take_rc_clone!(x, x as y);
+        assert!(Rc::ptr_eq(&x, &y));
+        assert_eq!(Rc::strong_count(&x), 3);
+    }
+    {
+        take_rc_clone!(mut x, mut x as y,);
+        y = Rc::new(2);
+        assert_eq!(Rc::strong_count(&x), 2);
+    }
+    take_rc_clone!(x as [a, b, c,]);
+    assert_eq!(Rc::strong_count(&x), 4);
+    drop((a, b, c));
+    assert_eq!(Rc::strong_count(&x), 1);
+}
+
+#[cfg(test)]
+mod attributes {
+    #![deny(warnings)]
+
+    #[test]
+    fn sanity_attributes() {
+        let (x, y, z) = (1, 2, vec![3]);
+        take!(
+            #[allow(unused_variables)] x,
+            #[allow(unused_mut)] mut y,
+            #[cfg(test)] =z as cloned,
+            #[cfg(not(test))] does_not_exist,
+        );
+        assert_eq!(y, 2);
+        assert_eq!(cloned, z);
+
+        take!(#[cfg(any())] &mut missing as also_missing);
+        take!(#[allow(unused_variables)] #[allow(unused_mut)] =mut z as w);
+        take!(#[cfg(any())] drop z, #[cfg(any())] missing <- 1);
+        assert_eq!(z, vec![3]);
+    }
+
+    #[test]
+    fn sanity_attributes_fallible() {
+        fn f(x: Option<u32>, y: Option<u32>) -> Option<u32> {
+            take!(
+                #[allow(unused_variables)] x?,
+                #[cfg(test)] y else return Some(0),
+            );
+            Some(y)
+        }
+        assert_eq!(f(Some(1), Some(2)), Some(2));
+        assert_eq!(f(None, Some(2)), None);
+    }
+}
+
+#[test]
+#[allow(unused_mut, unused_variables)]
+fn sanity_own() {
+    let (a, b, c, d) = (1, String::from("b"), vec![3], Box::new(4));
+    own!(a, &b, =mut c, *d as e);
+    c.push(4);
+    assert_eq!((a, b.as_str(), c, e), (1, "b", vec![3, 4], 4));
+
+    let mut f = 5;
+    {
+        own!(&mut f, ref b as r, hold a);
+        *f += 1;
+        assert_eq!(r.as_str(), "b");
+    }
+    let h = String::from("h");
+    own!(mut f as g, drop h);
+    g += 1;
+    assert_eq!(g, 7);
+
+    fn fallible(x: Option<u32>, y: Result<u32, ()>) -> Option<u32> {
+        let mut z = 0;
+        own!(x?, !y, z <- 3 as old);
+        Some(x + y + old + z - 3)
+    }
+    assert_eq!(fallible(Some(1), Ok(2)), Some(3));
+    assert_eq!(fallible(None, Ok(2)), None);
+
+    let tuple = (1, String::from("t"));
+    own!(..tuple as (mut t0, =t1),);
+    t0 += 1;
+    assert_eq!((t0, t1.as_str()), (2, "t"));
+    own!();
+}
+
+#[test]
+#[allow(unused_mut, unused_variables)]
+fn sanity_group() {
+    let (a, b, c) = (vec![1], String::from("b"), 3);
+    take!(=(a, b, c));
+    take!(&(a, b), mut (c,));
+    c += 1;
+    let (a, b): (&Vec<i32>, &String) = (a, b);
+    assert_eq!((a, b.as_str(), c), (&vec![1], "b", 4));
+
+    let (mut x, mut y, z, w) = (1, 2, Box::new(3), Box::new(4));
+    {
+        take!(&mut (x, y), *(z, w), =mut (a,));
+        *x += 1;
+        *y += z + w;
+    }
+    assert_eq!((x, y), (2, 9));
+
+    fn fallible(a: Option<u32>, b: Option<u32>, c: Result<u32, ()>) -> Option<u32> {
+        take!(?(a, b), !(c,));
+        Some(a + b + c)
+    }
+    assert_eq!(fallible(Some(1), Some(2), Ok(3)), Some(6));
+    assert_eq!(fallible(Some(1), None, Ok(3)), None);
+
+    let (s, t, u, v) = (
+        String::from("s"),
+        String::from("t"),
+        String::from("u"),
+        String::from("v"),
+    );
+    take!(&*(s, t), hold (x,), drop (u, v));
+    let (s, t): (&str, &str) = (s, t);
+    assert_eq!((s, t), ("s", "t"));
+}
+
+#[test]
+fn sanity_fan_out() {
+    use std::sync::mpsc::channel;
+    use std::thread::spawn;
+
+    let (tx, rx) = channel();
+    take!(=tx as [tx_a, tx_b], =mut tx as [tx_c,]);
+    tx_c = tx_c.clone();
+    let threads = vec![
+        spawn(move || tx_a.send("a").unwrap()),
+        spawn(move || tx_b.send("b").unwrap()),
+        spawn(move || tx_c.send("c").unwrap()),
+    ];
+    for th in threads {
+        th.join().unwrap();
+    }
+    let mut received: Vec<_> = rx.try_iter().collect();
+    received.sort();
+    assert_eq!(received, vec!["a", "b", "c"]);
+
+    // the original is still alive
+    tx.send("tx").unwrap();
+    assert_eq!(rx.recv().unwrap(), "tx");
+
+    let v = vec![1];
+    take!(#[allow(unused_mut)] =mut v as [w, x], &v as r);
+    assert_eq!((w, x, r), (vec![1], vec![1], &vec![1]));
+}
+
+#[test]
+fn sanity_try_take() {
+    let (a, b, c, d): (Option<u32>, Result<u32, ()>, Option<String>, Option<u32>) =
+        (None, Ok(2), Some(String::from("c")), None);
+    let mut calls = 0;
+    try_take!(
+        a or 1,
+        mut b as two or_else |_| { calls += 1; 0 },
+        #[allow(unused_variables)] c or panic "c is set",
+        mut d or { calls += 10; 4 },
+    );
+    two += 1;
+    d += 1;
+    // `or_else` is lazy, `or` isn't
+    assert_eq!((a, two, calls, d), (1, 3, 10, 5));
+
+    fn propagate(x: Option<u32>, y: Option<u32>) -> Option<u32> {
+        try_take!(?x, ?mut y as z,);
+        z += x;
+        Some(z)
+    }
+    assert_eq!(propagate(Some(1), Some(2)), Some(3));
+    assert_eq!(propagate(Some(1), None), None);
+    try_take!();
+}
+
+#[test]
+#[should_panic(expected = "missing")]
+fn sanity_try_take_panic() {
+    let x: Result<u32, ()> = Err(());
+    try_take!(x or panic "missing");
+    let _ = x;
+}
+
+#[test]
+fn sanity_not() {
+    use std::sync::mpsc::channel;
+    use std::thread;
+
+    let (mut database, (tx, rx)) = (vec![1], channel());
+    thread::scope(|s| {
+        let worker = s.spawn({
+            take!(=tx, not database);
+            move || tx.send(2).unwrap()
+        });
+        // the closure doesn't borrow `database`, so it can be mutated in the meantime
+        database.push(rx.recv().unwrap());
+        worker.join().unwrap();
+    });
+    assert_eq!(database, vec![1, 2]);
+    take!(not tx, not database);
+    let _: (NotCaptured, NotCaptured) = (tx, database);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn sanity_take_sender() {
+    use std::sync::mpsc::channel;
+    use std::thread;
+
+    let (tx, rx) = channel();
+    let (a, b) = {
+        take_sender!(=tx as first, =tx,);
+        (
+            thread::spawn(move || first.send(1).unwrap()),
+            thread::spawn(move || tx.send(2).unwrap()),
+        )
+    };
+    a.join().unwrap();
+    b.join().unwrap();
+    send_value!(tx, 3);
+    take_sender!(tx);
+    tx.send(4).unwrap();
+    let mut received: Vec<u32> = rx.iter().collect();
+    received.sort();
+    assert_eq!(received, vec![1, 2, 3, 4]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "was dropped without sending"))]
+fn sanity_take_sender_unused() {
+    let (tx, _rx) = std::sync::mpsc::channel::<u32>();
+    take_sender!(tx);
+    drop(tx);
+}
+
+#[test]
+#[should_panic(expected = "the receiver of `tx` was dropped")]
+fn sanity_send_value_disconnected() {
+    let tx = std::sync::mpsc::channel().0;
+    send_value!(tx, 1);
+}
+
+#[test]
+fn sanity_self() {
+    use std::thread;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Job {
+        ids: Vec<u32>,
+    }
+
+    impl Job {
+        fn spawn_clone(&self) -> thread::JoinHandle<usize> {
+            take!(=self as this);
+            thread::spawn(move || this.ids.len())
+        }
+
+        fn borrowed(&self) -> Job {
+            take!(&self as this, =mut self as copy,);
+            copy.ids.push(this.ids[0]);
+            let finish = move || copy;
+            finish()
+        }
+
+        fn by_value(self) -> (Job, Job) {
+            take!(=self as copy, mut self as this);
+            this.ids.push(3);
+            let finish = move || (this, copy);
+            finish()
+        }
+    }
+
+    let job = Job { ids: vec![1, 2] };
+    assert_eq!(job.spawn_clone().join().unwrap(), 2);
+    assert_eq!(job.borrowed().ids, vec![1, 2, 1]);
+    let (changed, copy) = job.clone().by_value();
+    assert_eq!((changed.ids, copy), (vec![1, 2, 3], job));
+}
+
+#[test]
+fn sanity_lazy_take() {
+    use std::cell::Cell;
+
+    let (inits, source) = (Cell::new(0), vec![1]);
+    {
+        lazy_take!(
+            a = || { inits.set(inits.get() + 1); 1 },
+            mut b = || vec![2],
+            clone source,
+            mut clone source as copy,
+        );
+        assert_eq!(inits.get(), 0);
+        b.push(*a);
+        copy.push(3);
+        assert_eq!((*a, &*b, &*source, &*copy), (1, &vec![2, 1], &vec![1], &vec![1, 3]));
+        assert_eq!(*a + *a, 2);
+        assert_eq!(inits.get(), 1);
+    }
+    // a `move` closure hands the original to the cell
+    lazy_take!(owned = move || source);
+    assert_eq!(*owned, vec![1]);
+    lazy_take!();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn sanity_lazy_take_sync() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::thread;
+
+    let (inits, source) = (AtomicU32::new(0), String::from("s"));
+    lazy_take_sync!(
+        value = || inits.fetch_add(1, Ordering::SeqCst) + 10,
+        clone source as copy,
+    );
+    thread::scope(|s| {
+        let workers: Vec<_> = (0..3).map(|_| s.spawn(|| *value + copy.len() as u32)).collect();
+        for worker in workers {
+            assert_eq!(worker.join().unwrap(), 11);
+        }
+    });
+    assert_eq!(inits.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn sanity_unwind() {
+    use std::cell::RefCell;
+    use std::panic::{catch_unwind, UnwindSafe};
+
+    fn run<R>(f: impl FnOnce() -> R + UnwindSafe) -> Result<R, ()> {
+        catch_unwind(f).map_err(|_| ())
+    }
+
+    let (mut counters, shared, name) = (vec![0, 0], RefCell::new(1), String::from("n"));
+    let result = run({
+        take!(unwind &mut counters, unwind =shared as cell, unwind name,);
+        move || {
+            counters[0] += 1;
+            *cell.borrow_mut() += 1;
+            if name.len() == 1 {
+                panic!("halfway");
+            }
+            counters[1] += 1;
+        }
+    });
+    assert_eq!(result, Err(()));
+    assert_eq!((counters, shared.into_inner()), (vec![1, 0], 1));
+
+    // single entries and by-value access
+    let state = vec![1];
+    take!(unwind state);
+    state.push(2);
+    let inner: Vec<i32> = state.0;
+    assert_eq!(run(move || inner.len()), Ok(2));
+
+    // variables can still be called `unwind`
+    let unwind = 1;
+    take!(unwind, &unwind as r);
+    assert_eq!(*r, 1);
+}
+
+#[test]
+fn sanity_take_and_assert() {
+    let (mut w, x, s, b) = (1, vec![2], String::from("s"), Box::new(3u8));
+    take_and_assert!(
+        &mut w: &mut i32,
+        =x as y: Vec<i32>,
+        #[allow(unused_variables)] &s as r: &String,
+        *b: u8,
+    );
+    *w += y[0];
+    capture_assert_type!(w: &mut i32, x: Vec<i32>, s: String,);
+    assert_eq!((*w, b), (3, 3));
+    take_and_assert!();
+    capture_assert_type!();
+}
+
+#[test]
+fn sanity_raw_pointers() {
+    use std::os::raw::c_void;
+
+    extern "C" fn shim(read: *const c_void, write: *mut c_void) -> u32 {
+        let (read, write) = unsafe { (&*(read as *const Vec<u32>), &mut *(write as *mut u32)) };
+        *write += read.iter().sum::<u32>();
+        read.len() as u32
+    }
+    type Callback = extern "C" fn(*const c_void, *mut c_void) -> u32;
+    fn register(callback: Callback, read: *const c_void, write: *mut c_void) -> u32 {
+        callback(read, write)
+    }
+
+    let (ctx, mut state) = (vec![1, 2, 3], 10u32);
+    take!(&raw const ctx as ctx_ptr, &raw mut state as state_ptr,);
+    let len = register(shim, ctx_ptr as *const c_void, state_ptr as *mut c_void);
+    // the originals were neither moved nor borrowed
+    assert_eq!((len, state, ctx.len()), (3, 16, 3));
+    take!(&raw const state as again);
+    assert_eq!(unsafe { *again }, 16);
+
+    // variables can still be called `raw`
+    let raw = 1;
+    take!(&raw as r, &raw);
+    assert_eq!((*r, *raw), (1, 1));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sanity_weak() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    let (a, b) = (Arc::new(1), Arc::new(2));
+    take_arc_weak!(a as weak_a, b as weak_b,);
+    assert_eq!((Arc::strong_count(&a), Arc::weak_count(&b)), (1, 1));
+    assert_eq!((weak_a.upgrade(), weak_b.upgrade()), (Some(Arc::new(1)), Some(Arc::new(2))));
+    {
+        let owner = Arc::clone(&a);
+        take_weak!(a, b);
+        assert_eq!((a.upgrade(), b.upgrade()), (Some(owner), None));
+    }
+
+    let (c, d) = (Rc::new(String::from("c")), Rc::new(4));
+    take_rc_weak!(c as weak_c);
+    drop(c);
+    assert!(weak_c.upgrade().is_none());
+    let keep = Rc::clone(&d);
+    take_weak_rc!(d,);
+    assert_eq!((d.upgrade(), Rc::weak_count(&keep)), (Some(Rc::new(4)), 1));
+    take_weak!();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sanity_shrink() {
+    let mut buf = Vec::with_capacity(1024);
+    buf.extend([1u8, 2, 3]);
+    let (name, log) = (String::with_capacity(64) + "name", vec![String::from("a")]);
+    take!(shrink buf, shrink name as title, shrink mut log,);
+    let (buf, title): (Box<[u8]>, Box<str>) = (buf, title);
+    log[0].push('b');
+    let closure = move || (buf.len(), title.len(), log);
+    let (len, title_len, log) = closure();
+    assert_eq!((len, title_len, &*log[0]), (3, 4, "ab"));
+
+    let (a, b) = (vec![()], String::new());
+    take!(shrink mut a as boxed_a, shrink b);
+    boxed_a[0] = ();
+    assert_eq!((boxed_a.len(), &*b), (1, ""));
+}
+
+#[test]
+fn sanity_deref_clone() {
+    use std::sync::Arc;
+
+    let (a, b, c) = (Arc::new(String::from("a")), Box::new(vec![1, 2]), &String::from("c"));
+    take!(=*a as owned_a, =mut *b, =*c);
+    b.push(3);
+    let _: (String, Vec<i32>, String) = (owned_a.clone(), b.clone(), c.clone());
+    assert_eq!((&*owned_a, &*b, &*c), ("a", &[1, 2, 3][..], "c"));
+    assert_eq!(Arc::strong_count(&a), 1);
+
+    let (d, e, f) = (Arc::new(String::from("d")), Box::new(vec![4]), &String::from("f"));
+    take_deref_clone!(d as owned_d, mut e, f,);
+    e.push(5);
+    let _: (String, Vec<i32>, String) = (owned_d.clone(), e.clone(), f.clone());
+    assert_eq!((&*owned_d, &*e, &*f, d.as_str()), ("d", &[4, 5][..], "f", "d"));
+    take_deref_clone!(mut d as g);
+    g.push('!');
+    assert_eq!(g, "d!");
+}
+
+#[test]
+fn sanity_field_groups() {
+    #[derive(Clone)]
+    struct Config {
+        host: String,
+        port: u16,
+        token: String,
+        tags: Vec<u8>,
+    }
+
+    let cfg = Config { host: "a".into(), port: 1, token: "t".into(), tags: vec![1] };
+    let snapshot = cfg.clone();
+
+    // all clones and borrows: `cfg` stays usable
+    let mut cfg = cfg;
+    take!(cfg.{=host, =mut token as tok, &port, ..}, =snapshot as copy);
+    tok.push('!');
+    assert_eq!((host.as_str(), tok.as_str(), *port), ("a", "t!", 1));
+    take!(cfg.{&mut tags, ref host as h, ..});
+    tags.push(2);
+    assert_eq!((h.as_str(), cfg.tags.len(), copy.tags.len()), ("a", 2, 1));
+
+    // all moves: `cfg` is consumed
+    take!(#[allow(unused_mut)] cfg.{host, mut port as p, token, tags,});
+    let _: NotCaptured = cfg;
+    p += 1;
+    assert_eq!((host, p, token, tags), (String::from("a"), 2, String::from("t"), vec![1, 2]));
+
+    // mixed without `..`: the clone of a field and moves of the others
+    let mut c = snapshot;
+    take!(c.{=host as cloned, token, ref mut port});
+    *port += 1;
+    assert_eq!((cloned, token, *port), ("a".into(), "t".into(), 2));
+}
+
+#[test]
+fn sanity_conditional_take() {
+    let (a, b, c, d) = (String::from("a"), String::from("b"), vec![1], 4);
+    conditional_take!(a if true else String::new(), =b as cloned if false else b.clone() + "!");
+    assert_eq!((a.as_str(), cloned.as_str(), b.as_str()), ("a", "b!", "b"));
+
+    conditional_take!(=mut c if !b.is_empty() else c, mut d if b.len() > 1 else 0,);
+    d += 1;
+    c.push(d);
+    assert_eq!(c, vec![1, 1]);
+
+    let e = Some(String::from("e"));
+    conditional_take!(e as f if e.is_some() && c.len() == 2 else None);
+    assert_eq!(f.as_deref(), Some("e"));
+}
+
+#[cfg(feature = "macros")]
+#[test]
+fn sanity_capture_set() {
+    use std::sync::mpsc::{channel, Sender};
+
+    let (model, assets) = (vec![1, 2], [3u8; 4]);
+    let (sender, receiver) = channel::<usize>();
+    capture_set! {
+        ui_deps = (=model, =sender, &assets);
+        counter = (=mut model as counted);
     }
 
-    {
-        take!(=x);
-        assert_eq!(x, 1);
+    let make = |label: &'static str| {
+        take!(use ui_deps; label);
+        move || {
+            sender.send(model.len()).unwrap();
+            (label, model.len() + assets.len())
+        }
+    };
+    let (first, second) = (make("a"), make("a"));
+    assert_eq!(first(), second());
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![2, 2]);
+
+    let inline = {
+        take!(=model, =sender, &assets);
+        let _: (Vec<i32>, Sender<usize>, &[u8; 4]) = (model.clone(), sender, assets);
+        model
+    };
+    let spliced = {
+        take!(use ui_deps, counter;);
+        counted.push(3);
+        let _: (Vec<i32>, Sender<usize>, &[u8; 4]) = (model.clone(), sender, assets);
+        (model, counted)
+    };
+    assert_eq!((inline, spliced.1), (spliced.0, vec![1, 2, 3]));
+    take!(use counter);
+    counted.clear();
+    assert_eq!(model.len(), 2);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn sanity_shared() {
+    use std::sync::{Arc, Mutex, RwLock};
+    use std::thread::spawn;
+
+    let (a, b) = (1, String::from("b"));
+    take_into_mutex!(a, b as locked_b,);
+    let _: (&Mutex<i32>, &Mutex<String>) = (&a, &locked_b);
+    *a.lock().unwrap() += 1;
+    assert_eq!((*a.lock().unwrap(), locked_b.lock().unwrap().as_str()), (2, "b"));
+
+    let (counter, names) = (0, vec![]);
+    take_shared!(counter, names as shared_names);
+    let _: (&Arc<Mutex<i32>>, &Arc<Mutex<Vec<&str>>>) = (&counter, &shared_names);
+    let threads: Vec<_> = ["x", "y"]
+        .iter()
+        .map(|&name| {
+            take_arc_clone!(counter, shared_names);
+            spawn(move || {
+                *counter.lock().unwrap() += 1;
+                shared_names.lock().unwrap().push(name);
+            })
+        })
+        .collect();
+    threads.into_iter().for_each(|th| th.join().unwrap());
+    shared_names.lock().unwrap().sort();
+    assert_eq!(*counter.lock().unwrap(), 2);
+    assert_eq!(*shared_names.lock().unwrap(), vec!["x", "y"]);
+
+    let (c, d) = (vec![1], 4);
+    take_shared_rw!(c, d as rw_d);
+    let _: (&Arc<RwLock<Vec<i32>>>, &Arc<RwLock<i32>>) = (&c, &rw_d);
+    let th = spawn({
+        take_arc_clone!(c, rw_d);
+        move || c.write().unwrap().push(*rw_d.read().unwrap())
+    });
+    th.join().unwrap();
+    assert_eq!(*c.read().unwrap(), vec![1, 4]);
+}
+
+#[test]
+fn sanity_take_tuple() {
+    use std::cell::Cell;
+
+    let calls = Cell::new(0);
+    let make = || {
+        calls.set(calls.get() + 1);
+        (String::from("a"), vec![1], (2u8, String::from("d")), 5, 6)
+    };
+    take_tuple!((a, =mut b, (&mut c, =d), ref e, _) = make());
+    b.push(2);
+    *c += 1;
+    let _: (String, Vec<i32>, &mut u8, String, &i32) = (a.clone(), b.clone(), &mut *c, d.clone(), e);
+    assert_eq!((a.as_str(), &*b, *c, d.as_str(), *e), ("a", &[1, 2][..], 3, "d", 5));
+    assert_eq!(calls.get(), 1);
+
+    // a second tuple doesn't clash with the first temporary
+    let pair = (String::from("x"), String::from("y"));
+    take_tuple!((&x, mut y,) = pair);
+    y.push_str(x);
+    take_tuple!((single,) = (1,));
+    assert_eq!((y.as_str(), single, e), ("yx", 1, &5));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sanity_unwrap_or_clone() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    thread_local!(static CLONES: Cell<u32> = const { Cell::new(0) });
+    #[derive(Debug, PartialEq)]
+    struct Counted(u32);
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.with(|c| c.set(c.get() + 1));
+            Counted(self.0)
+        }
     }
 
+    // unique: moved out without a clone
+    let (a, b) = (Arc::new(Counted(1)), Rc::new(Counted(2)));
+    take!(unwrap a, unwrap mut b as owned_b,);
+    owned_b.0 += 1;
+    assert_eq!((a, owned_b, CLONES.with(Cell::get)), (Counted(1), Counted(3), 0));
+
+    // shared: cloned, the other owners keep the original
+    let (c, d) = (Arc::new(Counted(4)), Rc::new(Counted(5)));
+    let (other_c, other_d) = (Arc::clone(&c), Rc::clone(&d));
+    take!(unwrap mut c, unwrap d as owned_d);
+    c.0 += 1;
+    assert_eq!((c, owned_d, CLONES.with(Cell::get)), (Counted(5), Counted(5), 2));
+    assert_eq!((&*other_c, &*other_d), (&Counted(4), &Counted(5)));
+}
+
+#[test]
+fn sanity_box() {
+    #[cfg(feature = "alloc")]
     {
-        take!(=mut x);
-        x = 2;
-        assert_eq!(x, 2);
+        let (a, b, c) = (String::from("a"), vec![1], 3);
+        take_box!(a, mut b as boxed_b, c,);
+        boxed_b.push(2);
+        let (a, c): (Box<String>, Box<i32>) = (a, c);
+        assert_eq!((a.as_str(), &**boxed_b, *c), ("a", &[1, 2][..], 3));
     }
-    assert_eq!(x, 1);
+
+    let (d, e) = (Box::new(String::from("d")), Box::new(vec![4]));
+    unbox_clone!(d as cloned_d, mut e);
+    e.push(5);
+    let _: (String, Vec<i32>) = (cloned_d.clone(), e.clone());
+    assert_eq!((d.as_str(), cloned_d.as_str(), e), ("d", "d", vec![4, 5]));
+
+    let (f, g) = (Box::new(String::from("f")), Box::new(vec![6]));
+    unbox!(f, mut g as items,);
+    items.push(7);
+    let _: (String, Vec<i32>) = (f.clone(), items.clone());
+    assert_eq!((f.as_str(), items), ("f", vec![6, 7]));
 }
 
 #[test]
-#[allow(unused_mut, unused_variables, unused_assignments)]
-fn sanity_syntax_comma() {
-    // all of these just have trailing commas, which actually tests the `rest` block as well.
-    let x = 1;
-    take!(x,);
-    assert_eq!(x, 1);
+#[deny(unused_variables)]
+fn sanity_renaming_take() {
+    #[derive(Clone)]
+    struct Handle {
+        name: String,
+    }
+    impl Handle {
+        fn owned(&self) -> usize {
+            renaming_take!(=self as this, self.name.len() as len);
+            this.name.len() + len
+        }
+    }
 
-    take!(mut x,);
-    x = 2;
-    assert_eq!(x, 2);
+    let (a, b, c, d) = (String::from("a"), vec![1], 3, Some(4));
+    renaming_take!(=a as a2, mut b as items, &c as c_ref, d else return as e, =a as [f, g]);
+    items.push(2);
+    assert_eq!((a.as_str(), a2.as_str(), items, *c_ref, e), ("a", "a", vec![1, 2], 3, 4));
+    assert_eq!((f, g), (a2.clone(), a2));
 
-    {
-        take!(&mut x,);
-        *x = 1;
-        assert_eq!(*x, 1);
+    let handle = Handle { name: String::from("h") };
+    assert_eq!(handle.owned(), 2);
+}
+
+#[test]
+fn sanity_take_disjoint() {
+    #[derive(Clone)]
+    struct Handle(usize);
+    impl Handle {
+        fn twice(&self) -> usize {
+            take_disjoint!(=self as a, =self as b);
+            a.0 + b.0
+        }
     }
 
+    let (a, b, c, d, e, f, g) = (String::from("a"), vec![1], 3, Some(4), (5, 6), [7, 8], 9);
+    take_disjoint!(=a as [a1, a2], mut b, &(c, g), d else return, [f0, _] = f,);
+    b.push(c.to_owned());
+    assert_eq!((a1, a2, b, d, *g, *f0), (a.clone(), a.clone(), vec![1, 3], 4, 9, 7));
+    take_disjoint!(#[allow(unused_mut)] mut a, small[16] e);
+    take_disjoint!(together: &f, =e as e2);
+    take_disjoint!();
+    let sum = take_disjoint!({ e, &f } => e.0 + f.len());
+    assert_eq!((a.len(), sum, e2, Handle(1).twice()), (1, 7, (5, 6), 2));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sanity_weak_closure() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    let (state, queue) = (Rc::new(RefCell::new(vec![0])), vec![10, 20]);
+    let push = take!((weak state as this else return, =queue) move |ev: usize| {
+        this.borrow_mut().push(ev + queue.len());
+    });
+    let len = take!((weak state else 0) move || state.borrow().len());
+    let sum = take!((weak state else return usize::MAX, &queue) move |extra| -> usize {
+        state.borrow().iter().sum::<usize>() + extra + queue.len()
+    });
+    push(1);
+    assert_eq!((len(), sum(0), Rc::weak_count(&state)), (2, 5, 3));
+
+    drop(state);
+    push(2); // returns early instead of panicking
+    assert_eq!((len(), sum(0), queue.len()), (0, usize::MAX, 2));
+
+    let shared = Arc::new(String::from("shared"));
+    take!(weak shared as weak_shared, weak shared);
+    assert_eq!(weak_shared.upgrade().as_deref().map(String::as_str), Some("shared"));
+    let _: alloc::sync::Weak<String> = shared;
+}
+
+#[test]
+fn sanity_together() {
+    use recorder::{log, Recorder};
+
+    let dropped = log();
+    let record = |name| Recorder(name, dropped.clone());
     {
-        take!(&x,);
-        assert_eq!(*x, 1);
+        let (guard, logger, ctx) = (record("guard"), record("logger"), String::from("ctx"));
+        take!(guard, logger, =ctx);
+        let _ = (&guard, &logger, ctx);
     }
+    assert_eq!(*dropped.borrow(), vec!["logger", "guard"]);
 
+    dropped.borrow_mut().clear();
     {
-        take!(=x,);
-        assert_eq!(x, 1);
+        let (guard, logger, ctx) = (record("guard"), record("logger"), String::from("ctx"));
+        take!(together: guard, logger, =ctx); // let (ctx, logger, guard) = (...);
+        let _ = (&guard, &logger, ctx);
     }
+    assert_eq!(*dropped.borrow(), vec!["guard", "logger"]);
 
+    dropped.borrow_mut().clear();
     {
-        take!(=mut x,);
-        x = 2;
-        assert_eq!(x, 2);
+        let (a, b, mut c, d, e) = (record("a"), record("b"), vec![1], Some(2), Box::new((3, 4)));
+        let (f, g) = (record("f"), String::from("g"));
+        take!(together:
+            mut a as first,
+            &b,
+            &mut c,
+            d else return as two,
+            =g as [g1, g2],
+            hold f,
+            *e,
+        );
+        first.0 = "first";
+        c.push(2);
+        let _: (&Recorder, &mut Vec<i32>, i32) = (b, c, two);
+        assert_eq!((g1.as_str(), g2, e), ("g", String::from("g"), (3, 4)));
     }
-    assert_eq!(x, 1);
+    assert_eq!(*dropped.borrow(), vec!["first", "f", "b"]);
 }
 
 #[test]
-#[allow(unused_mut, unused_variables, unused_assignments)]
-fn sanity_syntax_as() {
-    let mut x = 1;
-    {
-        take!(x as y);
-        assert_eq!(x, y);
+fn sanity_expand_take_arm() {
+    let (a, mut b, c, d, e) = (String::from("a"), vec![1], 3, vec![4], String::from("e"));
+    __expand_take_arm!(&, a, a_ref);
+    __expand_take_arm!((&mut), b, b_mut);
+    __expand_take_arm!((), c, c_moved);
+    __expand_take_arm!(=, d, d_clone);
+    __expand_take_arm!((=mut), e, e_clone,);
+    b_mut.push(2);
+    e_clone.push('!');
+    let _: (&String, i32, Vec<i32>) = (a_ref, c_moved, d_clone);
+    assert_eq!((b, e.as_str(), e_clone.as_str()), (vec![1, 2], "e", "e!"));
+}
+
+#[test]
+fn sanity_take_field() {
+    #[derive(Clone, Default)]
+    struct Job {
+        id: u32,
+        name: String,
+        tags: Vec<&'static str>,
+        done: bool,
     }
 
+    let job = Job { id: 1, name: String::from("job"), tags: vec!["a"], done: false };
+    take_field!(job => id, =name, =mut tags as labels, mut done,);
+    labels.push("b");
+    done = !done;
+    assert_eq!((id, name, labels, done, job.name.as_str()), (1, "job".into(), vec!["a", "b"], true, "job"));
+
+    let mut job = Job::default();
+    take_field!(job => &id as id_ref, &mut tags, ref mut done, =name);
+    tags.push("c");
+    *done = true;
+    assert_eq!((*id_ref, name.as_str()), (0, ""));
+    assert_eq!((&job.tags, job.done), (&vec!["c"], true));
+
+    take_field!(&mut job => id, name as title);
+    *id = 2;
+    title.push('!');
+    take_field!(&job => id as id_ref, name);
+    assert_eq!((*id_ref, name.as_str()), (2, "!"));
+}
+
+#[test]
+fn sanity_cloned() {
+    use executor::block_on;
+
+    let (a, b, c) = (String::from("a"), vec![1], String::from("c"));
+    cloned!(a, mut b as list, c,);
+    list.push(2);
+    assert_eq!((a.as_str(), list, c.as_str()), ("a", vec![1, 2], "c"));
+
+    let (name, mut log) = (String::from("n"), Vec::new());
     {
-        take!(mut x as y);
-        y = 2;
-        assert_eq!(y, 2);
+        let mut push = cloned!(name, mut b => move |i: i32| {
+            b.push(i);
+            format!("{}{:?}", name, b)
+        });
+        log.push(push(2));
+        log.push(push(3));
     }
+    assert_eq!((log, b, name.as_str()), (vec!["n[1, 2]".to_string(), "n[1, 2, 3]".to_string()], vec![1], "n"));
 
-    {
-        take!(&mut x as y);
-        *y = 2;
-        assert_eq!(*y, 2);
+    let fut = cloned!(name as label => async move { label.len() });
+    assert_eq!(block_on(fut), 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sanity_arc_clone() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    let (a, b) = (Arc::new(vec![1]), Rc::new(String::from("b")));
+    take!(arc_clone a, arc_clone b as shared_b,);
+    assert_eq!((Arc::strong_count(&a), Rc::strong_count(&shared_b)), (2, 2));
+    assert!(Rc::ptr_eq(&b, &shared_b));
+
+    let dyn_arc: Arc<dyn Fn() -> i32> = Arc::new(|| 3);
+    take!(arc_clone dyn_arc as f, &a as first);
+    assert_eq!((f(), first.len()), (3, 1));
+}
+
+#[test]
+fn sanity_ref_counted() {
+    #[derive(Debug, PartialEq)]
+    struct Handle(u32);
+
+    impl RefCounted for Handle {
+        fn ref_clone(&self) -> Self {
+            Handle(self.0 + 1)
+        }
     }
-    assert_eq!(x, 2);
-    x = 1;
 
+    let (a, b) = (Handle(1), Handle(10));
+    take!(rc_clone a, rc_clone b as shared_b,);
+    assert_eq!((a, shared_b), (Handle(2), Handle(11)));
+    take_ref_counted!(b, mut b as [c, d], b as e,);
+    c.0 += 1;
+    d.0 += 10;
+    assert_eq!((b.0, c.0, d.0, e.0), (11, 13, 22, 12));
+    take!(together: rc_clone c as f, &e);
+    assert_eq!(f.0, 14);
+
+    #[cfg(feature = "alloc")]
     {
-        take!(&x as y);
-        assert_eq!(*y, 1);
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        let (arc, rc) = (Arc::new(vec![1]), Rc::new(String::from("rc")));
+        take!(rc_clone arc, rc_clone rc as shared);
+        take_ref_counted!(mut arc as other);
+        assert!(Arc::get_mut(&mut other).is_none());
+        assert_eq!((Arc::strong_count(&arc), Rc::strong_count(&shared)), (3, 2));
+        assert!(Rc::ptr_eq(&rc, &shared));
     }
+}
 
-    {
-        take!(=x as y);
-        assert_eq!(x, y);
+#[test]
+fn sanity_take_enum_variant() {
+    #[derive(Debug, PartialEq)]
+    enum Shape {
+        Circle(u32),
+        Square(u32),
     }
 
-    {
-        take!(=mut x as y);
-        y = 2;
-        assert_eq!(y, 2);
+    fn area(shape: Shape) -> Option<u32> {
+        take_enum_variant!(shape as Shape::Circle(r) else return None);
+        Some(3 * r * r)
     }
-    assert_eq!(x, 1);
+    assert_eq!((area(Shape::Circle(2)), area(Shape::Square(2))), (Some(12), None));
+
+    let (a, b, c) = (Some(1), Err::<u32, _>("bad"), Shape::Square(4));
+    take_enum_variant!(
+        mut a as Some(a),
+        b as Ok(b) else |other: Result<u32, &str>| other.unwrap_err().len() as u32,
+        c as Shape::Circle(side) else |other| match other {
+            Shape::Square(side) => side,
+            other => panic!("{:?}", other),
+        },
+    );
+    a += 1;
+    assert_eq!((a, b, side), (2, 3, 4));
+
+    let mut sum = 0;
+    for value in [Some(1), None, Some(3)] {
+        take_enum_variant!(value as Some(value) else continue);
+        sum += value;
+    }
+    assert_eq!(sum, 4);
 }
 
+#[test]
+#[should_panic(expected = "`e` is not `Result::Ok(..)`")]
+fn sanity_take_enum_variant_panic() {
+    let e: Result<u32, ()> = Err(());
+    take_enum_variant!(e as Result::Ok(v));
+    let _ = v;
+}
 
 #[test]
-#[allow(unused_mut, unused_variables, unused_assignments)]
-fn sanity_multi() {
-    {
-        let (x, y, z) = (1, 2, 3);
-        take!(x, y, z);
+fn sanity_take_iter() {
+    fn pair(s: &str) -> Option<(char, char)> {
+        take_iter!(s.chars() => a?, _?, mut b?,);
+        b = b.to_ascii_uppercase();
+        Some((a, b))
     }
-    {
-        let (x, y, z) = (1, 2, 3);
-        take!(mut x, y, z);
+    assert_eq!((pair("xyz"), pair("xy")), (Some(('x', 'Z')), None));
+
+    take_iter!(vec![1, 2, 3] => a, mut b, _);
+    b += a;
+    assert_eq!(b, 3);
+
+    fn renamed(s: &str) -> Option<(char, char, char)> {
+        take_iter!(s.chars() => first as x, mut second as y, third? as z);
+        y = y.to_ascii_uppercase();
+        Some((x, y, z))
     }
+    assert_eq!((renamed("abc"), renamed("ab")), (Some(('a', 'B', 'c')), None));
+
+    #[cfg(feature = "alloc")]
     {
-        let (x, y, z) = (1, 2, 3);
-        take!(mut x, mut y, z);
+        take_iter!(0..5 => _, first, mut ..rest,);
+        rest.push(first);
+        assert_eq!(rest, [2, 3, 4, 1]);
+        take_iter!(0..1 => only, ..empty);
+        assert_eq!((only, empty.len()), (0, 0));
+        take_iter!(0..3 => _, mut ..rest as tail);
+        tail.push(3);
+        assert_eq!(tail, [1, 2, 3]);
     }
-    {
-        let (x, y, z) = (1, 2, 3);
-        take!(mut x, mut y, mut z);
+
+    take_iter_array!([String::from("a"), String::from("b")] => [mut a, b,]);
+    a.push_str(&b);
+    assert_eq!(a, "ab");
+    take_iter_array!(core::iter::empty::<u8>() => []);
+}
+
+#[test]
+#[should_panic(expected = "`take_iter!` ran out of elements before `c`")]
+fn sanity_take_iter_panic() {
+    take_iter!([1, 2] => a, b, c);
+    let _ = (a, b, c);
+}
+
+#[test]
+#[should_panic(expected = "`take_iter!` ran out of elements before `second`")]
+fn sanity_take_iter_renamed_panic() {
+    take_iter!([1] => first as a, second as b);
+    let _ = (a, b);
+}
+
+#[test]
+#[should_panic(expected = "`take_iter_array!` has more elements than `[a, b]`")]
+fn sanity_take_iter_array_panic() {
+    take_iter_array!([1, 2, 3] => [a, b]);
+    let _ = (a, b);
+}
+
+#[test]
+fn sanity_bound_check() {
+    use std::fmt::Debug;
+    use std::rc::Rc;
+
+    let (a, b, c) = (vec![1], Rc::new(2), String::from("c"));
+    bound_check!(a: Send + Sync + Clone + Debug + 'static, b: Clone, c: AsRef<str> + Into<String>,);
+    bound_check!();
+    take!(a, &b);
+    bound_check!(a: Send, b: Copy);
+    assert_eq!((a, b.as_ref(), c), (vec![1], &2, String::from("c")));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sanity_take_callback() {
+    let (a, b, mut c) = (String::from("a"), vec![1, 2], 0);
+
+    let unit = take_callback!(; || ());
+    unit();
+    let add = take_callback!(=a, &b; |x: usize, y: usize,| a.len() + b.len() + x + y);
+    assert_eq!(add(1, 2), 6);
+    let ret: Box<dyn Fn(&str) -> String> = take_callback!(&a; move |s: &str| -> String { s.to_owned() + a });
+    assert_eq!(ret("b"), "ba");
+    drop((add, ret));
+
+    let mut bump = take_callback_mut!(&mut c; |by: i32| *c += by);
+    bump(2);
+    bump(3);
+    drop(bump);
+    assert_eq!(c, 5);
+
+    let consume = take_callback_once!(a, b as v,; || (a, v));
+    assert_eq!(consume(), (String::from("a"), vec![1, 2]));
+}
+
+#[test]
+fn sanity_take_path() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Inner {
+        name: String,
+        pair: (u8, Vec<u8>),
+    }
+    struct Outer {
+        inner: Inner,
+        count: usize,
+        tuple: (Inner, u8),
+    }
+    fn outer() -> Outer {
+        let inner = Inner { name: String::from("n"), pair: (1, vec![2]) };
+        Outer { inner: inner.clone(), count: 3, tuple: (inner, 4) }
     }
 
-    {
-        let (x, y, z) = (1, 2, 3);
-        take!(
-            mut x,
-            mut y,
-            mut z
-        );
+    let mut o = outer();
+    take_path!(
+        =o.inner.name,
+        =mut o.inner.pair.1 as bytes,
+        &o.inner.pair.0 as first,
+        ref o.tuple.1 as fourth,
+        o.count,
+    );
+    bytes.push(*first + *fourth);
+    assert_eq!((name.as_str(), bytes, count), ("n", vec![2, 5], 3));
 
-        take!(
-            &x,
-            &mut y,
-            z
-        );
+    take_path!(&mut o.inner.pair as pair, ref mut o.tuple.0.name as tuple_name);
+    pair.0 = 9;
+    tuple_name.push('!');
+    assert_eq!((o.inner.pair.0, o.tuple.0.name.as_str()), (9, "n!"));
+
+    take_path!(mut o.tuple.0.pair.1 as moved, o.inner.name);
+    moved.clear();
+    assert_eq!((moved.len(), name.as_str()), (0, "n"));
+
+    let mut calls = 0;
+    let mut make = || {
+        calls += 1;
+        outer()
+    };
+    take_path!((make()).tuple.0 as inner, &(make()).inner.pair.1 as borrowed);
+    assert_eq!((inner.name.as_str(), borrowed.len(), calls), ("n", 1, 2));
+
+    let fresh = outer();
+    let r = &fresh.inner;
+    take_path!(=r.name as cloned, &r.pair);
+    assert_eq!((cloned, pair.1.len()), (fresh.inner.name.clone(), 1));
+}
+
+#[test]
+fn sanity_take_or_default() {
+    let (a, b, c): (Option<String>, Result<Vec<u8>, ()>, Option<u32>) = (None, Ok(vec![1]), Some(3));
+    take_or_default!(a, mut b as bytes, #[allow(unused_mut)] mut c,);
+    bytes.push(2);
+    assert_eq!((a.as_str(), bytes, c), ("", vec![1, 2], 3));
+    take_or_default!();
+
+    let (d, e): (Option<&str>, Result<i32, String>) = (Some("d"), Err(String::from("e")));
+    take_or!(d, "fallback");
+    take_or!(mut e as n, -1,);
+    n -= 1;
+    assert_eq!((d, n), ("d", -2));
+}
+
+#[test]
+fn sanity_take_inspect() {
+    use std::cell::RefCell;
+
+    let seen = RefCell::new(Vec::<usize>::new());
+    let (a, b, c) = (String::from("a"), vec![1, 2], 3);
+    take_inspect!(
+        a |v| seen.borrow_mut().push(v.len()),
+        mut b as list |v: &Vec<i32>| seen.borrow_mut().push(v.len()),
+        c |_| seen.borrow_mut().push(0),
+    );
+    list.push(c);
+    take_inspect!();
+    take_inspect_mut!(mut list |v| v.push(4), a as renamed |s: &mut String| s.push('!'));
+    list.push(5);
+
+    if cfg!(debug_assertions) {
+        assert_eq!(*seen.borrow(), [1, 2, 0]);
+        assert_eq!((list, renamed.as_str()), (vec![1, 2, 3, 4, 5], "a!"));
+    } else {
+        assert!(seen.borrow().is_empty());
+        assert_eq!((list, renamed.as_str()), (vec![1, 2, 3, 5], "a"));
+    }
+}
+
+#[test]
+fn sanity_snapshot() {
+    let (mut a, b) = (String::from("a"), vec![1]);
+    snapshot!(a as a0, mut b as b0,);
+    a.push('!');
+    b0.push(2);
+    snapshot!(a as a1);
+    snapshot!();
+    take!(a, b);
+    assert_eq!((a0.as_str(), a1.as_str(), a.as_str()), ("a", "a!", "a!"));
+    assert_eq!((b0, b), (vec![1, 2], vec![1]));
+}
+
+#[test]
+fn sanity_take_count() {
+    let (a, b, c, d) = (String::from("a"), 2, vec![3], String::from("d"));
+    take_count!(=a, mut b, &c,);
+    b += 1;
+    let counted: [usize; TAKEN_COUNT] = [a.len(), b, c.len()];
+    assert_eq!((TAKEN_COUNT, counted), (3, [1, 3, 1]));
+
+    let strings = take_into_array!([=a as first, d, =a]);
+    let lens = take_into_array!([&c, &c as again,]);
+    let _: [String; 3] = strings.clone();
+    assert_eq!((strings, lens.len()), (["a".to_string(), "d".into(), "a".into()], 2));
+    assert_eq!(a, "a");
+}
+
+#[test]
+fn sanity_raw_identifiers() {
+    let (r#type, r#move, r#impl, r#trait) = (String::from("t"), vec![1], 3, String::from("tr"));
+    take!(=r#type, =mut r#move, &r#impl, ref r#trait);
+    r#move.push(2);
+    assert_eq!((r#type.as_str(), r#move, *r#impl, r#trait.as_str()), ("t", vec![1, 2], 3, "tr"));
+
+    let (r#type, mut r#move, r#impl, r#trait) = (String::from("t"), vec![1], Box::new(3), 4);
+    take!(&mut r#move as moved, *mut r#impl, mut r#trait, =r#type as r#struct);
+    moved.push(2);
+    r#impl += 1;
+    r#trait += 1;
+    assert_eq!((r#struct, r#impl, r#trait), (r#type.clone(), 4, 5));
+    take!(r#type as ty, r#move);
+    assert_eq!((ty.as_str(), r#move), ("t", vec![1, 2]));
+
+    let (r#type, r#move) = (Some(1), Some(String::from("m")));
+    take!(r#type else return, !r#move or "m" as r#impl, =r#type as r#trait);
+    let _: (i32, String, i32) = (r#type, r#impl, r#trait);
+
+    let (r#type, r#move) = (String::from("a"), String::from("b"));
+    take!({ =r#type, &r#move } => assert_eq!((r#type.as_str(), r#move.as_str()), ("a", "b")));
+    conditional_take!(=r#type if true else String::new());
+    cloned!(r#type, mut r#move as r#trait);
+    r#trait.push('!');
+    assert_eq!((r#type, r#trait), (String::from("a"), String::from("b!")));
+
+    struct Field {
+        r#type: Option<u8>,
+        r#move: u8,
     }
+    let r#struct = Field { r#type: None, r#move: 2 };
+    take_field!(r#struct => r#type, r#move as r#ref);
+    try_take!(r#type or r#ref);
+    assert_eq!(r#type, 2);
 
+    let (r#type, r#move, r#impl, r#trait) = (1, String::from("m"), 3, String::from("t"));
+    take!(hold r#type, drop r#move, send sync r#impl, =r#trait as [r#fn, r#for]);
+    assert_eq!(r#fn, "t");
+    take!(together: small[8] r#impl as r#in, must r#trait);
+    take!(not r#fn);
+    let _: (i32, String) = (r#in, r#for);
+    let _ = r#trait.into_inner();
+
+    let r#async = async {};
+    take!(pin r#async);
+    let _pinned: core::pin::Pin<&mut _> = r#async;
+
+    #[cfg(feature = "alloc")]
     {
-        // with trailing comma
-        let (x, y, z) = (1, 2, 3);
-        take!(
-            x,
-            y,
-            z,
-        );
+        use std::borrow::Cow;
+        use std::rc::Rc;
+
+        let (r#type, r#move, r#impl) = (String::from("t"), vec![1], Rc::new(vec![2]));
+        take!(cow r#type, shrink r#move, arc_clone r#impl as r#trait, weak r#impl as r#dyn);
+        take!(unwrap r#impl);
+        let _: (Cow<str>, Box<[i32]>, Vec<i32>) = (r#type, r#move, r#impl);
+        assert_eq!((r#trait.len(), r#dyn.upgrade().is_some()), (1, true));
+    }
+}
+
+#[test]
+fn sanity_clone_many() {
+    let (x, y) = (vec![1], String::from("y"));
+    take!(=x as a, =x as b, =mut x as c, &y as r, x as d, =y as e, #[cfg(any())] y as d);
+    c.push(2);
+    assert_eq!((a, b, c, d), (vec![1], vec![1], vec![1, 2], vec![1]));
+    assert_eq!((r.as_str(), e.as_str()), ("y", "y"));
+}
+
+#[test]
+fn sanity_scope_spawn() {
+    use std::sync::mpsc::channel;
+    use std::thread;
+
+    let data = vec![1, 2, 3, 4];
+    let mut doubled = Vec::new();
+    let (tx, rx) = channel();
+    let (sum, max) = thread::scope(|s| {
+        let sum = scope_spawn!(s, (&data, =tx) {
+            let sum: u32 = data.iter().sum();
+            tx.send(sum).unwrap();
+            sum
+        });
+        let max = scope_spawn!(s, (&data, =tx as sender) {
+            sender.send(0).unwrap();
+            *data.iter().max().unwrap()
+        });
+        let double = scope_spawn!(s, (&data, &mut doubled) {
+            doubled.extend(data.iter().map(|v| v * 2));
+        });
+        double.join().unwrap();
+        (sum.join().unwrap(), max.join().unwrap())
+    });
+    drop(tx);
+    let mut sent: Vec<u32> = rx.iter().collect();
+    sent.sort();
+    assert_eq!((sum, max), (10, 4));
+    assert_eq!(sent, vec![0, 10]);
+    assert_eq!(doubled, vec![2, 4, 6, 8]);
+    assert_eq!(data, vec![1, 2, 3, 4]);
+}
+
+#[test]
+#[allow(unused_mut, unused_variables)]
+fn sanity_spread() {
+    let tuple = (1, String::from("b"), vec![3], 4);
+    take!(..tuple as (a, &b, =mut c, mut d));
+    let b: &String = b;
+    c.push(4);
+    d += 1;
+    assert_eq!((a, b.as_str(), c, d), (1, "b", vec![3, 4], 5));
+
+    fn make() -> (u32, Box<u32>) {
+        (1, Box::new(2))
     }
+    let x = 10;
+    take!(..(make()) as (mut a, *b), &x,);
+    a += *x;
+    assert_eq!((a, b), (11, 2));
+
+    let single = (String::from("single"),);
+    take!(..single as (&mut s));
+    s.push('!');
+    assert_eq!(s, "single!");
+}
 }
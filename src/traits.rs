@@ -0,0 +1,57 @@
+//! Traits that let your own types take part in `take!`.
+
+#[cfg(feature = "alloc")]
+use alloc::{rc::Rc, sync::Arc};
+
+/// A handle whose clone only increments a reference count.
+///
+/// Used by `take!(rc_clone x)` and [`take_ref_counted!`](macro.take_ref_counted.html), which
+/// expand to `let x = RefCounted::ref_clone(&x);`. It is implemented for `Arc<T>` and `Rc<T>`
+/// (with the `alloc` feature), and can be implemented for interned strings, arena handles or any
+/// other type whose clone is cheap and shares the underlying data.
+///
+/// ```rust
+/// # #[macro_use] extern crate taken;
+/// # fn main() {
+/// use taken::RefCounted;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Handle(u32);
+///
+/// impl RefCounted for Handle {
+///     fn ref_clone(&self) -> Self {
+///         // increment the count in the arena here
+///         Handle(self.0)
+///     }
+/// }
+///
+/// let handle = Handle(7);
+/// take!(rc_clone handle as copy); // let copy = RefCounted::ref_clone(&handle);
+/// assert_eq!(copy, handle);
+/// # }
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` doesn't implement `RefCounted`",
+    label = "expected an `Arc<T>`, an `Rc<T>` or a type implementing `taken::RefCounted`",
+    note = "`rc_clone` only clones reference counted handles; use `=x` or `=*x` to clone other values"
+)]
+pub trait RefCounted {
+    /// Returns a new handle to the same data, incrementing the reference count.
+    fn ref_clone(&self) -> Self;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> RefCounted for Arc<T> {
+    #[inline(always)]
+    fn ref_clone(&self) -> Self {
+        Arc::clone(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> RefCounted for Rc<T> {
+    #[inline(always)]
+    fn ref_clone(&self) -> Self {
+        Rc::clone(self)
+    }
+}
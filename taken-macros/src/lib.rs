@@ -0,0 +1,1558 @@
+//! Procedural macros for the [`taken`](https://docs.rs/taken) crate.
+//!
+//! Don't depend on this crate directly. Enable the `macros` feature of `taken` instead, which
+//! re-exports everything in here. The expansions refer to `::taken`.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Group, Ident, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::{Expr, GenericParam, Token};
+
+/// Annotate a closure with how it captures its environment.
+///
+/// `#[capture(...)]` accepts the same entries as `take!` and hoists them out of the closure, which
+/// is turned into a `move` closure so that the hoisted bindings are what it captures:
+///
+/// ```rust,ignore
+/// #[capture(x, =y, &mut z)]
+/// move || { body }
+/// // expands to
+/// {
+///     ::taken::take!(x, =y, &mut z);
+///     move || { body }
+/// }
+/// ```
+///
+/// This keeps long capture lists out of the closure body. Async closures and `async` blocks are
+/// supported as well.
+///
+/// Attributes on expressions are not stable yet, so the crate using `#[capture]` has to enable
+/// `#![feature(stmt_expr_attributes, proc_macro_hygiene)]` on a nightly compiler.
+#[proc_macro_attribute]
+pub fn capture(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand_capture(attr.into(), item.into())
+        .unwrap_or_else(compile_error)
+        .into()
+}
+
+/// Declare the complete set of variables a closure captures.
+///
+/// Takes the same entries as `take!` and hoists them out of the closure like
+/// [`#[capture(...)]`](attr.capture.html), but also hides every other local variable from the
+/// closure, so using one is a compile error naming it. Entries that are listed twice or never
+/// used by the closure are rejected as well.
+///
+/// It can be put on a function whose body is a single closure, where the entries refer to the
+/// parameters:
+///
+/// ```rust,ignore
+/// #[captures(tx, =config)]
+/// fn handler(tx: Sender<u32>, config: &Config, debug: bool) -> impl FnOnce(u32) {
+///     move |n| tx.send(n * config.factor).unwrap() // using `debug` fails to compile
+/// }
+/// ```
+///
+/// Or on a `let` statement binding a closure, which requires `#![feature(proc_macro_hygiene)]` on
+/// a nightly compiler:
+///
+/// ```rust,ignore
+/// #[captures(tx, &metrics)]
+/// let handler = move |n| { metrics.record(n); tx.send(n).unwrap() };
+/// ```
+///
+/// Items such as functions, constants and types can still be used. `self` can't be hidden, so it
+/// stays usable without being listed.
+#[proc_macro_attribute]
+pub fn captures(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = TokenStream2::from(item);
+    expand_captures(attr.into(), item.clone())
+        .unwrap_or_else(|err| {
+            // keep the item, so that the errors aren't followed by unresolved names
+            let mut errors = item_compile_error(err);
+            errors.extend(item);
+            errors
+        })
+        .into()
+}
+
+/// Build a closure whose body can only use the listed captures.
+///
+/// The body is moved into a nested `fn` item, which takes the captures and the arguments of the
+/// closure as parameters, and the result is a `move` closure that forwards its calls to it. Using
+/// any other local variable in the body fails to compile with "can't capture dynamic environment
+/// in a fn item".
+///
+/// ```rust,ignore
+/// let retry = take_fn!((&job: Job, =tx: Sender<u32>) |attempt: u32| -> bool {
+///     tx.send(attempt).unwrap();
+///     job.run(attempt)
+/// });
+/// // expands to
+/// {
+///     ::taken::take!(&job as job, =tx as tx);
+///     fn __take_fn(job: &Job, tx: &Sender<u32>, attempt: u32) -> bool { ... }
+///     move |attempt: u32| __take_fn(job, &tx, attempt)
+/// }
+/// ```
+///
+/// The entries are those of `take!`, each followed by the type of the value. Since a `fn` item
+/// can't infer types, the arguments of the closure need types as well. The function sees every
+/// capture by reference: `&mut` for `&mut` entries and entries with `mut`, `&` for the others. This
+/// way the closure can be called more than once and is `Fn`, or `FnMut` if something is mutable.
+/// `hold` and `drop` entries aren't passed to the function.
+///
+/// Like any `fn` item, the body can't use `self` or the generic parameters of the enclosing
+/// function.
+#[proc_macro]
+pub fn take_fn(input: TokenStream) -> TokenStream {
+    expand_take_fn(input.into())
+        .unwrap_or_else(compile_error)
+        .into()
+}
+
+/// Derive a builder that dismantles a struct field by field.
+///
+/// `#[derive(TakeBuilder)]` on `struct Ctx` generates `Ctx::take_builder(self)`, which
+/// destructures the struct into a `CtxTakeBuilder` that owns every field. Each field can then be
+/// moved out once, with `take_field()` or `field()`, without needing `Default`.
+///
+/// `Ctx::take_builder_mut(&mut self)` leaves the struct in place and holds a `&mut` to every field
+/// instead. Each field can then be taken out once, in one of three modes:
+///
+/// - `take_field()` moves the value out, leaving `Default::default()` in the struct (like
+///   `with_taken!`).
+/// - `clone_field()` clones the value.
+/// - `borrow_field()` borrows it immutably.
+/// - `field()` uses the mode set with `#[take(mode = "take" | "clone" | "borrow")]` on the field,
+///   `"take"` if there is none.
+///
+/// Every method consumes the builder and returns a new one, and `build()` returns a `CtxTaken`
+/// with a public field for each field of `Ctx`. The fields that weren't taken are left as `&mut`s
+/// into the struct, or as [`Untaken`](https://docs.rs/taken/*/taken/struct.Untaken.html)s if the
+/// builder owns them.
+///
+/// ```rust,ignore
+/// #[derive(TakeBuilder)]
+/// struct Ctx {
+///     name: String,
+///     #[take(mode = "clone")]
+///     tx: Sender<Event>,
+///     config: Config,
+/// }
+///
+/// let CtxTaken { name, tx, config, .. } =
+///     ctx.take_builder_mut().take_name().tx().borrow_config().build();
+/// // name: String, tx: Sender<Event>, config: &Config
+///
+/// let CtxTaken { name, tx, config } = ctx.take_builder().name().take_config().build();
+/// // name: String, tx: Untaken<Sender<Event>>, config: Config
+/// ```
+///
+/// Taking the same field twice fails to compile, as does `take_field()` through
+/// `take_builder_mut()` if the type doesn't implement `Default`. Structs that implement `Drop`
+/// can't derive `TakeBuilder`, since `take_builder()` moves their fields out.
+#[proc_macro_derive(TakeBuilder, attributes(take))]
+pub fn take_builder(item: TokenStream) -> TokenStream {
+    expand_take_builder(item.into())
+        .unwrap_or_else(item_compile_error)
+        .into()
+}
+
+/// Take parameters at the start of a function.
+///
+/// `#[taken(clone(b, c), freeze(a))]` inserts `::taken::take!(=b, =c, a);` as the first statement
+/// of the body, so that the function works on clones of `b` and `c` and can no longer mutate `a`.
+/// A parameter can also be frozen with `#[freeze]` in the signature, which is the same as listing
+/// it in `freeze(...)`:
+///
+/// ```rust,ignore
+/// #[taken(clone(config))]
+/// fn run(config: &Config, #[freeze] retries: u32) {
+///     let retries = retries.min(config.max_retries);
+///     // ...
+/// }
+/// // the body becomes
+/// {
+///     ::taken::take!(=config, retries);
+///     let retries = retries.min(config.max_retries);
+///     // ...
+/// }
+/// ```
+///
+/// Since the parameters are taken before any other code runs, a frozen parameter declared `mut`
+/// gets an `unused_mut` warning, and assigning to it fails to compile.
+///
+/// The entries are taken in the order they are listed in the attribute, followed by the
+/// `#[freeze]` parameters in the order of the signature, before anything else in the body. Every
+/// entry has to name a parameter, at most once. Everything else about the function, including its
+/// attributes, generics, `async`, `unsafe` and `extern`, is kept as is.
+#[proc_macro_attribute]
+pub fn taken(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = TokenStream2::from(item);
+    expand_taken(attr.into(), item.clone())
+        .unwrap_or_else(|err| {
+            let mut errors = item_compile_error(err);
+            errors.extend(item);
+            errors
+        })
+        .into()
+}
+
+/// Derive helpers for taking single fields out of a struct.
+///
+/// `#[derive(TakeFields)]` on `struct Ctx` generates, for every field, a
+/// `take_field(&mut self) -> Field` that moves the value out and leaves `Default::default()` in its
+/// place, with the same visibility as the field. Fields marked `#[take(skip)]` don't get one.
+/// `into_fields(self)` splits the struct into a tuple of all fields, in the order they are
+/// declared.
+///
+/// ```rust,ignore
+/// #[derive(TakeFields)]
+/// struct Ctx {
+///     sender: Sender<Event>,
+///     cache: Option<Cache>,
+///     #[take(skip)]
+///     db: Db, // not `Default`
+/// }
+///
+/// take!(ctx.take_sender() as tx); // `ctx.sender` is now a disconnected `Sender`
+/// let (_, cache, db) = ctx.into_fields();
+/// ```
+///
+/// `take_field()` only requires `Default` when it is used. `into_fields()` is private unless all
+/// fields have the same visibility, and can't be used on types that implement `Drop`.
+#[proc_macro_derive(TakeFields, attributes(take))]
+pub fn take_fields(item: TokenStream) -> TokenStream {
+    expand_take_fields(item.into())
+        .unwrap_or_else(item_compile_error)
+        .into()
+}
+/// Take the fields of a struct, each in its own way.
+///
+/// `take_all_fields!(config => { a, =b, &c, mut d as e })` binds the listed fields with the sigils
+/// of `take!`:
+///
+/// ```rust,ignore
+/// take_all_fields!(config => { a, =b, &c, mut d as e });
+/// // expands to
+/// let a = (config).a;
+/// let b = (config).b.clone();
+/// let c = &(config).c;
+/// let mut e = (config).d;
+/// ```
+///
+/// Fields that aren't listed are left alone, as with `..` in a pattern. Fields are taken one at a
+/// time, so `config` keeps the fields that were cloned or borrowed and can be a reference as long
+/// as nothing is moved out of it. A source that isn't a place, like `make_config()`, is evaluated
+/// once. The entries can be moves, `mut`, `=`, `&`, `ref` and `*`, with an optional `as` rename.
+/// A field that doesn't support its sigil is a compile error at the field, e.g. `=b` if `b` isn't
+/// `Clone`.
+#[proc_macro]
+pub fn take_all_fields(input: TokenStream) -> TokenStream {
+    expand_take_all_fields(input.into())
+        .unwrap_or_else(item_compile_error)
+        .into()
+}
+
+/// Used by the macros defined with `capture_set!`: `__take_set!([path] anchor entries)` expands to
+/// `path![entries]`, with the entries resolved where `anchor` was written instead of inside the
+/// `macro_rules!` that stored them, so that the `let`s are visible to the code after the `take!`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __take_set(input: TokenStream) -> TokenStream {
+    expand_take_set(input.into())
+        .unwrap_or_else(item_compile_error)
+        .into()
+}
+
+/// Like `syn::Error::to_compile_error`, but without the `::core` path that 2015 edition crates
+/// can't resolve.
+fn compile_error(err: syn::Error) -> TokenStream2 {
+    err.into_iter()
+        .map(|err| {
+            let msg = err.to_string();
+            quote_spanned!(err.span()=> { compile_error!(#msg) })
+        })
+        .collect()
+}
+
+/// Like `compile_error`, but usable where items and statements are expected.
+fn item_compile_error(err: syn::Error) -> TokenStream2 {
+    err.into_iter()
+        .map(|err| {
+            let msg = err.to_string();
+            quote_spanned!(err.span()=> compile_error! { #msg })
+        })
+        .collect()
+}
+
+fn expand_capture(captures: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let closure = match syn::parse2::<Expr>(item)? {
+        Expr::Closure(mut closure) => {
+            closure.capture = Some(<Token![move]>::default());
+            Expr::Closure(closure)
+        }
+        Expr::Async(mut block) => {
+            block.capture = Some(<Token![move]>::default());
+            Expr::Async(block)
+        }
+        other => {
+            return Err(syn::Error::new_spanned(
+                other,
+                "`#[capture(...)]` can only be applied to closures and async blocks",
+            ))
+        }
+    };
+    Ok(quote! {{
+        ::taken::take!(#captures);
+        #closure
+    }})
+}
+
+fn expand_captures(captures: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    if let Ok(mut func) = syn::parse2::<syn::ItemFn>(item.clone()) {
+        let body = match single_expr(&func.block) {
+            Some(body) => hide_environment(captures, body)?,
+            None => {
+                return Err(syn::Error::new_spanned(
+                    &func.block,
+                    "`#[captures(...)]` functions must consist of a single closure",
+                ))
+            }
+        };
+        func.block = Box::new(syn::parse_quote!({ #body }));
+        return Ok(func.into_token_stream());
+    }
+    match syn::parse2::<syn::Stmt>(item)? {
+        syn::Stmt::Local(syn::Local {
+            attrs,
+            let_token,
+            pat,
+            init:
+                Some(syn::LocalInit {
+                    expr,
+                    diverge: None,
+                    ..
+                }),
+            semi_token,
+        }) => {
+            let init = hide_environment(captures, &expr)?;
+            Ok(quote!(#(#attrs)* #let_token #pat = #init #semi_token))
+        }
+        other => Err(syn::Error::new_spanned(
+            other,
+            "`#[captures(...)]` can only be applied to functions and `let` statements",
+        )),
+    }
+}
+
+fn single_expr(block: &syn::Block) -> Option<&Expr> {
+    match block.stmts.as_slice() {
+        [syn::Stmt::Expr(expr, None)] => Some(expr),
+        _ => None,
+    }
+}
+
+/// A `take!` entry of `#[captures(...)]`.
+struct Capture {
+    /// The entry, without an `as` rename.
+    source: TokenStream2,
+    /// The name the closure sees.
+    name: Ident,
+    /// `hold` or `drop`, whose values aren't meant to be used by the closure.
+    keyword: Option<String>,
+}
+
+/// Split `captures` into `take!` entries, `what` is the macro for the error messages.
+fn parse_captures(captures: TokenStream2, what: &str) -> syn::Result<Vec<Capture>> {
+    let mut entries = vec![Vec::new()];
+    for token in captures {
+        match token {
+            TokenTree::Punct(ref p) if p.as_char() == ',' => entries.push(Vec::new()),
+            token => entries.last_mut().unwrap().push(token),
+        }
+    }
+    if entries.last().is_some_and(Vec::is_empty) {
+        entries.pop();
+    }
+    entries
+        .into_iter()
+        .map(|mut tokens| {
+            let span = match tokens.first() {
+                Some(first) => first.span(),
+                None => return Err(syn::Error::new(Span::call_site(), "found an empty entry")),
+            };
+            let keyword = match tokens.first() {
+                Some(TokenTree::Ident(ident)) if ident == "hold" || ident == "drop" => {
+                    Some(ident.to_string())
+                }
+                _ => None,
+            };
+            let name = match tokens.as_slice() {
+                [.., TokenTree::Ident(as_), TokenTree::Ident(name)] if as_ == "as" => {
+                    let name = name.clone();
+                    tokens.truncate(tokens.len() - 2);
+                    name
+                }
+                [.., TokenTree::Ident(name)] => name.clone(),
+                _ => {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`{}` entries must end with the name of a variable", what),
+                    ))
+                }
+            };
+            Ok(Capture {
+                source: tokens.into_iter().collect(),
+                name,
+                keyword,
+            })
+        })
+        .collect()
+}
+
+/// Give the identifiers of `tokens` `macro_rules!` hygiene, so that they can't see the local
+/// variables of the call site. Items are still resolved at the call site.
+fn respan(tokens: TokenStream2) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(mut ident) => {
+                if !matches!(
+                    ident.to_string().as_str(),
+                    "self" | "Self" | "super" | "crate"
+                ) {
+                    ident.set_span(Span::mixed_site().located_at(ident.span()));
+                }
+                TokenTree::Ident(ident)
+            }
+            TokenTree::Literal(mut lit) => {
+                // format strings can refer to variables as well
+                lit.set_span(Span::mixed_site().located_at(lit.span()));
+                TokenTree::Literal(lit)
+            }
+            TokenTree::Group(group) => {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream()));
+                respanned.set_span(group.span());
+                TokenTree::Group(respanned)
+            }
+            token => token,
+        })
+        .collect()
+}
+
+/// Resolve the identifiers and literals of `tokens` at `site`, keeping their locations.
+fn resolve_at(tokens: TokenStream2, site: Span) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(mut ident) => {
+                ident.set_span(ident.span().resolved_at(site));
+                TokenTree::Ident(ident)
+            }
+            TokenTree::Literal(mut lit) => {
+                lit.set_span(lit.span().resolved_at(site));
+                TokenTree::Literal(lit)
+            }
+            TokenTree::Group(group) => {
+                let mut resolved = Group::new(group.delimiter(), resolve_at(group.stream(), site));
+                resolved.set_span(group.span());
+                TokenTree::Group(resolved)
+            }
+            token => token,
+        })
+        .collect()
+}
+
+fn mentions(tokens: TokenStream2, name: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == *name,
+        TokenTree::Group(group) => mentions(group.stream(), name),
+        _ => false,
+    })
+}
+
+fn hide_environment(captures: TokenStream2, closure: &Expr) -> syn::Result<TokenStream2> {
+    let closure = match closure.clone() {
+        Expr::Closure(mut closure) => {
+            closure.capture = Some(<Token![move]>::default());
+            Expr::Closure(closure)
+        }
+        Expr::Async(mut block) => {
+            block.capture = Some(<Token![move]>::default());
+            Expr::Async(block)
+        }
+        other => {
+            return Err(syn::Error::new_spanned(
+                other,
+                "`#[captures(...)]` requires a closure or an async block",
+            ))
+        }
+    };
+    let closure = closure.into_token_stream();
+
+    let captures = parse_captures(captures, "#[captures(...)]")?;
+    let mut errors: Option<syn::Error> = None;
+    let mut push = |err: syn::Error| match errors {
+        Some(ref mut errors) => errors.combine(err),
+        None => errors = Some(err),
+    };
+    let mut entries = Vec::new();
+    for (i, capture) in captures.iter().enumerate() {
+        if capture.keyword.as_deref() == Some("drop") {
+            entries.push(capture.source.clone());
+            continue;
+        }
+        if captures[..i].iter().any(|c| c.name == capture.name) {
+            push(syn::Error::new(
+                capture.name.span(),
+                format!("`{}` is captured more than once", capture.name),
+            ));
+        } else if capture.keyword.is_none() && !mentions(closure.clone(), &capture.name) {
+            push(syn::Error::new(
+                capture.name.span(),
+                format!(
+                    "`{}` is captured but never used by the closure",
+                    capture.name
+                ),
+            ));
+        }
+        let source = &capture.source;
+        let name = respan(capture.name.to_token_stream());
+        entries.push(quote!(#source as #name));
+    }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+    let closure = respan(closure);
+    Ok(quote! {{
+        ::taken::take!(#(#entries),*);
+        #closure
+    }})
+}
+
+/// A `take_fn!` capture: a `take!` entry and the type of its value.
+struct TypedCapture {
+    capture: Capture,
+    ty: syn::Type,
+}
+
+fn parse_typed_capture(input: ParseStream) -> syn::Result<TypedCapture> {
+    let mut tokens = TokenStream2::new();
+    while !input.is_empty() && (input.peek(Token![::]) || !input.peek(Token![:])) {
+        tokens.extend(Some(input.parse::<TokenTree>()?));
+    }
+    let span = input.span();
+    if input.parse::<Option<Token![:]>>()?.is_none() {
+        return Err(syn::Error::new(
+            span,
+            "`take_fn!` captures need the type of the value, i.e. `=x: Vec<u32>`",
+        ));
+    }
+    let ty = input.parse()?;
+    let capture = parse_captures(tokens, "take_fn!")?
+        .pop()
+        .ok_or_else(|| syn::Error::new(span, "found an empty entry"))?;
+    Ok(TypedCapture { capture, ty })
+}
+
+fn expand_take_fn(input: TokenStream2) -> syn::Result<TokenStream2> {
+    let (captures, closure) = (|input: ParseStream| {
+        let content;
+        syn::parenthesized!(content in input);
+        let captures =
+            Punctuated::<_, Token![,]>::parse_terminated_with(&content, parse_typed_capture)?;
+        Ok((captures, input.parse::<syn::ExprClosure>()?))
+    })
+    .parse2(input)?;
+    if let Some(asyncness) = closure.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "`take_fn!` doesn't support async closures",
+        ));
+    }
+
+    let mut entries = Vec::new();
+    let mut params = Vec::new();
+    let mut passed = Vec::new();
+    for TypedCapture { capture, ty } in &captures {
+        let source = &capture.source;
+        let name = &capture.name;
+        if capture.keyword.as_deref() == Some("drop") {
+            entries.push(source.clone());
+            continue;
+        }
+        entries.push(quote!(#source as #name));
+        if capture.keyword.is_some() {
+            continue;
+        }
+        let words: Vec<String> = source.clone().into_iter().map(|t| t.to_string()).collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let (param, pass) = match words.as_slice() {
+            ["&", "mut", ..] | ["ref", "mut", ..] => (quote!(&mut #ty), quote!(&mut *#name)),
+            ["&", ..] | ["ref", ..] => (quote!(&#ty), quote!(#name)),
+            words if words.contains(&"mut") => (quote!(&mut #ty), quote!(&mut #name)),
+            _ => (quote!(&#ty), quote!(&#name)),
+        };
+        params.push(quote!(#name: #param));
+        passed.push(pass);
+    }
+
+    let mut inputs = Vec::new();
+    let mut args = Vec::new();
+    let mut forwarded = Vec::new();
+    for (i, input) in closure.inputs.iter().enumerate() {
+        let ty = match input {
+            syn::Pat::Type(pat) => &pat.ty,
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "`take_fn!` closure arguments need a type, i.e. `|x: u32|`",
+                ))
+            }
+        };
+        let arg = Ident::new(&format!("__arg{}", i), Span::mixed_site());
+        inputs.push(input);
+        args.push(quote!(#arg: #ty));
+        forwarded.push(arg);
+    }
+    let output = &closure.output;
+    let body = match *closure.body {
+        Expr::Block(ref block) if block.label.is_none() && block.attrs.is_empty() => {
+            block.to_token_stream()
+        }
+        ref body => quote!({ #body }),
+    };
+    let func = Ident::new("__take_fn", Span::mixed_site());
+    Ok(quote! {{
+        ::taken::take!(#(#entries),*);
+        // the captures are passed as `&T`, even where `&str` or `&[T]` would be more general
+        #[allow(clippy::ptr_arg)]
+        fn #func(#(#params,)* #(#inputs),*) #output #body
+        move |#(#args),*| #func(#(#passed,)* #(#forwarded),*)
+    }})
+}
+
+fn expand_take_set(input: TokenStream2) -> syn::Result<TokenStream2> {
+    let mut tokens = input.into_iter();
+    let path = match tokens.next() {
+        Some(TokenTree::Group(group)) => group.stream(),
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "expected `[path] anchor entries`",
+            ))
+        }
+    };
+    let anchor = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "expected `[path] anchor entries`",
+            ))
+        }
+    };
+    let entries = resolve_at(tokens.collect(), anchor.span());
+    Ok(quote!(#path![#entries];))
+}
+
+fn expand_take_all_fields(input: TokenStream2) -> syn::Result<TokenStream2> {
+    let (source, entries) = (|input: ParseStream| {
+        let source = input.parse::<Expr>()?;
+        input.parse::<Token![=>]>()?;
+        let content;
+        syn::braced!(content in input);
+        Ok((source, content.parse::<TokenStream2>()?))
+    })
+    .parse2(input)?;
+
+    let mut out = TokenStream2::new();
+    // places are taken from field by field, anything else is evaluated once
+    let source = match source {
+        Expr::Path(_) | Expr::Field(_) | Expr::Index(_) => quote!((#source)),
+        Expr::Unary(ref unary) if matches!(unary.op, syn::UnOp::Deref(_)) => quote!((#source)),
+        source => {
+            let value = Ident::new("__taken_fields", Span::mixed_site());
+            out.extend(quote!(let #value = #source;));
+            value.into_token_stream()
+        }
+    };
+    for capture in parse_captures(entries, "take_all_fields!")? {
+        let mut sigil: Vec<TokenTree> = capture.source.into_iter().collect();
+        let field = match sigil.pop() {
+            Some(TokenTree::Ident(field)) => field,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &capture.name,
+                    "`take_all_fields!` entries must name a field",
+                ))
+            }
+        };
+        let name = &capture.name;
+        let words: Vec<String> = sigil.iter().map(ToString::to_string).collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let span = field.span();
+        let (trace, binding, value) = match words.as_slice() {
+            [] => ("", quote!(#name), quote_spanned!(span=> #source.#field)),
+            ["mut"] => (
+                "mut",
+                quote!(mut #name),
+                quote_spanned!(span=> #source.#field),
+            ),
+            ["="] => (
+                "=",
+                quote!(#name),
+                quote_spanned!(span=> ::taken::__take_clone!(#source.#field)),
+            ),
+            ["=", "mut"] => (
+                "=mut",
+                quote!(mut #name),
+                quote_spanned!(span=> ::taken::__take_clone!(#source.#field)),
+            ),
+            ["&"] => ("&", quote!(#name), quote_spanned!(span=> &#source.#field)),
+            ["&", "mut"] => (
+                "&mut",
+                quote!(#name),
+                quote_spanned!(span=> &mut #source.#field),
+            ),
+            ["ref"] => ("ref", quote!(#name), quote_spanned!(span=> &#source.#field)),
+            ["ref", "mut"] => (
+                "ref mut",
+                quote!(#name),
+                quote_spanned!(span=> &mut #source.#field),
+            ),
+            ["*"] => ("*", quote!(#name), quote_spanned!(span=> *#source.#field)),
+            ["*", "mut"] => (
+                "*mut",
+                quote!(mut #name),
+                quote_spanned!(span=> *#source.#field),
+            ),
+            _ => {
+                let entry: TokenStream2 = sigil.into_iter().chain(Some(field.into())).collect();
+                return Err(syn::Error::new_spanned(
+                    &entry,
+                    format!(
+                        "`take_all_fields!` entries take a field with no sigil, `mut`, `=`, `&`, \
+                         `ref` or `*`, found `{}`",
+                        entry
+                    ),
+                ));
+            }
+        };
+        out.extend(quote! {
+            let #binding = #value;
+            ::taken::__take_trace!(#trace, #name);
+        });
+    }
+    Ok(out)
+}
+
+/// How a field of a `#[derive(TakeBuilder)]` struct is taken by default.
+#[derive(Clone, Copy)]
+enum Mode {
+    Take,
+    Clone,
+    Borrow,
+}
+
+/// The `#[take(...)]` attributes of a field, shared by `TakeBuilder` and `TakeFields`.
+struct FieldAttrs {
+    mode: Mode,
+    /// `#[take(skip)]`: no `take_field()` for `#[derive(TakeFields)]`.
+    skip: bool,
+}
+
+fn field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs {
+        mode: Mode::Take,
+        skip: false,
+    };
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("take"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                attrs.skip = true;
+                return Ok(());
+            }
+            if !meta.path.is_ident("mode") {
+                return Err(meta.error("expected `mode = \"...\"` or `skip`"));
+            }
+            let lit: syn::LitStr = meta.value()?.parse()?;
+            attrs.mode = match lit.value().as_str() {
+                "take" => Mode::Take,
+                "clone" => Mode::Clone,
+                "borrow" => Mode::Borrow,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "expected `\"take\"`, `\"clone\"` or `\"borrow\"`",
+                    ))
+                }
+            };
+            Ok(())
+        })?;
+    }
+    Ok(attrs)
+}
+
+/// `field_name` to `FieldName`.
+fn camel_case(ident: &Ident) -> String {
+    ident
+        .unraw()
+        .to_string()
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn expand_take_builder(item: TokenStream2) -> syn::Result<TokenStream2> {
+    let input = syn::parse2::<syn::DeriveInput>(item)?;
+    let fields = match input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(ref fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`#[derive(TakeBuilder)]` only supports structs with named fields",
+            ))
+        }
+    };
+    let vis = &input.vis;
+    let name = &input.ident;
+    let builder = format_ident!("{}TakeBuilder", name);
+    let taken = format_ident!("{}Taken", name);
+    let lt = syn::Lifetime::new("'__taken", Span::call_site());
+
+    // the generics of the struct, without defaults since the states of the fields come after them
+    let mut params = input.generics.params.clone();
+    for param in params.iter_mut() {
+        match param {
+            GenericParam::Type(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Const(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+    let params: Vec<_> = params.into_iter().collect();
+    let args: Vec<_> = input
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        })
+        .collect();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let idents: Vec<&Ident> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let tys: Vec<&syn::Type> = fields.iter().map(|f| &f.ty).collect();
+    let states: Vec<Ident> = idents
+        .iter()
+        .map(|ident| format_ident!("__{}", camel_case(ident)))
+        .collect();
+    let modes = fields
+        .iter()
+        .map(|field| Ok(field_attrs(field)?.mode))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mut methods = Vec::new();
+    for (i, field) in idents.iter().enumerate() {
+        let ty = tys[i];
+        let others: Vec<_> = states
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, s)| s)
+            .collect();
+        let with_state = |state: TokenStream2| {
+            let states = states.iter().enumerate().map(|(j, s)| {
+                if j == i {
+                    state.clone()
+                } else {
+                    s.to_token_stream()
+                }
+            });
+            quote!(#builder<#lt, #(#args,)* #(#states),*>)
+        };
+        let pending = with_state(quote!(&#lt mut #ty));
+        let owned = with_state(ty.to_token_stream());
+        let borrowed = with_state(quote!(&#lt #ty));
+        let with_value = |value: TokenStream2| {
+            let values = idents.iter().enumerate().map(|(j, ident)| {
+                if j == i {
+                    quote!(#ident: #value)
+                } else {
+                    quote!(#ident: self.#ident)
+                }
+            });
+            quote!(#builder { #(#values,)* __source: ::core::marker::PhantomData })
+        };
+        let untaken = with_state(quote!(::taken::Untaken<#ty>));
+        let take = with_value(quote!(::core::mem::take(self.#field)));
+        let take_owned = with_value(quote!(self.#field.0));
+        let clone = with_value(quote!(::core::clone::Clone::clone(&*self.#field)));
+        let borrow = with_value(quote!(self.#field));
+        let unraw = field.unraw();
+        let take_fn = format_ident!("take_{}", unraw);
+        let clone_fn = format_ident!("clone_{}", unraw);
+        let borrow_fn = format_ident!("borrow_{}", unraw);
+        let (default_fn, default_ty, default_bound) = match modes[i] {
+            Mode::Take => (
+                &take_fn,
+                &owned,
+                quote!(where for<'__x> #ty: ::core::default::Default,),
+            ),
+            Mode::Clone => (
+                &clone_fn,
+                &owned,
+                quote!(where for<'__x> #ty: ::core::clone::Clone,),
+            ),
+            Mode::Borrow => (&borrow_fn, &borrowed, quote!()),
+        };
+        let take_doc = format!(
+            "Move `{}` out, leaving `Default::default()` in its place.",
+            unraw
+        );
+        let clone_doc = format!("Take a clone of `{}`.", unraw);
+        let borrow_doc = format!("Take an immutable reference to `{}`.", unraw);
+        let default_doc = format!("Take `{}` the way its `#[take(mode)]` says.", unraw);
+        let take_owned_doc = format!("Move `{}` out.", unraw);
+        let default_owned_doc = format!(
+            "Move `{}` out. The struct is gone, so there is nothing to clone or borrow from.",
+            unraw
+        );
+        // The bounds are higher ranked so that they are only checked when the method is used.
+        methods.push(quote! {
+            impl<#lt, #(#params,)* #(#others),*> #pending #where_clause {
+                #[doc = #take_doc]
+                #vis fn #take_fn(self) -> #owned
+                where
+                    for<'__x> #ty: ::core::default::Default,
+                {
+                    #take
+                }
+
+                #[doc = #clone_doc]
+                #vis fn #clone_fn(self) -> #owned
+                where
+                    for<'__x> #ty: ::core::clone::Clone,
+                {
+                    #clone
+                }
+
+                #[doc = #borrow_doc]
+                #vis fn #borrow_fn(self) -> #borrowed {
+                    #borrow
+                }
+
+                #[doc = #default_doc]
+                #vis fn #field(self) -> #default_ty #default_bound {
+                    self.#default_fn()
+                }
+            }
+
+            impl<#lt, #(#params,)* #(#others),*> #untaken #where_clause {
+                #[doc = #take_owned_doc]
+                #vis fn #take_fn(self) -> #owned {
+                    #take_owned
+                }
+
+                #[doc = #default_owned_doc]
+                #vis fn #field(self) -> #owned {
+                    self.#take_fn()
+                }
+            }
+        });
+    }
+
+    let builder_doc = format!(
+        "Takes the fields of [`{0}`] one by one, created by [`{0}::take_builder`].",
+        name
+    );
+    let taken_doc = format!("The fields taken out of a [`{}`].", name);
+    let build_doc = format!(
+        "Finish taking, the fields that weren't taken are `&mut` into the `{}`, or \
+         `Untaken` if the builder owns them.",
+        name
+    );
+    let start_doc = format!(
+        "Start taking the fields of this struct by moving them out, see [`{}`].",
+        builder
+    );
+    let start_mut_doc = format!(
+        "Start taking the fields of this struct through a `&mut`, see [`{}`].",
+        builder
+    );
+    Ok(quote! {
+        #[doc = #builder_doc]
+        #vis struct #builder<#lt, #(#params,)* #(#states),*> #where_clause {
+            #(#idents: #states,)*
+            __source: ::core::marker::PhantomData<&#lt mut #name #ty_generics>,
+        }
+
+        #[doc = #taken_doc]
+        #vis struct #taken<#(#states),*> {
+            #(
+                #[allow(missing_docs)]
+                #vis #idents: #states,
+            )*
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #start_doc]
+            #vis fn take_builder<#lt>(self) -> #builder<#lt, #(#args,)* #(::taken::Untaken<#tys>),*>
+            where
+                Self: #lt,
+            {
+                let #name { #(#idents),* } = self;
+                #builder {
+                    #(#idents: ::taken::Untaken(#idents),)*
+                    __source: ::core::marker::PhantomData,
+                }
+            }
+
+            #[doc = #start_mut_doc]
+            #vis fn take_builder_mut(&mut self) -> #builder<'_, #(#args,)* #(&mut #tys),*> {
+                #builder {
+                    #(#idents: &mut self.#idents,)*
+                    __source: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        #(#methods)*
+
+        impl<#lt, #(#params,)* #(#states),*> #builder<#lt, #(#args,)* #(#states),*> #where_clause {
+            #[doc = #build_doc]
+            #vis fn build(self) -> #taken<#(#states),*> {
+                #taken { #(#idents: self.#idents),* }
+            }
+        }
+    })
+}
+
+fn expand_take_fields(item: TokenStream2) -> syn::Result<TokenStream2> {
+    let input = syn::parse2::<syn::DeriveInput>(item)?;
+    let fields = match input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(ref fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`#[derive(TakeFields)]` only supports structs with named fields",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut methods = Vec::new();
+    for field in fields {
+        if field_attrs(field)?.skip {
+            continue;
+        }
+        let (vis, ident, ty) = (&field.vis, field.ident.as_ref().unwrap(), &field.ty);
+        let take_fn = format_ident!("take_{}", ident.unraw());
+        let doc = format!(
+            "Move `{}` out, leaving `Default::default()` in its place.",
+            ident.unraw()
+        );
+        // The bound is higher ranked so that it is only checked when the method is used.
+        methods.push(quote! {
+            #[doc = #doc]
+            #vis fn #take_fn(&mut self) -> #ty
+            where
+                for<'__x> #ty: ::core::default::Default,
+            {
+                ::core::mem::take(&mut self.#ident)
+            }
+        });
+    }
+
+    // `into_fields` would give access to all fields, so it is only as visible as all of them are
+    let vis_of = |f: &syn::Field| f.vis.to_token_stream().to_string();
+    let into_vis = match fields.first() {
+        Some(first) if fields.iter().all(|f| vis_of(f) == vis_of(first)) => first.vis.clone(),
+        _ => syn::Visibility::Inherited,
+    };
+    let idents: Vec<&Ident> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let tys = fields.iter().map(|f| &f.ty);
+    let into_doc = format!(
+        "Split into the fields, in the order they are declared: `({})`.",
+        idents
+            .iter()
+            .map(|ident| ident.unraw().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#methods)*
+
+            #[doc = #into_doc]
+            #into_vis fn into_fields(self) -> (#(#tys,)*) {
+                let #name { #(#idents),* } = self;
+                (#(#idents,)*)
+            }
+        }
+    })
+}
+
+fn expand_taken(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    // `clone(a, b), freeze(c)`, in the order written
+    let lists = (|input: ParseStream| {
+        let mut entries = Vec::new();
+        while !input.is_empty() {
+            let kind = input.call(Ident::parse_any)?;
+            let sigil = match kind.to_string().as_str() {
+                "clone" => Some(<Token![=]>::default()),
+                "freeze" => None,
+                _ => {
+                    return Err(syn::Error::new(
+                        kind.span(),
+                        "expected `clone(...)` or `freeze(...)`",
+                    ))
+                }
+            };
+            let content;
+            syn::parenthesized!(content in input);
+            for name in Punctuated::<Ident, Token![,]>::parse_terminated(&content)? {
+                entries.push((sigil, name));
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(entries)
+    })
+    .parse2(attr)?;
+
+    let mut func = match syn::parse2::<syn::ItemFn>(item) {
+        Ok(func) => func,
+        Err(err) => {
+            return Err(syn::Error::new(
+                err.span(),
+                "`#[taken]` can only be applied to functions",
+            ))
+        }
+    };
+
+    let mut params = Vec::new();
+    let mut frozen = Vec::new();
+    for input in func.sig.inputs.iter_mut() {
+        let syn::FnArg::Typed(typed) = input else {
+            continue;
+        };
+        let freeze = typed.attrs.iter().position(|a| a.path().is_ident("freeze"));
+        let name = match &*typed.pat {
+            syn::Pat::Ident(pat) => Some(pat.ident.clone()),
+            _ => None,
+        };
+        if let Some(pos) = freeze {
+            let attr = typed.attrs.remove(pos);
+            match &name {
+                Some(name) => frozen.push((None, name.clone())),
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "`#[freeze]` parameters must be a single name, not a pattern",
+                    ))
+                }
+            }
+        }
+        params.extend(name);
+    }
+
+    let mut errors = Vec::new();
+    let mut seen: Vec<&Ident> = Vec::new();
+    let entries: Vec<_> = lists.into_iter().chain(frozen).collect();
+    for (_, name) in &entries {
+        if !params.contains(name) {
+            errors.push(syn::Error::new(
+                name.span(),
+                format!("`{}` is not a parameter of `{}`", name, func.sig.ident),
+            ));
+        } else if seen.contains(&name) {
+            errors.push(syn::Error::new(
+                name.span(),
+                format!("`{}` is taken more than once", name),
+            ));
+        }
+        seen.push(name);
+    }
+    if let Some(mut err) = errors.pop() {
+        for other in errors {
+            err.combine(other);
+        }
+        return Err(err);
+    }
+
+    if !entries.is_empty() {
+        let entries = entries.iter().map(|(sigil, name)| quote!(#sigil #name));
+        func.block
+            .stmts
+            .insert(0, syn::parse_quote!(::taken::take!(#(#entries),*);));
+    }
+    Ok(func.into_token_stream())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(captures: &str, item: &str) -> String {
+        expand_capture(captures.parse().unwrap(), item.parse().unwrap())
+            .unwrap_or_else(compile_error)
+            .to_string()
+    }
+
+    /// Round-trips the expected tokens through `syn` so that spacing of
+    /// punctuation (`||` vs `| |`) matches the expansion.
+    fn expected(tokens: TokenStream2) -> String {
+        syn::parse2::<Expr>(tokens)
+            .unwrap()
+            .to_token_stream()
+            .to_string()
+    }
+
+    #[test]
+    fn capture_closure() {
+        assert_eq!(
+            expand("x, =y", "move || x + y"),
+            expected(quote!({
+                ::taken::take!(x, =y);
+                move || x + y
+            })),
+        );
+        // closures are always made `move`
+        assert_eq!(
+            expand("&mut z", "|a: u32| -> u32 { *z += a; *z }"),
+            expected(quote!({
+                ::taken::take!(&mut z);
+                move |a: u32| -> u32 {
+                    *z += a;
+                    *z
+                }
+            })),
+        );
+    }
+
+    #[test]
+    fn capture_async() {
+        assert_eq!(
+            expand("=db", "async || db.len()"),
+            expected(quote!({
+                ::taken::take!(=db);
+                async move || db.len()
+            })),
+        );
+        assert_eq!(
+            expand("=db", "async { db.len() }"),
+            expected(quote!({
+                ::taken::take!(=db);
+                async move { db.len() }
+            })),
+        );
+    }
+
+    #[test]
+    fn capture_not_a_closure() {
+        assert!(expand("x", "x + 1").contains("can only be applied to closures"));
+    }
+
+    fn expand_fn(captures: &str, item: &str) -> String {
+        expand_captures(captures.parse().unwrap(), item.parse().unwrap())
+            .unwrap_or_else(compile_error)
+            .to_string()
+    }
+
+    #[test]
+    fn captures_function() {
+        let expected = quote! {
+            fn f(x: u32, y: String, z: u32) -> impl Fn() -> usize {
+                {
+                    ::taken::take!(x as x, =y as name, hold z as z);
+                    move || x as usize + name.len()
+                }
+            }
+        };
+        assert_eq!(
+            expand_fn(
+                "x, =y as name, hold z",
+                "fn f(x: u32, y: String, z: u32) -> impl Fn() -> usize { || x as usize + name.len() }",
+            ),
+            syn::parse2::<syn::ItemFn>(expected).unwrap().to_token_stream().to_string(),
+        );
+        assert!(expand_fn("x", "fn f(x: u32) -> u32 { x }").contains("requires a closure"));
+        assert!(expand_fn("x", "fn f(x: u32) { drop(x); }").contains("single closure"));
+    }
+
+    #[test]
+    fn captures_let() {
+        let expected = quote!(let f = {
+            ::taken::take!(&x as x, drop y);
+            move || x.len()
+        };);
+        assert_eq!(
+            expand_fn("&x, drop y", "let f = || x.len();"),
+            syn::parse2::<syn::Stmt>(expected)
+                .unwrap()
+                .to_token_stream()
+                .to_string(),
+        );
+        assert!(expand_fn("x", "let f = x;").contains("requires a closure"));
+    }
+
+    #[test]
+    fn captures_errors() {
+        let err = expand_fn("x, &x, y as x", "let f = || x;");
+        assert_eq!(err.matches("`x` is captured more than once").count(), 2);
+        let err = expand_fn("x, =y", "let f = || x;");
+        assert!(err.contains("`y` is captured but never used"));
+        // names used inside of macros count as used
+        assert!(!expand_fn("y", "let f = || println!(\"{}\", y);").contains("never used"));
+        assert!(expand_fn("x <- 1", "let f = || x;").contains("must end with the name"));
+    }
+
+    fn expand_derive(item: &str) -> String {
+        expand_take_builder(item.parse().unwrap())
+            .unwrap_or_else(item_compile_error)
+            .to_string()
+    }
+
+    #[test]
+    fn take_builder_items() {
+        let expanded = expand_derive(
+            "pub struct Ctx<'a, T = u32> {
+                name: String,
+                #[take(mode = \"borrow\")]
+                r#type: &'a T,
+            }",
+        );
+        for item in [
+            "pub struct CtxTakeBuilder",
+            "pub struct CtxTaken < __Name , __Type >",
+            "pub fn take_builder < '__taken > (self)",
+            "pub fn take_builder_mut (& mut self)",
+            "let Ctx { name , r#type } = self ;",
+            "impl < '__taken , 'a , T , __Type > CtxTakeBuilder < '__taken , 'a , T , :: taken :: Untaken < String > , __Type >",
+            "pub fn take_name",
+            "pub fn clone_type",
+            "pub fn r#type (self) -> \
+             CtxTakeBuilder < '__taken , 'a , T , __Name , & '__taken & 'a T >",
+            "self . borrow_type ()",
+            "pub fn build",
+        ] {
+            assert!(
+                expanded.contains(item),
+                "missing `{}` in {}",
+                item,
+                expanded
+            );
+        }
+    }
+
+    #[test]
+    fn take_builder_errors() {
+        let named = "only supports structs with named fields";
+        assert!(expand_derive("struct Ctx(u32);").contains(named));
+        assert!(expand_derive("enum Ctx { A }").contains(named));
+        assert!(
+            expand_derive("struct Ctx { #[take(mode = \"move\")] a: u32 }")
+                .contains("expected `\\\"take\\\"`")
+        );
+        assert!(expand_derive("struct Ctx { #[take(clone)] a: u32 }").contains("expected `mode"));
+    }
+
+    fn expand_taken_str(attr: &str, item: &str) -> String {
+        expand_taken(attr.parse().unwrap(), item.parse().unwrap())
+            .unwrap_or_else(item_compile_error)
+            .to_string()
+    }
+
+    #[test]
+    fn taken_function() {
+        let expected = quote! {
+            #[inline]
+            pub async unsafe extern "C" fn f<T: Clone>(mut a: u32, b: &T, c: T) -> T
+            where
+                T: Send
+            {
+                ::taken::take!(=b, =c, a);
+                let d = a + 1;
+                c
+            }
+        };
+        assert_eq!(
+            expand_taken_str(
+                "clone(b, c)",
+                "#[inline] pub async unsafe extern \"C\" fn f<T: Clone>(#[freeze] mut a: u32, \
+                 b: &T, c: T) -> T where T: Send { let d = a + 1; c }",
+            ),
+            syn::parse2::<syn::ItemFn>(expected)
+                .unwrap()
+                .to_token_stream()
+                .to_string(),
+        );
+        // the attribute's order is kept, nothing is inserted without entries
+        assert!(
+            expand_taken_str("freeze(b), clone(a)", "fn f(a: u32, b: u32) {}")
+                .contains("take ! (b , = a)")
+        );
+        assert!(!expand_taken_str("", "fn f(a: u32) {}").contains("take"));
+    }
+
+    #[test]
+    fn taken_errors() {
+        assert!(expand_taken_str("clone(x)", "fn f(a: u32) {}")
+            .contains("`x` is not a parameter of `f`"));
+        assert!(expand_taken_str("freeze(a)", "fn f(#[freeze] a: u32) {}")
+            .contains("`a` is taken more than once"));
+        assert!(expand_taken_str("move(a)", "fn f(a: u32) {}").contains("expected `clone"));
+        assert!(
+            expand_taken_str("", "fn f(#[freeze] (a, b): (u32, u32)) {}")
+                .contains("must be a single name")
+        );
+        assert!(expand_taken_str("", "struct S;").contains("only be applied to functions"));
+    }
+
+    fn expand_fields(item: &str) -> String {
+        expand_take_fields(item.parse().unwrap())
+            .unwrap_or_else(item_compile_error)
+            .to_string()
+    }
+
+    #[test]
+    fn take_fields_items() {
+        let expanded = expand_fields(
+            "pub struct Ctx<'a> {
+                pub name: String,
+                #[take(skip)]
+                pub r#type: &'a u32,
+            }",
+        );
+        for item in [
+            "pub fn take_name (& mut self) -> String",
+            "pub fn into_fields (self) -> (String , & 'a u32 ,)",
+            "let Ctx { name , r#type } = self",
+        ] {
+            assert!(
+                expanded.contains(item),
+                "missing `{}` in {}",
+                item,
+                expanded
+            );
+        }
+        assert!(!expanded.contains("take_type"));
+        // `into_fields` is private when the fields' visibilities differ
+        let expanded = expand_fields("pub struct Ctx { pub a: u8, b: u8 }");
+        assert!(expanded.contains("pub fn take_a") && expanded.contains(" fn take_b"));
+        assert!(expanded.contains("] fn into_fields"));
+    }
+
+    #[test]
+    fn take_fields_errors() {
+        let named = "only supports structs with named fields";
+        assert!(expand_fields("struct Ctx(u32);").contains(named));
+        assert!(expand_fields("struct Ctx { #[take(move)] a: u32 }").contains("or `skip`"));
+    }
+
+    fn expand_take_fn_str(input: &str) -> String {
+        expand_take_fn(input.parse().unwrap())
+            .unwrap_or_else(compile_error)
+            .to_string()
+    }
+
+    #[test]
+    fn take_fn_expansion() {
+        let expanded = expand_take_fn_str(
+            "(&job: Job, =tx as sender: Sender<u32>, mut n: u32, &mut log: Vec<u32>, drop x: u8) \
+             |attempt: u32, (a, b): (u8, u8)| -> bool { job.run(attempt) }",
+        );
+        for part in [
+            "take ! (& job as job , = tx as sender , mut n as n , & mut log as log , drop x)",
+            "fn __take_fn (job : & Job , sender : & Sender < u32 > , n : & mut u32 , \
+             log : & mut Vec < u32 > , attempt : u32 , (a , b) : (u8 , u8)) -> bool",
+            "move | __arg0 : u32 , __arg1 : (u8 , u8) | __take_fn (job , & sender , & mut n , \
+             & mut * log , __arg0 , __arg1)",
+        ] {
+            assert!(
+                expanded.contains(part),
+                "missing `{}` in {}",
+                part,
+                expanded
+            );
+        }
+    }
+
+    #[test]
+    fn take_fn_errors() {
+        assert!(expand_take_fn_str("(x) || x").contains("need the type of the value"));
+        assert!(expand_take_fn_str("(x: u32) |y| x + y").contains("arguments need a type"));
+        assert!(expand_take_fn_str("(x: u32) async || x").contains("async closures"));
+        assert!(expand_take_fn_str("(x <- 1: u32) || 1").contains("must end with the name"));
+    }
+
+    fn expand_take_all_fields_str(input: &str) -> String {
+        expand_take_all_fields(input.parse().unwrap())
+            .unwrap_or_else(item_compile_error)
+            .to_string()
+    }
+
+    #[test]
+    fn take_all_fields_expansion() {
+        let expanded = expand_take_all_fields_str("cfg => { a, =mut b, &c as d, ref mut e, *f }");
+        for part in [
+            "let a = (cfg) . a ;",
+            "let mut b = :: taken :: __take_clone ! ((cfg) . b) ;",
+            "let d = & (cfg) . c ;",
+            "let e = & mut (cfg) . e ;",
+            "let f = * (cfg) . f ;",
+            ":: taken :: __take_trace ! (\"=mut\" , b) ;",
+        ] {
+            assert!(
+                expanded.contains(part),
+                "missing `{}` in {}",
+                part,
+                expanded
+            );
+        }
+        // anything but a place is evaluated once
+        let expanded = expand_take_all_fields_str("make() => { a, b }");
+        assert!(expanded.starts_with("let __taken_fields = make () ;"));
+        assert!(expanded.contains("let b = __taken_fields . b ;"));
+    }
+
+    #[test]
+    fn take_all_fields_errors() {
+        assert!(expand_take_all_fields_str("cfg => { drop a }").contains("found `drop a`"));
+        assert!(expand_take_all_fields_str("cfg => { a? }").contains("must end with the name"));
+        assert!(expand_take_all_fields_str("cfg => { a <- 1 }").contains("must end with the name"));
+    }
+}
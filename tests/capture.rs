@@ -0,0 +1,13 @@
+//! `#[capture(...)]` is applied to expressions and `#[captures(...)]` to statements, which requires
+//! a nightly compiler. Run these with `cargo +nightly test --features macros -- --ignored`.
+#![cfg(feature = "macros")]
+
+extern crate trybuild;
+
+#[test]
+#[ignore]
+fn capture() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/capture/pass/*.rs");
+    t.compile_fail("tests/capture/fail/*.rs");
+}
@@ -0,0 +1,12 @@
+#![feature(proc_macro_hygiene)]
+
+extern crate taken;
+
+use taken::captures;
+
+fn main() {
+    let (listed, secret) = (1, 2);
+    #[captures(listed)]
+    let closure = || listed + secret;
+    closure();
+}
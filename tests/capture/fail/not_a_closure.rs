@@ -0,0 +1,11 @@
+#![feature(stmt_expr_attributes, proc_macro_hygiene)]
+
+extern crate taken;
+
+use taken::capture;
+
+fn main() {
+    let value = 1;
+    let doubled = #[capture(value)] (value * 2);
+    assert_eq!(doubled, 2);
+}
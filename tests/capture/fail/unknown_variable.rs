@@ -0,0 +1,11 @@
+#![feature(stmt_expr_attributes, proc_macro_hygiene)]
+
+extern crate taken;
+
+use taken::capture;
+
+fn main() {
+    let value = 1;
+    let closure = #[capture(valeu)] move || value + 1;
+    closure();
+}
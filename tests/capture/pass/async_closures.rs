@@ -0,0 +1,28 @@
+#![feature(stmt_expr_attributes, proc_macro_hygiene)]
+
+extern crate taken;
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+use taken::capture;
+
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
+
+fn main() {
+    let db = vec![1, 2, 3];
+    let query = #[capture(=db)] async move || db.len();
+    assert_eq!(block_on(query()), 3);
+
+    let task = #[capture(&db)] async { db.iter().sum::<i32>() };
+    assert_eq!(block_on(task), 6);
+    assert_eq!(db.len(), 3);
+}
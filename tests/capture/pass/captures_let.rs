@@ -0,0 +1,16 @@
+#![feature(proc_macro_hygiene)]
+
+extern crate taken;
+
+use std::sync::mpsc::channel;
+use taken::captures;
+
+fn main() {
+    let (tx, rx) = channel();
+    let (metrics, unrelated) = (vec![1, 2], 3);
+    #[captures(tx, &metrics as m)]
+    let handler = move |n: usize| tx.send(n + m.len()).unwrap();
+    handler(1);
+    assert_eq!(rx.recv().unwrap(), 3);
+    assert_eq!((metrics.len(), unrelated), (2, 3));
+}
@@ -0,0 +1,33 @@
+#![feature(stmt_expr_attributes, proc_macro_hygiene)]
+
+extern crate taken;
+
+use std::thread::spawn;
+use taken::capture;
+
+fn apply<F: FnOnce(u32) -> u32>(f: F) -> u32 {
+    f(1)
+}
+
+fn main() {
+    let (x, y, mut z) = (vec![1], String::from("y"), 0);
+    let mut closure = #[capture(x, =y, &mut z)]
+    move || {
+        *z += x.len() + y.len();
+    };
+    closure();
+    assert_eq!(z, 2);
+    assert_eq!(y, "y");
+
+    // with an explicit return type, passed as an argument
+    let offset = 10;
+    let out = apply(#[capture(offset)] |a: u32| -> u32 { a + offset });
+    assert_eq!(out, 11);
+
+    // the attribute also works inside of other macros
+    let name = String::from("thread");
+    let th = spawn(#[capture(=name as for_thread)] || for_thread.len());
+    assert_eq!(th.join().unwrap(), 6);
+    assert_eq!(name, "thread");
+    assert_eq!(vec![#[capture(&name)] || name.len()][0](), 6);
+}
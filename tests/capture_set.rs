@@ -0,0 +1,28 @@
+//! `capture_set!` lists spliced into `take!` with `use`.
+#![cfg(feature = "macros")]
+
+#[macro_use]
+extern crate taken;
+extern crate trybuild;
+
+// a set defined at module level is usable by every function after it
+capture_set! {
+    handles = (=name, &mut count);
+}
+
+fn bump(name: String, mut count: usize) -> (String, usize) {
+    take!(use handles; =name as copy);
+    *count += copy.len();
+    (name, *count)
+}
+
+#[test]
+fn module_level_set() {
+    assert_eq!(bump(String::from("abc"), 1), (String::from("abc"), 4));
+}
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/capture_set/*.rs");
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let model = vec![1];
+    take!(use vec; model);
+}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (model, assets) = (vec![1], String::from("icons"));
+    capture_set! {
+        ui_deps = (=model, &assets);
+    }
+    take!(use ui_dep; model);
+}
@@ -0,0 +1,12 @@
+//! `#[captures(...)]` on functions works on a stable compiler. The `let` statement form is tested
+//! with the other nightly only attributes in `tests/capture.rs`.
+#![cfg(feature = "macros")]
+
+extern crate trybuild;
+
+#[test]
+fn captures() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/captures/pass/*.rs");
+    t.compile_fail("tests/captures/fail/*.rs");
+}
@@ -0,0 +1,12 @@
+extern crate taken;
+
+use taken::captures;
+
+#[captures(a, &a, b as a)]
+fn make(a: u32, b: u32) -> impl Fn() -> u32 {
+    move || a
+}
+
+fn main() {
+    make(1, 2)();
+}
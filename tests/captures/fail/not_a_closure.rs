@@ -0,0 +1,13 @@
+extern crate taken;
+
+use taken::captures;
+
+#[captures(a)]
+fn make(a: u32) -> u32 {
+    let b = a;
+    b
+}
+
+fn main() {
+    make(1);
+}
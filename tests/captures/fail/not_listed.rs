@@ -0,0 +1,12 @@
+extern crate taken;
+
+use taken::captures;
+
+#[captures(a)]
+fn make(a: u32, b: u32) -> impl Fn() -> u32 {
+    move || a + b
+}
+
+fn main() {
+    make(1, 2)();
+}
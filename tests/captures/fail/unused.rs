@@ -0,0 +1,12 @@
+extern crate taken;
+
+use taken::captures;
+
+#[captures(a, =b)]
+fn make(a: u32, b: String) -> impl Fn() -> u32 {
+    move || a
+}
+
+fn main() {
+    make(1, String::new())();
+}
@@ -0,0 +1,43 @@
+extern crate taken;
+
+use std::sync::mpsc::{channel, Sender};
+use taken::captures;
+
+const FACTOR: u32 = 10;
+
+struct Config {
+    offset: u32,
+}
+
+fn scale(n: u32) -> u32 {
+    n * FACTOR
+}
+
+#[captures(tx, =offset as base, names, hold guard)]
+fn handler(
+    tx: Sender<u32>,
+    offset: u32,
+    names: &'static [&'static str],
+    guard: (),
+    _debug: bool,
+) -> impl FnOnce(u32) {
+    move |n| {
+        // items are still visible, and so are the closure's own bindings
+        let config = Config { offset: base };
+        let total = scale(n) + config.offset + names.len() as u32;
+        println!("{total}");
+        tx.send(total).unwrap();
+    }
+}
+
+#[captures(=prefix)]
+fn greeter(prefix: &str) -> impl Fn(&str) -> String + '_ {
+    |name| format!("{}{}", prefix, name)
+}
+
+fn main() {
+    let (tx, rx) = channel();
+    handler(tx, 1, &["a", "b"], (), true)(2);
+    assert_eq!(rx.recv().unwrap(), 23);
+    assert_eq!(greeter("hi ")("you"), "hi you");
+}
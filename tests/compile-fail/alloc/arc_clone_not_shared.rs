@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let a = vec![1];
+    take!(arc_clone a);
+}
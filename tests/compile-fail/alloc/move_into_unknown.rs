@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let x = 1;
+    move_into!(x: Cell<_>);
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let a = String::from("a");
+    take!(rc_clone a);
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let ids = [1, 2, 3];
+    take!(shrink ids);
+}
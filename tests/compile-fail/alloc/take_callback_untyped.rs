@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let a = 1;
+    let _ = take_callback!(a; |x| x + a);
+}
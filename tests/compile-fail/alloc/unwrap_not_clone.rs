@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate taken;
+
+use std::sync::Arc;
+
+struct Handle;
+
+fn main() {
+    let (handle, name) = (Arc::new(Handle), String::from("name"));
+    take!(unwrap handle);
+    take!(unwrap name);
+}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate taken;
+
+use std::rc::Rc;
+
+fn main() {
+    let handle = Rc::new(1);
+    take_arc_weak!(handle as weak);
+    take_rc_weak!(handle);
+}
@@ -0,0 +1,8 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (s, x) = (String::from("s"), vec![1u8]);
+    take_and_assert!(&s as r: &str, =x: Vec<i32>);
+    take_and_assert!(x);
+}
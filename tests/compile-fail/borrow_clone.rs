@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (config, db) = (1, String::new());
+    borrow!(config, =db);
+}
@@ -0,0 +1,9 @@
+#[macro_use]
+extern crate taken;
+
+use std::rc::Rc;
+
+fn main() {
+    let (a, b) = (1, Rc::new(2));
+    bound_check!(a: Send + Sync, b: Clone + Send);
+}
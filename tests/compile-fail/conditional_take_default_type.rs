@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (name, verbose) = (String::from("liam"), true);
+    conditional_take!(name if verbose else None);
+}
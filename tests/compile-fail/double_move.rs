@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (x, y, z) = (vec![1], 2, 3);
+    take!(x as a, =x as b, mut x as c);
+    take!(&z as d, z as e, #[cfg(any())] z as f, z as g);
+    take_disjoint!(x as h, &y as i, mut x as j);
+    take_disjoint!(y as k, =y as l);
+}
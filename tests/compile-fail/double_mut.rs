@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let mut x = 1;
+    take!(&mut mut x);
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let point = (1, 2);
+    take!(=point.0);
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let tx = std::sync::mpsc::channel::<u32>().0;
+    take!(=tx as []);
+}
@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate taken;
+
+struct Config {
+    host: String,
+    port: u16,
+}
+
+fn main() {
+    let cfg = Config { host: "db".into(), port: 1 };
+    take!(cfg.{=host});
+    println!("{}", cfg.port);
+}
@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate taken;
+
+struct Config {
+    host: String,
+    port: u16,
+    token: String,
+}
+
+fn main() {
+    let cfg = Config { host: "db".into(), port: 1, token: "t".into() };
+    take!(cfg.{=host, token, mut port, ..});
+}
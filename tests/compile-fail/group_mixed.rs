@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (a, b) = (1, 2);
+    take!(&(a, =b));
+}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let v = vec![1];
+    mut_scope!(v {
+        v.push(2);
+    });
+    v.push(3);
+}
@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (database, label) = (vec![1], String::from("job"));
+    let report = {
+        take!(=label, not database);
+        move || label.len() + database.len()
+    };
+    report();
+}
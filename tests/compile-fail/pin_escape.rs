@@ -0,0 +1,14 @@
+#[macro_use]
+extern crate taken;
+
+use std::future::Future;
+use std::pin::Pin;
+
+fn pinned<F: Future>(fut: F) -> Pin<&'static mut F> {
+    take!(pin fut);
+    fut
+}
+
+fn main() {
+    pinned(async {});
+}
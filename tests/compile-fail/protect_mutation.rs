@@ -0,0 +1,9 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let mut config = vec![1, 2];
+    protect!(config {
+        config.push(3);
+    });
+}
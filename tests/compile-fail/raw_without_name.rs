@@ -0,0 +1,8 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (ctx, mut state) = (1, 2);
+    take!(&raw const ctx);
+    take!(&raw mut state, &ctx as c);
+}
@@ -0,0 +1,8 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (a, b) = (1, 2);
+    renaming_take!(=a as a2, &b);
+    let _ = (a, b);
+}
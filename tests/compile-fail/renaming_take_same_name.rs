@@ -0,0 +1,8 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (a, b) = (String::new(), 2);
+    renaming_take!(&b as c, =a as a);
+    let _ = (a, b, c);
+}
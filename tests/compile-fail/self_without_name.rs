@@ -0,0 +1,17 @@
+#[macro_use]
+extern crate taken;
+
+#[derive(Clone)]
+struct Job;
+
+impl Job {
+    fn run(&self) {
+        take!(=self);
+    }
+
+    fn run_many(&self) {
+        take!(&self as this, =mut self, self);
+    }
+}
+
+fn main() {}
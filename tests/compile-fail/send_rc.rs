@@ -0,0 +1,15 @@
+#[macro_use]
+extern crate taken;
+
+use std::rc::Rc;
+use std::thread;
+
+#[allow(deprecated)]
+fn main() {
+    let (job, shared) = (vec![1], Rc::new(2));
+    let th = thread::spawn({
+        take!(send job, send =shared as s);
+        move || job.len() + *s
+    });
+    th.join().unwrap();
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let x = vec![1];
+    snapshot!(x);
+}
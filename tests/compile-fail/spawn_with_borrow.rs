@@ -0,0 +1,14 @@
+#[macro_use]
+extern crate taken;
+
+use std::future::Future;
+
+fn spawn<F: Future + Send + 'static>(fut: F) -> F {
+    fut
+}
+
+fn main() {
+    let config = String::from("verbose");
+    let task = spawn_with!(spawn, (&config) async { config.len() });
+    drop(task);
+}
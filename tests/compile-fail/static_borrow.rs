@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let local = String::from("local");
+    let th = std::thread::spawn({
+        take!(static &local);
+        move || local.len()
+    });
+    th.join().unwrap();
+}
@@ -0,0 +1,8 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (tx, _rx) = std::sync::mpsc::channel::<u32>();
+    take_sender!(tx);
+    let _ = tx.clone();
+}
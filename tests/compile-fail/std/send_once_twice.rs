@@ -0,0 +1,9 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (tx, _rx) = std::sync::mpsc::channel();
+    take_sender!(=tx as done);
+    done.send(1).unwrap();
+    done.send(2).unwrap();
+}
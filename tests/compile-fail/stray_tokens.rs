@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (x, y) = (1, 2);
+    take!(x y);
+}
@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate taken;
+
+use std::cell::RefCell;
+use std::sync::Arc;
+
+fn main() {
+    let state = Arc::new(RefCell::new(0));
+    take!(sync &state);
+    let _ = state;
+}
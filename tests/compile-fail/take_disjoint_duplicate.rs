@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (x, y) = (String::from("x"), 2);
+    take_disjoint!(=x as a, y, &x as b);
+}
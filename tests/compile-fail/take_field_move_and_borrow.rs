@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate taken;
+
+struct Job {
+    name: String,
+    tags: Vec<String>,
+    id: u32,
+}
+
+fn main() {
+    let job = Job { name: String::new(), tags: Vec::new(), id: 1 };
+    take_field!(job => name, &tags, =id);
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (a, b) = (String::new(), vec![1]);
+    let _ = take_into_array!([a, =b]);
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    take_iter!(0..3 => a, ..rest, b);
+    take_iter!(0..3 => ..rest as tail, b);
+}
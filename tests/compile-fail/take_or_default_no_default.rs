@@ -0,0 +1,9 @@
+#[macro_use]
+extern crate taken;
+
+struct NoDefault;
+
+fn main() {
+    let x: Option<NoDefault> = None;
+    take_or_default!(x);
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let pair = (1, (2, 3));
+    take_path!(pair.1.0);
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let pair = (Box::new(1), 2);
+    take_tuple!((*boxed, n) = pair);
+}
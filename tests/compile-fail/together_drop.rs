@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let (a, b) = (String::new(), 1);
+    take!(together: a, drop b);
+}
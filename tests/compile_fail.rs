@@ -0,0 +1,11 @@
+extern crate trybuild;
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile-fail/*.rs");
+    #[cfg(feature = "alloc")]
+    t.compile_fail("tests/compile-fail/alloc/*.rs");
+    #[cfg(feature = "std")]
+    t.compile_fail("tests/compile-fail/std/*.rs");
+}
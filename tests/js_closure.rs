@@ -0,0 +1,66 @@
+//! `js_closure!` against a stand-in for `wasm_bindgen`, so that the captures and the closure types
+//! are checked without the wasm32 target.
+#![cfg(feature = "wasm")]
+
+#[macro_use]
+extern crate taken;
+
+// `::wasm_bindgen` in the expansion resolves to this crate
+extern crate self as wasm_bindgen;
+
+pub mod closure {
+    pub struct Closure<T: ?Sized>(pub Box<T>);
+
+    impl<T: ?Sized> Closure<T> {
+        pub fn wrap(f: Box<T>) -> Self {
+            Closure(f)
+        }
+    }
+
+    impl<A> Closure<dyn FnMut(A)> {
+        pub fn once<F: FnOnce(A) + 'static>(f: F) -> Self {
+            let mut f = Some(f);
+            Closure(Box::new(move |a| (f.take().expect("called twice"))(a)))
+        }
+    }
+}
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+
+struct Event(&'static str);
+
+#[test]
+fn fn_mut_takes_the_entries() {
+    let (clicks, label) = (Rc::new(RefCell::new(vec![])), String::from("button"));
+    let mut on_click: Closure<dyn FnMut(Event)> = js_closure!((rc_clone clicks, =label) |event: Event| {
+        clicks.borrow_mut().push(format!("{} {}", label, event.0));
+    });
+    (on_click.0)(Event("click"));
+    (on_click.0)(Event("dblclick"));
+    assert_eq!(*clicks.borrow(), vec!["button click", "button dblclick"]);
+    assert_eq!(label, "button"); // only a clone was moved
+}
+
+#[test]
+fn return_types_and_no_arguments() {
+    let count = Rc::new(RefCell::new(0));
+    let mut add: Closure<dyn FnMut(u32, u32) -> u32> =
+        js_closure!((rc_clone count) |a: u32, b: u32| -> u32 { *count.borrow_mut() += 1; a + b });
+    let mut tick: Closure<dyn FnMut()> = js_closure!((rc_clone count as c) || { *c.borrow_mut() += 10 });
+    assert_eq!((add.0)(1, 2), 3);
+    (tick.0)();
+    assert_eq!(*count.borrow(), 11);
+}
+
+#[test]
+fn once() {
+    let (seen, token) = (Rc::new(RefCell::new(None)), String::from("token"));
+    let mut on_load = js_closure!((rc_clone seen, token) once |event: Event| {
+        *seen.borrow_mut() = Some(format!("{} {}", token, event.0));
+    });
+    (on_load.0)(Event("load"));
+    assert_eq!(seen.borrow().as_deref(), Some("token load"));
+}
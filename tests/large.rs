@@ -0,0 +1,98 @@
+//! `take!` expands lists whose entries don't contain expressions in a single pass, so that long
+//! lists stay well below the default `recursion_limit` of 128.
+#[macro_use]
+extern crate taken;
+
+macro_rules! many {
+    ($($a:ident $b:ident)*) => {{
+        $(let (mut $a, $b) = (0, vec![1]);)*
+        take!($(&mut $a, =$b,)*);
+        $(*$a += $b.len();)*
+        take!($(*$a, mut $b,)*);
+        0 $(+ $a + $b.len())*
+    }};
+}
+
+#[test]
+fn four_hundred_entries() {
+    let total = many!(
+        v0 v1 v2 v3 v4 v5 v6 v7 v8 v9 v10 v11 v12 v13 v14 v15 v16 v17 v18 v19 v20 v21 v22 v23 v24
+        v25 v26 v27 v28 v29 v30 v31 v32 v33 v34 v35 v36 v37 v38 v39 v40 v41 v42 v43 v44 v45 v46 v47
+        v48 v49 v50 v51 v52 v53 v54 v55 v56 v57 v58 v59 v60 v61 v62 v63 v64 v65 v66 v67 v68 v69 v70
+        v71 v72 v73 v74 v75 v76 v77 v78 v79 v80 v81 v82 v83 v84 v85 v86 v87 v88 v89 v90 v91 v92 v93
+        v94 v95 v96 v97 v98 v99 v100 v101 v102 v103 v104 v105 v106 v107 v108 v109 v110 v111 v112
+        v113 v114 v115 v116 v117 v118 v119 v120 v121 v122 v123 v124 v125 v126 v127 v128 v129 v130
+        v131 v132 v133 v134 v135 v136 v137 v138 v139 v140 v141 v142 v143 v144 v145 v146 v147 v148
+        v149 v150 v151 v152 v153 v154 v155 v156 v157 v158 v159 v160 v161 v162 v163 v164 v165 v166
+        v167 v168 v169 v170 v171 v172 v173 v174 v175 v176 v177 v178 v179 v180 v181 v182 v183 v184
+        v185 v186 v187 v188 v189 v190 v191 v192 v193 v194 v195 v196 v197 v198 v199 v200 v201 v202
+        v203 v204 v205 v206 v207 v208 v209 v210 v211 v212 v213 v214 v215 v216 v217 v218 v219 v220
+        v221 v222 v223 v224 v225 v226 v227 v228 v229 v230 v231 v232 v233 v234 v235 v236 v237 v238
+        v239 v240 v241 v242 v243 v244 v245 v246 v247 v248 v249 v250 v251 v252 v253 v254 v255 v256
+        v257 v258 v259 v260 v261 v262 v263 v264 v265 v266 v267 v268 v269 v270 v271 v272 v273 v274
+        v275 v276 v277 v278 v279 v280 v281 v282 v283 v284 v285 v286 v287 v288 v289 v290 v291 v292
+        v293 v294 v295 v296 v297 v298 v299 v300 v301 v302 v303 v304 v305 v306 v307 v308 v309 v310
+        v311 v312 v313 v314 v315 v316 v317 v318 v319 v320 v321 v322 v323 v324 v325 v326 v327 v328
+        v329 v330 v331 v332 v333 v334 v335 v336 v337 v338 v339 v340 v341 v342 v343 v344 v345 v346
+        v347 v348 v349 v350 v351 v352 v353 v354 v355 v356 v357 v358 v359 v360 v361 v362 v363 v364
+        v365 v366 v367 v368 v369 v370 v371 v372 v373 v374 v375 v376 v377 v378 v379 v380 v381 v382
+        v383 v384 v385 v386 v387 v388 v389 v390 v391 v392 v393 v394 v395 v396 v397 v398 v399
+    );
+    assert_eq!(total, 400);
+}
+
+struct Point {
+    x: usize,
+    y: String,
+}
+
+macro_rules! mixed {
+    ($($a:ident $b:ident $c:ident $d:ident $e:ident $f:ident $p:ident $t:ident)*) => {{
+        fn run() -> Option<usize> {
+            $(
+                let ($a, $b, $c, $d) = (Box::new(1), String::from("bb"), vec![1u8, 2], "3");
+                let ($e, $f) = (Some(4), Some(5));
+                let ($p, $t) = (Point { x: 6, y: String::from("y") }, (7, String::from("t")));
+            )*
+            take!($(
+                *$a,
+                &*$b: &str,
+                =$c as [first, second],
+                $c.len() as len,
+                $d.parse::<usize>() as parsed,
+                $e?,
+                !$f,
+                $p.{x as px, =y as py},
+                ..$t as (t0, =t1),
+            )*);
+            assert_eq!((first, second, len, parsed, px, py, t0, t1), (vec![1, 2], vec![1, 2], 2, Ok(3), 6, String::from("y"), 7, String::from("t")));
+            Some(0 $(+ $a + $b.len() + $e + $f)*)
+        }
+        run()
+    }};
+}
+
+#[test]
+fn three_hundred_and_sixty_mixed_entries() {
+    let total = mixed!(
+        a0 b0 c0 d0 e0 f0 p0 t0 a1 b1 c1 d1 e1 f1 p1 t1 a2 b2 c2 d2 e2 f2 p2 t2
+        a3 b3 c3 d3 e3 f3 p3 t3 a4 b4 c4 d4 e4 f4 p4 t4 a5 b5 c5 d5 e5 f5 p5 t5
+        a6 b6 c6 d6 e6 f6 p6 t6 a7 b7 c7 d7 e7 f7 p7 t7 a8 b8 c8 d8 e8 f8 p8 t8
+        a9 b9 c9 d9 e9 f9 p9 t9 a10 b10 c10 d10 e10 f10 p10 t10 a11 b11 c11 d11 e11 f11 p11 t11
+        a12 b12 c12 d12 e12 f12 p12 t12 a13 b13 c13 d13 e13 f13 p13 t13
+        a14 b14 c14 d14 e14 f14 p14 t14 a15 b15 c15 d15 e15 f15 p15 t15
+        a16 b16 c16 d16 e16 f16 p16 t16 a17 b17 c17 d17 e17 f17 p17 t17
+        a18 b18 c18 d18 e18 f18 p18 t18 a19 b19 c19 d19 e19 f19 p19 t19
+        a20 b20 c20 d20 e20 f20 p20 t20 a21 b21 c21 d21 e21 f21 p21 t21
+        a22 b22 c22 d22 e22 f22 p22 t22 a23 b23 c23 d23 e23 f23 p23 t23
+        a24 b24 c24 d24 e24 f24 p24 t24 a25 b25 c25 d25 e25 f25 p25 t25
+        a26 b26 c26 d26 e26 f26 p26 t26 a27 b27 c27 d27 e27 f27 p27 t27
+        a28 b28 c28 d28 e28 f28 p28 t28 a29 b29 c29 d29 e29 f29 p29 t29
+        a30 b30 c30 d30 e30 f30 p30 t30 a31 b31 c31 d31 e31 f31 p31 t31
+        a32 b32 c32 d32 e32 f32 p32 t32 a33 b33 c33 d33 e33 f33 p33 t33
+        a34 b34 c34 d34 e34 f34 p34 t34 a35 b35 c35 d35 e35 f35 p35 t35
+        a36 b36 c36 d36 e36 f36 p36 t36 a37 b37 c37 d37 e37 f37 p37 t37
+        a38 b38 c38 d38 e38 f38 p38 t38 a39 b39 c39 d39 e39 f39 p39 t39
+    );
+    assert_eq!(total, Some(40 * (1 + 2 + 4 + 5)));
+}
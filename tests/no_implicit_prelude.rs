@@ -0,0 +1,162 @@
+//! The expansions only use absolute paths, so they work without the prelude and can't pick up
+//! look-alikes from the calling scope, such as an inherent `clone` method.
+#![cfg(feature = "std")]
+
+#[macro_use]
+extern crate taken;
+
+// Nothing but the macros is imported, so that the expansions can't lean on the test's imports.
+#[no_implicit_prelude]
+mod sigils {
+    use ::taken::{
+        conditional_take, own, send_value, take, take_arc_clone, take_deref_clone, take_iter_array,
+        take_or_default, take_tuple, try_take, unbox_clone,
+    };
+
+    use ::std::{assert, assert_eq, vec};
+
+    type Box<T> = ::std::boxed::Box<T>;
+    type Arc<T> = ::std::sync::Arc<T>;
+    type Option<T> = ::std::option::Option<T>;
+    type Result<T> = ::std::result::Result<T, ()>;
+
+    #[derive(Debug, PartialEq)]
+    struct Doc(u32);
+
+    impl ::std::clone::Clone for Doc {
+        fn clone(&self) -> Self {
+            Doc(self.0 + 1)
+        }
+    }
+
+    impl Doc {
+        // never called by `take!`
+        #[allow(dead_code)]
+        fn clone(&self) -> Self {
+            ::std::panic!("the inherent `clone` was called")
+        }
+    }
+
+    fn string(s: &str) -> ::std::string::String {
+        ::std::borrow::ToOwned::to_owned(s)
+    }
+
+    #[test]
+    fn ownership() {
+        let (a, mut b, c, d, e, f) = (1, 2, 3, 4, Doc(5), Doc(6));
+        let (g, h, i) = (Box::new(7), Box::new(8), Arc::new(Doc(9)));
+        take!(&a, &mut b, c, mut d, =e as e2, =mut f, *g, *mut h, =*i as j);
+        *b += 1;
+        d += 1;
+        f.0 += 1;
+        h += 1;
+        assert_eq!((*a, c, d, g, h), (1, 3, 5, 7, 9));
+        assert_eq!((e2, f, j), (Doc(6), Doc(8), Doc(10)));
+
+        // `=` on a reference clones the value behind it
+        let (k, l) = (Doc(1), &Doc(2));
+        take!(ref k, =l, =k as [m, n]);
+        assert_eq!((k, l, m, n), (&Doc(1), Doc(3), Doc(2), Doc(2)));
+    }
+
+    #[test]
+    fn fallible() {
+        fn inner(x: Option<u32>, y: Result<u32>, z: Option<u32>) -> Option<u32> {
+            let w = ::std::option::Option::Some(4);
+            take!(x?, !y, z else return ::std::option::Option::Some(0), !w or "w" as v);
+            ::std::option::Option::Some(x + y + z + v)
+        }
+        let (some, none) = (::std::option::Option::Some, ::std::option::Option::None);
+        assert_eq!(inner(some(1), ::std::result::Result::Ok(2), some(3)), some(10));
+        assert_eq!(inner(some(1), ::std::result::Result::Ok(2), none), some(0));
+
+        let v = [1, 2, 3];
+        take!([first, .., last] = v);
+        assert_eq!((*first, *last), (1, 3));
+    }
+
+    #[test]
+    #[allow(clippy::drop_non_drop)]
+    fn wrappers() {
+        let (a, b, c, d) = (Doc(1), vec![1u8], string("c"), Arc::new(Doc(4)));
+        let (e, f, v) = (Doc(5), Doc(6), vec![2u8]);
+        assert_eq!(f.0, 6);
+        take!(hold a, must b, cow c, unwrap d, drop e, not f, shrink v as shrunk);
+        let _: Box<[u8]> = shrunk;
+        let _: ::taken::NotCaptured = f;
+        assert_eq!((a.0, b.into_inner(), c.len(), d), (1, vec![1], 1, Doc(4)));
+
+        let (g, h, x) = (Doc(7), Doc(8), Doc(9));
+        take!(send sync static g, unwind h, small[8] x);
+        assert_eq!((g, h.0, x), (Doc(7), Doc(8), Doc(9)));
+
+        let mut r = Doc(10);
+        {
+            take!(r <- Doc(0));
+            assert_eq!(r, Doc(10));
+        }
+        assert_eq!(r, Doc(0));
+
+        let fut = async { 1 };
+        take!(pin fut);
+        let fut: ::std::pin::Pin<&mut _> = fut;
+        ::std::mem::drop(fut);
+    }
+
+    #[test]
+    fn other_macros() {
+        let (a, b) = (Doc(1), ::std::option::Option::Some(2u32));
+        try_take!(b or 0);
+        conditional_take!(=a as c if b > 1 else Doc(0));
+        let boxed = Box::new(Doc(1));
+        take_deref_clone!(boxed as d);
+        own!(a);
+        assert_eq!((a, b, c, d), (Doc(1), 2, Doc(2), Doc(2)));
+
+        let (e, f) = (Arc::new(Doc(3)), Box::new(Doc(4)));
+        take_arc_clone!(e as e2);
+        unbox_clone!(f as g);
+        take_tuple!((=h, i) = (Doc(5), Doc(6)));
+        assert!(Arc::ptr_eq(&e, &e2));
+        assert_eq!((g, h, i), (Doc(5), Doc(6), Doc(6)));
+
+        let (none, err) = (::std::option::Option::None, ::std::result::Result::Err(()));
+        let (j, k, l): (Option<u32>, Result<u32>, Option<u32>) = (none, err, none);
+        try_take!(j or_else || 1, k or_else |()| 2);
+        take_or_default!(l);
+        take_iter_array!(vec![3, 4] => [m, n]);
+        assert_eq!((j, k, l, m, n), (1, 2, 0, 3, 4));
+
+        let (tx, rx) = ::std::sync::mpsc::channel();
+        send_value!(tx, Doc(7));
+        assert_eq!(rx.recv(), ::std::result::Result::Ok(Doc(7)));
+    }
+}
+
+/// `take!` used by another crate's macro, with the names coming from the caller.
+macro_rules! cloned_closure {
+    ($($name:ident),* => $body:expr) => {{
+        take!($(=$name),*);
+        move || $body
+    }};
+}
+
+/// The binding made by `take!` stays inside the macro that made it, like any other `let`.
+macro_rules! private_clone {
+    ($value:expr) => {{
+        let value = $value;
+        take!(=value as copy);
+        copy
+    }};
+}
+
+#[test]
+fn inside_other_macros() {
+    let (name, count) = (String::from("liam"), 2);
+    let closure = cloned_closure!(name, count => name.len() + count);
+    assert_eq!((closure(), name.as_str()), (6, "liam"));
+
+    let copy = "outer";
+    assert_eq!(private_clone!(vec![1]), vec![1]);
+    assert_eq!(copy, "outer");
+}
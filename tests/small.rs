@@ -0,0 +1,11 @@
+//! `small[N]` entries, whose size is only checked by a full build.
+
+extern crate trybuild;
+
+#[test]
+fn small() {
+    let t = trybuild::TestCases::new();
+    // a `pass` case makes trybuild run `cargo build` instead of `cargo check`
+    t.pass("tests/small/fits.rs");
+    t.compile_fail("tests/small/too_large.rs");
+}
@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let header = 7u64;
+    let fut = {
+        take!(small[64] header);
+        async move { header }
+    };
+    drop(fut);
+}
@@ -0,0 +1,8 @@
+#[macro_use]
+extern crate taken;
+
+fn main() {
+    let header = [0u8; 1024];
+    take!(small[64] header);
+    let _ = header;
+}
@@ -0,0 +1,23 @@
+//! With `strict-clone-semantics`, `=x` on an `Arc` or `Rc` is deprecated.
+#![cfg(feature = "strict-clone-semantics")]
+
+#[macro_use]
+extern crate taken;
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[test]
+#[deny(deprecated)]
+fn values_are_not_deprecated() {
+    let (a, b, c) = (vec![1], Arc::new(String::from("b")), Rc::new(3));
+    take!(=a, =*b, arc_clone c, =mut a as d);
+    d.push(2);
+    assert_eq!((a, b, *c, d), (vec![1], String::from("b"), 3, vec![1, 2]));
+}
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/strict_clone/*.rs");
+}
@@ -0,0 +1,13 @@
+#![deny(deprecated)]
+
+#[macro_use]
+extern crate taken;
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+fn main() {
+    let (a, b) = (Arc::new(1), Rc::new(2));
+    take!(=a, =b as c);
+    let _ = (a, c);
+}
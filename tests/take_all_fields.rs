@@ -0,0 +1,76 @@
+//! `take_all_fields!` taking the fields of a struct, each with its own sigil.
+#![cfg(feature = "macros")]
+
+extern crate trybuild;
+
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+
+use taken::take_all_fields;
+
+struct Config {
+    name: String,
+    tx: Sender<String>,
+    retries: u32,
+    tags: Vec<&'static str>,
+    limit: Box<usize>,
+}
+
+fn config() -> (Config, std::sync::mpsc::Receiver<String>) {
+    let (tx, rx) = channel();
+    let config = Config {
+        name: String::from("job"),
+        tx,
+        retries: 3,
+        tags: vec!["a"],
+        limit: Box::new(2),
+    };
+    (config, rx)
+}
+
+#[test]
+fn moves_clones_and_borrows() {
+    let (mut config, rx) = config();
+    let th = thread::spawn({
+        take_all_fields!(config => { name, =tx as sender, mut retries, *limit });
+        move || {
+            retries -= 1;
+            sender
+                .send(format!("{}{}{}", name, retries, limit))
+                .unwrap();
+        }
+    });
+    th.join().unwrap();
+    assert_eq!(rx.recv().unwrap(), "job22");
+
+    // the cloned and borrowed fields are still there
+    take_all_fields!(config => { &tx, &mut tags as all_tags, ref retries as r });
+    all_tags.push("b");
+    tx.send(r.to_string()).unwrap();
+    assert_eq!(rx.recv().unwrap(), "3");
+    assert_eq!(config.tags, vec!["a", "b"]);
+}
+
+#[test]
+fn references_and_expressions() {
+    let (config, _rx) = config();
+    let by_ref = &config;
+    take_all_fields!(by_ref => { &name, =tags, *limit as max, retries, });
+    let (name, tags, max, retries): (&String, Vec<&str>, usize, u32) = (name, tags, max, retries);
+    assert_eq!(
+        (name.as_str(), tags, max, retries),
+        ("job", vec!["a"], 2, 3)
+    );
+
+    // other expressions are evaluated once
+    let mut calls = 0;
+    take_all_fields!({ calls += 1; config } => { name as owned, tx });
+    drop(tx);
+    assert_eq!((owned.as_str(), calls), ("job", 1));
+}
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/take_all_fields/*.rs");
+}
@@ -0,0 +1,13 @@
+use taken::take_all_fields;
+
+struct Db;
+
+struct Ctx {
+    db: Db,
+    name: String,
+}
+
+fn main() {
+    let ctx = Ctx { db: Db, name: String::new() };
+    take_all_fields!(ctx => { name, =db });
+}
@@ -0,0 +1,11 @@
+use taken::take_all_fields;
+
+struct Ctx {
+    name: Option<String>,
+}
+
+fn main() {
+    let ctx = Ctx { name: None };
+    take_all_fields!(ctx => { name? });
+    take_all_fields!(ctx => { hold name });
+}
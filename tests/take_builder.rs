@@ -0,0 +1,125 @@
+//! `#[derive(TakeBuilder)]` dismantling a context struct into closures.
+#![cfg(feature = "macros")]
+
+extern crate trybuild;
+
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+
+use taken::{TakeBuilder, Untaken};
+
+#[derive(Debug, Default, PartialEq)]
+struct Config {
+    factor: u32,
+}
+
+#[derive(TakeBuilder)]
+struct Ctx {
+    name: String,
+    #[take(mode = "clone")]
+    tx: Sender<u32>,
+    #[take(mode = "borrow")]
+    config: Config,
+    counter: u32,
+}
+
+#[test]
+fn dismantle() {
+    let (tx, rx) = channel();
+    let mut ctx = Ctx {
+        name: String::from("worker"),
+        tx,
+        config: Config { factor: 3 },
+        counter: 1,
+    };
+    let CtxTaken {
+        name,
+        tx,
+        config,
+        counter,
+    } = ctx.take_builder_mut().take_name().tx().config().build();
+    let (name, config): (String, &Config) = (name, config);
+    *counter += 1;
+
+    thread::scope(|s| {
+        s.spawn(move || tx.send(config.factor * name.len() as u32).unwrap());
+    });
+    assert_eq!(rx.recv().unwrap(), 18);
+
+    // the name was taken, the rest is still there
+    assert_eq!(ctx.name, "");
+    assert_eq!(ctx.counter, 2);
+    ctx.tx.send(1).unwrap();
+    assert_eq!(rx.recv().unwrap(), 1);
+}
+
+#[test]
+fn override_modes() {
+    let mut ctx = Ctx {
+        name: String::from("name"),
+        tx: channel().0,
+        config: Config { factor: 2 },
+        counter: 7,
+    };
+    let taken = ctx
+        .take_builder_mut()
+        .clone_name()
+        .borrow_tx()
+        .take_config()
+        .counter()
+        .build();
+    let name: String = taken.name;
+    assert_eq!((name.as_str(), taken.counter), ("name", 7));
+    assert_eq!(taken.config, Config { factor: 2 });
+    assert_eq!((ctx.name.as_str(), ctx.counter), ("name", 0));
+    assert_eq!(ctx.config, Config::default());
+}
+
+struct NoDefault(u32);
+
+#[derive(TakeBuilder)]
+struct Owned {
+    value: NoDefault,
+    #[take(mode = "borrow")]
+    tx: Sender<u32>,
+    rest: Vec<u32>,
+}
+
+#[test]
+fn by_value() {
+    let (tx, rx) = channel();
+    let owned = Owned {
+        value: NoDefault(4),
+        tx,
+        rest: vec![1],
+    };
+    // moving doesn't need `Default`, and `tx()` moves in spite of its mode
+    let OwnedTaken { value, tx, rest } = owned.take_builder().take_value().tx().build();
+    let (value, tx, rest): (NoDefault, Sender<u32>, Untaken<Vec<u32>>) = (value, tx, rest);
+    thread::spawn(move || tx.send(value.0).unwrap());
+    assert_eq!(rx.recv().unwrap(), 4);
+    assert_eq!(rest.0, vec![1]);
+}
+
+#[derive(TakeBuilder)]
+pub struct Generic<'a, T: Clone> {
+    items: Vec<T>,
+    label: &'a str,
+}
+
+#[test]
+fn generic() {
+    let mut g = Generic {
+        items: vec![1, 2],
+        label: "g",
+    };
+    let GenericTaken { items, label } = g.take_builder_mut().clone_items().label().build();
+    assert_eq!((items, label), (vec![1, 2], "g"));
+    assert_eq!(g.label, "");
+}
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/take_builder/*.rs");
+}
@@ -0,0 +1,17 @@
+use taken::TakeBuilder;
+
+struct NoDefault;
+
+#[derive(TakeBuilder)]
+struct Ctx {
+    value: NoDefault,
+}
+
+fn main() {
+    let mut ctx = Ctx { value: NoDefault };
+    // borrowing works, taking through a `&mut` needs `Default`
+    ctx.take_builder_mut().borrow_value().build();
+    ctx.take_builder_mut().take_value().build();
+    // moving the struct doesn't
+    ctx.take_builder().take_value().build();
+}
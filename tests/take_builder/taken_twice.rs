@@ -0,0 +1,12 @@
+use taken::TakeBuilder;
+
+#[derive(TakeBuilder)]
+struct Ctx {
+    name: String,
+}
+
+fn main() {
+    let mut ctx = Ctx { name: String::new() };
+    ctx.take_builder_mut().take_name().clone_name().build();
+    ctx.take_builder().take_name().name().build();
+}
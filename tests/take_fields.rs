@@ -0,0 +1,53 @@
+//! `#[derive(TakeFields)]` peeling fields off a context struct.
+#![cfg(any(feature = "macros", feature = "derive"))]
+
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+
+use taken::TakeFields;
+
+/// Not `Default`, so it can only be moved out with `into_fields`.
+#[derive(Debug, PartialEq)]
+struct Db(&'static str);
+
+#[derive(TakeFields)]
+struct Ctx {
+    sender: Option<Sender<u32>>,
+    cache: Vec<u32>,
+    #[take(skip)]
+    db: Db,
+}
+
+fn ctx() -> (Ctx, std::sync::mpsc::Receiver<u32>) {
+    let (tx, rx) = channel();
+    let ctx = Ctx {
+        sender: Some(tx),
+        cache: vec![1, 2],
+        db: Db("main"),
+    };
+    (ctx, rx)
+}
+
+#[test]
+fn take_single_fields() {
+    let (mut ctx, rx) = ctx();
+    taken::take!(ctx.take_sender() as tx, ctx.take_cache() as cache);
+    let th = thread::spawn(move || {
+        for v in cache {
+            tx.as_ref().unwrap().send(v).unwrap();
+        }
+    });
+    th.join().unwrap();
+    assert_eq!(rx.iter().collect::<Vec<_>>(), vec![1, 2]);
+    // the rest is still there, the taken fields are defaults
+    assert!(ctx.sender.is_none() && ctx.cache.is_empty());
+    assert_eq!(ctx.db, Db("main"));
+}
+
+#[test]
+fn into_fields() {
+    let (ctx, _rx) = ctx();
+    let (sender, cache, db) = ctx.into_fields();
+    let _: Option<Sender<u32>> = sender;
+    assert_eq!((cache, db), (vec![1, 2], Db("main")));
+}
@@ -0,0 +1,62 @@
+//! `take_fn!` closures, whose bodies can't use anything but their captures.
+#![cfg(feature = "macros")]
+
+extern crate trybuild;
+
+use std::sync::mpsc::channel;
+
+use taken::take_fn;
+
+fn retry<F: FnMut(u32) -> bool>(mut f: F) -> u32 {
+    let mut attempt = 0;
+    while !f(attempt) {
+        attempt += 1;
+    }
+    attempt
+}
+
+#[test]
+fn forwards_calls() {
+    let job = vec![0, 0, 1];
+    let (tx, rx) = channel();
+    let attempts = retry(take_fn!((&job: Vec<u32>, =tx: std::sync::mpsc::Sender<u32>)
+        |attempt: u32| -> bool {
+            tx.send(attempt).unwrap();
+            job[attempt as usize] == 1
+        }
+    ));
+    assert_eq!(attempts, 2);
+    drop(tx);
+    assert_eq!(rx.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+}
+
+#[test]
+fn mutable_captures() {
+    let (mut log, count) = (Vec::new(), 10);
+    {
+        let mut push = take_fn!((&mut log: Vec<u32>, mut count: u32) |(a, b): (u32, u32)| {
+            *count += 1;
+            log.push(a + b + *count);
+        });
+        push((1, 2));
+        push((3, 4));
+    }
+    assert_eq!(log, vec![14, 19]);
+    assert_eq!(count, 10);
+}
+
+#[test]
+fn fn_trait() {
+    fn call_twice<F: Fn() -> usize>(f: F) -> usize {
+        f() + f()
+    }
+    let (name, other) = (String::from("abc"), 1);
+    let len = take_fn!((name as n: String, hold other: u32) || -> usize { n.len() });
+    assert_eq!(call_twice(len), 6);
+}
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/take_fn/*.rs");
+}
@@ -0,0 +1,7 @@
+use taken::take_fn;
+
+fn main() {
+    let (listed, unlisted) = (1u32, 2u32);
+    let f = take_fn!((listed: u32) |x: u32| -> u32 { listed + x + unlisted });
+    f(1);
+}
@@ -0,0 +1,7 @@
+use taken::take_fn;
+
+fn main() {
+    let x = 1u32;
+    let f = take_fn!((x) |y: u32| -> u32 { x + y });
+    f(1);
+}
@@ -0,0 +1,64 @@
+//! `#[taken(...)]` functions, which take their parameters at the start of the body.
+#![cfg(feature = "macros")]
+
+extern crate trybuild;
+
+use std::rc::Rc;
+
+use taken::taken;
+
+#[taken(clone(shared), freeze(limit))]
+fn clones_and_freezes(shared: &Rc<Vec<u32>>, limit: usize) -> (Rc<Vec<u32>>, usize) {
+    // `let` statements at the top already see the taken bindings
+    let first = shared.iter().take(limit).sum::<u32>() as usize;
+    let strong = Rc::strong_count(&shared);
+    (shared, first + strong)
+}
+
+#[taken]
+fn freeze_param(#[freeze] n: u32, step: u32) -> u32 {
+    let doubled = n * 2;
+    doubled + step
+}
+
+struct Counter(u32);
+
+impl Counter {
+    #[taken(clone(label))]
+    #[allow(clippy::needless_pass_by_value)]
+    fn describe<T>(&self, label: &T) -> String
+    where
+        T: ToString + Clone,
+    {
+        let label: T = label;
+        format!("{}: {}", label.to_string(), self.0)
+    }
+
+    #[taken]
+    unsafe fn add(&mut self, #[freeze] by: u32) {
+        self.0 += by;
+    }
+}
+
+#[test]
+fn takes_parameters() {
+    let shared = Rc::new(vec![1, 2, 3]);
+    let (cloned, sum) = clones_and_freezes(&shared, 2);
+    // the clone was returned, the original is still here
+    assert_eq!((Rc::strong_count(&shared), sum), (2, 3 + 2));
+    assert!(Rc::ptr_eq(&cloned, &shared));
+    assert_eq!(freeze_param(3, 1), 7);
+}
+
+#[test]
+fn methods() {
+    let mut counter = Counter(1);
+    unsafe { counter.add(2) };
+    assert_eq!(counter.describe(&"count"), "count: 3");
+}
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/taken_attr/*.rs");
+}
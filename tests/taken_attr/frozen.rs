@@ -0,0 +1,13 @@
+use taken::taken;
+
+#[taken(freeze(total))]
+fn sum(mut total: u32, items: &[u32]) -> u32 {
+    for item in items {
+        total += item;
+    }
+    total
+}
+
+fn main() {
+    sum(0, &[1, 2]);
+}
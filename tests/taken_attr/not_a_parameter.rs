@@ -0,0 +1,11 @@
+use taken::taken;
+
+#[taken(clone(config), freeze(count))]
+fn run(config: &String, mut conut: u32) -> u32 {
+    conut += config.len() as u32;
+    conut
+}
+
+fn main() {
+    run(&String::new(), 0);
+}
@@ -0,0 +1,39 @@
+//! With the `trace` feature every `take!` entry is printed to stderr. The test harness captures
+//! stderr in-process, so the test runs itself again as a child and reads the child's output.
+#![cfg(feature = "trace")]
+#[macro_use]
+extern crate taken;
+
+use std::env;
+use std::process::Command;
+
+const CHILD: &str = "TAKEN_TRACE_CHILD";
+
+#[test]
+fn trace() {
+    if env::var_os(CHILD).is_some() {
+        let data = vec![1u8, 2, 3];
+        let mut count = 0u64;
+        let name = String::from("liam");
+        take!(&data, &mut count as counter, =name as cloned);
+        *counter += data.len() as u64;
+        assert_eq!(cloned, name);
+        assert_eq!(count, 3);
+        return;
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["trace", "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("take!(& data): &alloc::vec::Vec<u8> ("), "{}", stderr);
+    assert!(stderr.contains("take!(&mut counter): &mut u64 ("), "{}", stderr);
+    let cloned = format!(
+        "take!(= cloned): alloc::string::String ({} bytes)",
+        std::mem::size_of::<String>()
+    );
+    assert!(stderr.contains(&cloned), "{}", stderr);
+}
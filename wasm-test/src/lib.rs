@@ -0,0 +1,38 @@
+//! Uses `js_closure!` from a crate that depends on `wasm-bindgen`, as the macro requires. Check it
+//! for the target it is meant for:
+//!
+//! ```text
+//! rustup target add wasm32-unknown-unknown
+//! cargo check -p taken-wasm-test --target wasm32-unknown-unknown
+//! ```
+
+#[macro_use]
+extern crate taken;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsValue;
+
+pub type Shared = Rc<RefCell<State>>;
+
+#[derive(Default)]
+pub struct State {
+    pub clicks: u32,
+    pub last: Option<String>,
+}
+
+pub fn on_click(label: String) -> (Shared, Closure<dyn FnMut(JsValue)>) {
+    let state = Rc::new(RefCell::new(State::default()));
+    let on_click = js_closure!((rc_clone state, label) |event: JsValue| {
+        let mut state = state.borrow_mut();
+        state.clicks += 1;
+        state.last = event.as_string().or_else(|| Some(label.clone()));
+    });
+    (state, on_click)
+}
+
+pub fn on_load(state: Shared) -> Closure<dyn FnMut() -> u32> {
+    js_closure!((state) once || -> u32 { state.borrow().clicks })
+}